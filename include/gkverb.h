@@ -0,0 +1,23 @@
+/* The C ABI of greek-writer, built with --features ffi. */
+
+#ifndef GKVERB_H
+#define GKVERB_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Conjugates a verb (a stem spec such as "pres:παυ", six comma-separated
+ * principal parts, or a lemma) in the comma-separated tvas, or every tva
+ * if tvas is NULL or "". Strings are UTF-8. Returns JSON: the paradigms,
+ * or {"error": "..."}. Free the result with gkverb_free. */
+char *gkverb_conjugate(const char *verb, const char *tvas);
+
+/* Frees a string returned by gkverb_conjugate. */
+void gkverb_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
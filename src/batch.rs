@@ -0,0 +1,131 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::io;
+
+// Batch input for --infile: a CSV file with a header row, each row naming
+// one verb by a stem spec ("pres:παυ"), all six principal parts in one
+// quoted field, or a lemma. An optional tva column lists the tvas wanted
+// for that row ("aai,ami" or "aor.act.ind,aor.mid.ind", or "all"); left
+// empty, --tva or --all applies.
+// Lines starting with # are comments.
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Row {
+    pub stem: String,
+    pub pp: String,
+    pub lemma: String,
+    pub tva: String,
+}
+
+impl Row {
+    // What names the row's verb, the same for every row naming it.
+    pub fn key(&self) -> (String, String, String) {
+        (self.stem.clone(), self.pp.clone(), self.lemma.clone())
+    }
+
+    pub fn tvas(&self) -> Vec<String> {
+        self.tva
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+// How many verbs a batch keeps built for rows that name them again.
+const MEMO: usize = 1024;
+
+// The verbs built for the rows read so far, so that one named again, as
+// lemmas are in a frequency list, is looked up and its stems derived once.
+// Only the MEMO most recently named are kept, so a long file of verbs
+// named once each is not held whole.
+pub struct Memo<V> {
+    built: HashMap<(String, String, String), (V, u64)>,
+    used: BTreeMap<u64, (String, String, String)>,
+    tick: u64,
+}
+
+impl<V> Memo<V> {
+    pub fn new() -> Self {
+        Memo {
+            built: HashMap::new(),
+            used: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    // The verb for the row, built by build unless it is kept.
+    pub fn get(&mut self, row: &Row, build: impl FnOnce() -> V) -> &V {
+        self.tick += 1;
+        let key = row.key();
+        match self.built.get_mut(&key) {
+            Some((_, used)) => {
+                self.used.remove(used);
+                *used = self.tick;
+            }
+            None => {
+                if self.built.len() == MEMO {
+                    if let Some((_, oldest)) = self.used.pop_first() {
+                        self.built.remove(&oldest);
+                    }
+                }
+                self.built.insert(key.clone(), (build(), self.tick));
+            }
+        }
+        self.used.insert(self.tick, key.clone());
+        &self.built[&key].0
+    }
+}
+
+// The rows as they are read, so that a long file need not be held whole.
+pub fn rows(path: &str) -> Result<impl Iterator<Item = Result<Row, csv::Error>>, Box<dyn Error>> {
+    let rows = csv::ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| format!("{}: {}", path, e))?
+        .into_deserialize();
+    Ok(rows)
+}
+
+// Forms for parse --infile, or lemmas for --lemmas: a column of a CSV file
+// (the first, unless one is named), or else one per line, - being stdin.
+// Blank lines and lines starting with # are skipped.
+pub fn forms(path: &str, column: Option<&str>) -> Result<Vec<String>, Box<dyn Error>> {
+    let text = if path == "-" {
+        io::read_to_string(io::stdin())?
+    } else {
+        fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?
+    };
+    let mut forms = Vec::new();
+    if path.ends_with(".csv") || column.is_some() {
+        let mut rdr = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes());
+        let i = match column {
+            Some(name) => rdr
+                .headers()?
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| format!("{}: no {} column", path, name))?,
+            None => 0,
+        };
+        for record in rdr.records() {
+            if let Some(form) = record?.get(i).filter(|f| !f.is_empty()) {
+                forms.push(form.to_string());
+            }
+        }
+    } else {
+        for line in text.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') {
+                forms.push(line.to_string());
+            }
+        }
+    }
+    Ok(forms)
+}
@@ -0,0 +1,211 @@
+use unicode_normalization::UnicodeNormalization;
+
+// Conversion between TLG Beta Code (pau/w, a)kou/w, *)aqh=nai) and
+// Unicode polytonic Greek. Letters are read case-insensitively; a capital
+// is marked with *, its diacritics written between the * and the letter.
+// Plain s is always read as medial σ, since stems and principal parts never
+// end in a final sigma; s2 and s3 give ς and ϲ.
+
+fn letter(c: char) -> Option<char> {
+    let l = match c.to_ascii_lowercase() {
+        'a' => 'α',
+        'b' => 'β',
+        'g' => 'γ',
+        'd' => 'δ',
+        'e' => 'ε',
+        'v' => 'ϝ',
+        'z' => 'ζ',
+        'h' => 'η',
+        'q' => 'θ',
+        'i' => 'ι',
+        'k' => 'κ',
+        'l' => 'λ',
+        'm' => 'μ',
+        'n' => 'ν',
+        'c' => 'ξ',
+        'o' => 'ο',
+        'p' => 'π',
+        'r' => 'ρ',
+        's' => 'σ',
+        't' => 'τ',
+        'u' => 'υ',
+        'f' => 'φ',
+        'x' => 'χ',
+        'y' => 'ψ',
+        'w' => 'ω',
+        _ => return None,
+    };
+    Some(l)
+}
+
+fn mark(c: char) -> Option<char> {
+    let m = match c {
+        ')' => '\u{0313}',
+        '(' => '\u{0314}',
+        '/' => '\u{0301}',
+        '\\' => '\u{0300}',
+        '=' => '\u{0342}',
+        '+' => '\u{0308}',
+        '|' => '\u{0345}',
+        _ => return None,
+    };
+    Some(m)
+}
+
+pub fn to_unicode(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '*' {
+            let mut marks = String::new();
+            while let Some(m) = chars.peek().and_then(|&c| mark(c)) {
+                marks.push(m);
+                chars.next();
+            }
+            if let Some(l) = chars.next().and_then(letter) {
+                out.extend(l.to_uppercase());
+                out.push_str(&marks);
+            }
+        } else if let Some(l) = letter(c) {
+            if l == 'σ' {
+                match chars.peek() {
+                    Some('1') => {
+                        chars.next();
+                    }
+                    Some('2') => {
+                        chars.next();
+                        out.push('ς');
+                        continue;
+                    }
+                    Some('3') => {
+                        chars.next();
+                        out.push('ϲ');
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            out.push(l);
+        } else if let Some(m) = mark(c) {
+            out.push(m);
+        } else {
+            out.push(c);
+        }
+    }
+    out.nfc().collect()
+}
+
+fn beta_letter(c: char) -> Option<char> {
+    let b = match c {
+        'α' => 'a',
+        'β' => 'b',
+        'γ' => 'g',
+        'δ' => 'd',
+        'ε' => 'e',
+        'ϝ' => 'v',
+        'ζ' => 'z',
+        'η' => 'h',
+        'θ' => 'q',
+        'ι' => 'i',
+        'κ' => 'k',
+        'λ' => 'l',
+        'μ' => 'm',
+        'ν' => 'n',
+        'ξ' => 'c',
+        'ο' => 'o',
+        'π' => 'p',
+        'ρ' => 'r',
+        'σ' | 'ς' => 's',
+        'τ' => 't',
+        'υ' => 'u',
+        'φ' => 'f',
+        'χ' => 'x',
+        'ψ' => 'y',
+        'ω' => 'w',
+        _ => return None,
+    };
+    Some(b)
+}
+
+fn beta_mark(c: char) -> Option<char> {
+    let b = match c {
+        '\u{0313}' => ')',
+        '\u{0314}' => '(',
+        '\u{0301}' => '/',
+        '\u{0300}' => '\\',
+        '\u{0342}' => '=',
+        '\u{0308}' => '+',
+        '\u{0345}' => '|',
+        _ => return None,
+    };
+    Some(b)
+}
+
+// Lower-case Beta Code, with final sigma written as plain s. A capital's
+// breathing and accent go between the * and the letter. Anything that is
+// not Greek, such as a romanized column, is passed through recomposed.
+pub fn from_unicode(s: &str) -> String {
+    let mut out = String::new();
+    let decomposed: Vec<char> = s.nfd().collect();
+    let mut i = 0;
+    while i < decomposed.len() {
+        let c = decomposed[i];
+        let mut j = i + 1;
+        let mut marks = String::new();
+        while let Some(m) = decomposed.get(j).and_then(|&m| beta_mark(m)) {
+            marks.push(m);
+            j += 1;
+        }
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        match beta_letter(lower) {
+            Some(b) if lower != c => {
+                out.push('*');
+                out.push_str(&marks.replace('|', ""));
+                out.push(b);
+                if marks.contains('|') {
+                    out.push('|');
+                }
+            }
+            Some(b) => {
+                out.push(b);
+                out.push_str(&marks);
+            }
+            None => {
+                out.push(c);
+                out.push_str(&marks);
+            }
+        }
+        i = j;
+    }
+    out.nfc().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_beta_code_as_polytonic_greek() {
+        assert_eq!(to_unicode("pau/w"), "παύω");
+        assert_eq!(to_unicode("a)kou/w"), "ἀκούω");
+        assert_eq!(to_unicode("A)KOU/W"), "ἀκούω");
+        assert_eq!(to_unicode("*)aqh=nai"), "Ἀθῆναι");
+        assert_eq!(to_unicode("lu/sh|"), "λύσῃ");
+        assert_eq!(to_unicode("proi+/sthmi"), "προΐστημι");
+        // Plain s is medial even at the end; s2 asks for a final sigma.
+        assert_eq!(to_unicode("lo/gos"), "λόγοσ");
+        assert_eq!(to_unicode("lo/gos2"), "λόγος");
+    }
+
+    #[test]
+    fn writes_greek_as_lower_case_beta_code() {
+        assert_eq!(from_unicode("παύω"), "pau/w");
+        assert_eq!(from_unicode("ἀκούεις"), "a)kou/eis");
+        assert_eq!(from_unicode("Ἀθῆναι"), "*)aqh=nai");
+        assert_eq!(from_unicode("ᾯ"), "*(=w|");
+        assert_eq!(from_unicode("λύσῃ (lysē)"), "lu/sh| (lysē)");
+        for word in ["ἐπαύθησαν", "Ἀθῆναι", "ᾯ", "προΐστημι"] {
+            assert_eq!(to_unicode(&from_unicode(word)), word.replace('ς', "σ"));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{derive, Class, EndingSet, GkVerbError, Nu, Profile, Stem, Style, Verb};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
+
+/// Builds a [`Verb`] from its stems, one stem spec per tense system, and
+/// its options. A later stem of a system replaces an earlier one; with no
+/// stems at all, those of a regular verb are derived from the lemma. The
+/// first stem spec that cannot be read is the error of [`build`].
+///
+/// [`build`]: VerbBuilder::build
+///
+/// ```
+/// use greek_writer::{Class, Nu, Verb};
+///
+/// let vb = Verb::builder()
+///     .lemma("παύω")
+///     .stem("pres:παυ")
+///     .stem("aor:ἐπαυσ")
+///     .class(Class::Regular)
+///     .nu(Nu::Both)
+///     .build()
+///     .unwrap();
+/// assert_eq!(vb.stems.len(), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct VerbBuilder {
+    lemma: String,
+    gloss: String,
+    stems: Vec<Stem>,
+    error: Option<GkVerbError>,
+    class: Class,
+    deponent: bool,
+    passive_deponent: bool,
+    overrides: BTreeMap<String, String>,
+    nu: Option<Nu>,
+    style: Option<Style>,
+    endings: Option<Arc<dyn EndingSet>>,
+}
+
+impl VerbBuilder {
+    pub fn lemma(mut self, lemma: &str) -> Self {
+        self.lemma = lemma.trim().nfc().collect();
+        self
+    }
+
+    pub fn gloss(mut self, gloss: &str) -> Self {
+        self.gloss = gloss.to_string();
+        self
+    }
+
+    /// A stem spec such as aor2:ἐλαβ.
+    pub fn stem(mut self, spec: &str) -> Self {
+        match Verb::parse_stem(spec) {
+            Ok(stem) => {
+                self.stems.retain(|s| s.system() != stem.system());
+                self.stems.push(stem);
+            }
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+        self
+    }
+
+    pub fn class(mut self, class: Class) -> Self {
+        self.class = class;
+        self
+    }
+
+    pub fn deponent(mut self, deponent: bool) -> Self {
+        self.deponent = deponent;
+        self
+    }
+
+    /// A deponent whose aorist is passive in form, as βούλομαι's.
+    pub fn passive_deponent(mut self, passive_deponent: bool) -> Self {
+        self.passive_deponent = passive_deponent;
+        self
+    }
+
+    /// An irregular form, keyed like "aai.3sg".
+    pub fn override_form(mut self, key: &str, form: &str) -> Self {
+        self.overrides.insert(key.to_string(), form.to_string());
+        self
+    }
+
+    pub fn nu(mut self, nu: Nu) -> Self {
+        self.nu = Some(nu);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Another set of endings than the Attic ones.
+    pub fn endings(mut self, endings: Arc<dyn EndingSet>) -> Self {
+        self.endings = Some(endings);
+        self
+    }
+
+    /// A textbook's endings, as --profile takes them; a later nu or style
+    /// replaces the profile's.
+    pub fn profile(self, profile: Profile) -> Self {
+        self.nu(profile.nu).style(profile.style)
+    }
+
+    pub fn build(self) -> Result<Verb, GkVerbError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let mut stems = self.stems;
+        if stems.is_empty() {
+            if self.lemma.is_empty() {
+                return Err(GkVerbError::Empty);
+            }
+            stems = derive::stems_from_lemma(&self.lemma)?;
+        }
+        let mut vb = Verb::with_stems(stems);
+        vb.lemma = self.lemma;
+        vb.gloss = self.gloss;
+        vb.class = self.class;
+        vb.deponent = self.deponent;
+        vb.passive_deponent = self.passive_deponent;
+        vb.overrides = self.overrides;
+        if let Some(nu) = self.nu {
+            vb.nu = nu;
+        }
+        if let Some(style) = self.style {
+            vb.style = style;
+        }
+        if let Some(endings) = self.endings {
+            vb.endings = endings;
+        }
+        Ok(vb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The stems of a verb, as their specs.
+    fn specs(vb: &Verb) -> Vec<String> {
+        vb.stems
+            .iter()
+            .map(|stem| format!("{}:{}", stem.system(), stem))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_last_stem_of_each_system() {
+        let vb = Verb::builder()
+            .stem("pres:παυ")
+            .stem("aor:ἐπαυσ")
+            .stem("pres:λυ")
+            .build()
+            .unwrap();
+        assert_eq!(specs(&vb), ["aor:ἐπαυσ", "pres:λυ"]);
+    }
+
+    #[test]
+    fn fails_with_the_first_bad_stem_spec() {
+        let e = Verb::builder()
+            .stem("pres:παυ")
+            .stem("xyz:παυ")
+            .stem("abc:παυ")
+            .build()
+            .unwrap_err();
+        assert_eq!(e, GkVerbError::UnknownSystem("xyz:παυ".to_string()));
+        assert_eq!(Verb::builder().build().unwrap_err(), GkVerbError::Empty);
+    }
+
+    #[test]
+    fn derives_a_regular_verbs_stems_from_its_lemma() {
+        let vb = Verb::builder().lemma(" παύω ").build().unwrap();
+        assert_eq!(vb.lemma, "παύω");
+        assert!(specs(&vb).contains(&"pres:παυ".to_string()));
+        assert!(specs(&vb).contains(&"aor:ἐπαυσ".to_string()));
+    }
+
+    #[test]
+    fn lets_a_later_option_replace_the_profiles() {
+        let athenaze = Profile::from_arg("athenaze").unwrap();
+        let vb = Verb::builder()
+            .stem("pres:παυ")
+            .profile(athenaze)
+            .nu(Nu::Omit)
+            .override_form("pai.1sg", "παύω")
+            .build()
+            .unwrap();
+        assert_eq!(vb.nu, Nu::Omit);
+        assert_eq!(vb.style, Style::Ei);
+        assert_eq!(vb.overrides["pai.1sg"], "παύω");
+    }
+}
@@ -0,0 +1,147 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+// Defaults read from ~/.config/gkverb/config.toml (or the file given with
+// --config), so that a teacher's usual flags need not be typed every run:
+//
+//     profile = "athenaze"
+//     nu = "rows"
+//     delimiter = ";"
+//
+// Anything given on the command line wins over the file.
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub profile: Option<String>,
+    pub nu: Option<String>,
+    pub style: Option<String>,
+    pub input_encoding: Option<String>,
+    pub output_encoding: Option<String>,
+    pub normalize: Option<String>,
+    pub transliterate: Option<String>,
+    pub format: Option<String>,
+    pub layout: Option<String>,
+    pub csv_format: Option<String>,
+    pub lexicon: Option<String>,
+    pub endings: Option<String>,
+    pub delimiter: Option<char>,
+    pub header: Option<bool>,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+// Every setting the file may give.
+const SETTINGS: [&str; 14] = [
+    "profile",
+    "nu",
+    "style",
+    "input_encoding",
+    "output_encoding",
+    "normalize",
+    "transliterate",
+    "format",
+    "layout",
+    "csv_format",
+    "lexicon",
+    "endings",
+    "delimiter",
+    "header",
+];
+
+// The values each setting may take, as for the matching command-line option.
+const CHOICES: [(&str, &[&str]); 10] = [
+    ("profile", &greek_writer::PROFILES),
+    ("nu", &greek_writer::NUS),
+    ("style", &greek_writer::STYLES),
+    ("input_encoding", &["unicode", "betacode"]),
+    ("output_encoding", &["unicode", "betacode"]),
+    ("normalize", &["nfc", "nfd"]),
+    ("transliterate", &greek_writer::translit::SCHEMES),
+    ("format", &greek_writer::output::FORMATS),
+    ("layout", &greek_writer::output::LAYOUTS),
+    ("csv_format", &["wide", "long"]),
+];
+
+impl Config {
+    fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "profile" => self.profile.as_deref(),
+            "nu" => self.nu.as_deref(),
+            "style" => self.style.as_deref(),
+            "input_encoding" => self.input_encoding.as_deref(),
+            "output_encoding" => self.output_encoding.as_deref(),
+            "normalize" => self.normalize.as_deref(),
+            "transliterate" => self.transliterate.as_deref(),
+            "format" => self.format.as_deref(),
+            "layout" => self.layout.as_deref(),
+            "csv_format" => self.csv_format.as_deref(),
+            "lexicon" => self.lexicon.as_deref(),
+            "endings" => self.endings.as_deref(),
+            _ => None,
+        }
+    }
+
+    // The setting for a command-line option, e.g. "input-encoding".
+    pub fn value_of(&self, arg: &str) -> Option<&str> {
+        self.get(&arg.replace('-', "_"))
+    }
+
+    fn check(&self, path: &str) -> Result<(), String> {
+        for (key, choices) in CHOICES.iter() {
+            if let Some(value) = self.get(key) {
+                if !choices.contains(&value) {
+                    return Err(format!(
+                        "{}: {} must be one of {}, not {}",
+                        path,
+                        key,
+                        choices.join(", "),
+                        value
+                    ));
+                }
+            }
+        }
+        if let Some(delimiter) = self.delimiter {
+            if !delimiter.is_ascii() {
+                return Err(format!("{}: delimiter must be an ASCII character", path));
+            }
+        }
+        if let Some(key) = self.unknown.keys().next() {
+            return Err(format!(
+                "{}: unknown setting {}; expected one of {}",
+                path,
+                key,
+                SETTINGS.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn default_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("gkverb").join("config.toml"))
+}
+
+// Reads the given config file, which must exist, or else the default one
+// if there is one.
+pub fn load(path: Option<&str>) -> Result<Config, Box<dyn Error>> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => match default_path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        },
+    };
+    let shown = path.display().to_string();
+    let data = fs::read_to_string(&path).map_err(|e| format!("{}: {}", shown, e))?;
+    let config: Config = toml::from_str(&data).map_err(|e| format!("{}: {}", shown, e))?;
+    config.check(&shown)?;
+    Ok(config)
+}
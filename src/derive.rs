@@ -0,0 +1,606 @@
+use crate::{strip_accents, Class, GkVerbError, Stem, Tense, Tva, Verb, Voice};
+use unicode_normalization::UnicodeNormalization;
+
+// Regular stem derivation from a dictionary form: the sigmatic future and
+// first aorist, the θη aorist passive and the perfect, κ after a vowel or
+// dental and aspirated after a labial or guttural, with the usual sandhi
+// of the stem-final consonant. A stem ending in a short vowel lengthens it
+// before the suffix: τιμα -> τιμησ, ποιε -> ποιησ, δηλο -> δηλωσ. A verb
+// in -ίζω of more than two syllables has the Attic future instead,
+// contracted like a liquid verb's: νομίζω, νομιῶ, νομιεῖς. Verbs in -άζω
+// keep the σ (ἀγοράσω); the few with an Attic future in -ῶ, -ᾷς, such as
+// βιβάζω, can be given it as a stem, fut:βιβα.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Final {
+    Vowel,
+    Labial,
+    Guttural,
+    Dental,
+    Liquid,
+}
+
+// Splits a present stem into what survives before a tense suffix and the
+// class of the sound it ended in, e.g. κοπτ -> (κο, Labial).
+fn classify(stem: &str) -> (&str, Final) {
+    for (cluster, class) in [
+        ("πτ", Final::Labial),
+        ("σσ", Final::Guttural),
+        ("ττ", Final::Guttural),
+    ]
+    .iter()
+    {
+        if let Some(base) = stem.strip_suffix(cluster) {
+            return (base, *class);
+        }
+    }
+    let last = match stem.chars().last() {
+        Some(c) => c,
+        None => return (stem, Final::Vowel),
+    };
+    let base = &stem[..stem.len() - last.len_utf8()];
+    match last {
+        'π' | 'β' | 'φ' => (base, Final::Labial),
+        'κ' | 'γ' | 'χ' => (base, Final::Guttural),
+        'τ' | 'δ' | 'θ' | 'ζ' => (base, Final::Dental),
+        'λ' | 'μ' | 'ν' | 'ρ' => (base, Final::Liquid),
+        _ => (stem, Final::Vowel),
+    }
+}
+
+// Stems whose short vowel stays short before a tense suffix: τελέω,
+// τελέσω; γελάω, γελάσομαι.
+const SHORT: [&str; 9] = [
+    "τελε",
+    "καλε",
+    "ἀρκε",
+    "ἐμε",
+    "ζε",
+    "ξε",
+    "γελα",
+    "σπα",
+    "κλα",
+];
+
+// A vowel stem with its final short vowel lengthened, as before the σ of
+// the future or the κ of the perfect: α -> η, but α after ε, ι or ρ, long
+// already, stays (ἐάω, ἐάσω; δράω, δράσω); ε -> η; ο -> ω.
+fn lengthen(stem: &str) -> String {
+    if SHORT.contains(&stem) {
+        return stem.to_string();
+    }
+    let mut chars = stem.chars();
+    let long = match chars.next_back() {
+        Some('α') => match chars.clone().next_back().map(bare) {
+            Some('ε' | 'ι' | 'ρ') => 'α',
+            _ => 'η',
+        },
+        Some('ε') => 'η',
+        Some('ο') => 'ω',
+        _ => return stem.to_string(),
+    };
+    format!("{}{}", chars.as_str(), long)
+}
+
+// The letter without breathings, accents or iota subscript.
+fn bare(c: char) -> char {
+    c.nfd().next().unwrap_or(c)
+}
+
+pub fn is_vowel(c: char) -> bool {
+    matches!(bare(c), 'α' | 'ε' | 'η' | 'ι' | 'ο' | 'υ' | 'ω')
+}
+
+fn augment(stem: &str) -> String {
+    let (aug, rest) = Verb::aug_and_stem(stem);
+    format!("{}{}", aug, rest)
+}
+
+// Perfect reduplication: the first consonant (deaspirated) plus ε before a
+// single consonant or stop + liquid; ἐ- before ρ, double consonants and other
+// clusters; the temporal augment before a vowel.
+fn reduplicate(stem: &str) -> String {
+    let mut chars = stem.chars();
+    let first = match chars.next() {
+        Some(c) => c,
+        None => return stem.to_string(),
+    };
+    if is_vowel(first) {
+        return augment(stem);
+    }
+    let second = chars.next().map(bare).unwrap_or('α');
+    let stop = matches!(first, 'π' | 'β' | 'φ' | 'κ' | 'γ' | 'χ' | 'τ' | 'δ' | 'θ');
+    let liquid = matches!(second, 'λ' | 'μ' | 'ν' | 'ρ');
+    if matches!(bare(first), 'ρ' | 'ζ' | 'ξ' | 'ψ') || !(is_vowel(second) || stop && liquid) {
+        return format!("ἐ{}", stem);
+    }
+    let redup = match first {
+        'φ' => 'π',
+        'θ' => 'τ',
+        'χ' => 'κ',
+        c => c,
+    };
+    format!("{}ε{}", redup, stem)
+}
+
+pub fn future(stem: &str) -> Result<String, GkVerbError> {
+    let (base, class) = classify(stem);
+    match class {
+        Final::Vowel => Ok(format!("{}σ", lengthen(stem))),
+        Final::Labial => Ok(format!("{}ψ", base)),
+        Final::Guttural => Ok(format!("{}ξ", base)),
+        Final::Dental => Ok(format!("{}σ", base)),
+        Final::Liquid => Err(GkVerbError::Liquid {
+            stem: stem.to_string(),
+            system: "fut",
+        }),
+    }
+}
+
+// The ε-contract future stem of a verb in -ίζω of more than two
+// syllables, e.g. νομιζ -> νομιε; None for one of two (κτίζω, κτίσω) or
+// whose ι ends a diphthong (ἀθροίζω, ἀθροίσω). A verb in -άζω is left
+// to the sigmatic future: most have one (σκευάζω, σκευάσω), and the few
+// Attic ones (βιβάζω, βιβῶ) are better typed as stems or in the lexicon.
+pub fn attic_future(stem: &str) -> Option<String> {
+    let base = stem.strip_suffix("ιζ")?;
+    if base.chars().last().is_some_and(is_vowel) || !base.chars().any(is_vowel) {
+        return None;
+    }
+    Some(format!("{}ιε", base))
+}
+
+// The ending with a contract stem's final vowel contracted into it: ε
+// with ομεν gives ουμεν, α with εις ᾳς, ο with ει οι. A 3rd sg. -ε(ν)
+// loses its movable nu (ἐποίει), and an ending that starts with no vowel
+// takes the stem's vowel as it is.
+pub fn contract(vowel: char, ending: &str) -> String {
+    let rules: &[(&str, &str)] = match vowel {
+        'ε' => &[
+            ("ε(ν)", "ει"),
+            ("ει", "ει"),
+            ("ου", "ου"),
+            ("ε", "ει"),
+            ("ο", "ου"),
+            ("ω", "ω"),
+            ("η", "η"),
+            ("ῃ", "ῃ"),
+        ],
+        'α' => &[
+            ("ε(ν)", "α"),
+            ("ει", "ᾳ"),
+            ("ου", "ω"),
+            ("ε", "α"),
+            ("ο", "ω"),
+            ("ω", "ω"),
+            ("η", "α"),
+            ("ῃ", "ᾳ"),
+        ],
+        'ο' => &[
+            ("ε(ν)", "ου"),
+            ("ει", "οι"),
+            ("ου", "ου"),
+            ("ε", "ου"),
+            ("ο", "ου"),
+            ("ω", "ω"),
+            ("η", "ω"),
+            ("ῃ", "οι"),
+        ],
+        _ => &[],
+    };
+    for (start, contracted) in rules {
+        if let Some(rest) = ending.strip_prefix(start) {
+            return format!("{}{}", contracted, rest);
+        }
+    }
+    format!("{}{}", vowel, ending)
+}
+
+pub fn aorist(stem: &str) -> Result<String, GkVerbError> {
+    future(stem).map(|fut| augment(&fut))
+}
+
+// The augmented stem that -θην is added to.
+pub fn aorist_passive(stem: &str) -> Result<String, GkVerbError> {
+    let (base, class) = classify(stem);
+    let unaugmented = match class {
+        Final::Vowel => lengthen(stem),
+        Final::Labial => format!("{}φ", base),
+        Final::Guttural => format!("{}χ", base),
+        Final::Dental => format!("{}σ", base),
+        Final::Liquid => {
+            return Err(GkVerbError::Liquid {
+                stem: stem.to_string(),
+                system: "aorpass",
+            })
+        }
+    };
+    Ok(augment(&unaugmented))
+}
+
+// The reduplicated perfect stem that -α is added to: κ after a vowel, or
+// in place of a dental (πεπεικ); a labial or guttural is aspirated
+// instead (κεκοφ, πεπραχ).
+pub fn perfect(stem: &str) -> Result<String, GkVerbError> {
+    let (base, class) = classify(stem);
+    match class {
+        Final::Vowel => Ok(format!("{}κ", reduplicate(&lengthen(stem)))),
+        Final::Dental => Ok(format!("{}κ", reduplicate(base))),
+        Final::Labial => Ok(format!("{}φ", reduplicate(base))),
+        Final::Guttural => Ok(format!("{}χ", reduplicate(base))),
+        Final::Liquid => Err(GkVerbError::Liquid {
+            stem: stem.to_string(),
+            system: "perf",
+        }),
+    }
+}
+
+// The perfect middle stem, for vowel stems; consonant stems assimilate to
+// each ending and cannot be written as one stem.
+pub fn perfect_middle(stem: &str) -> Option<String> {
+    match classify(stem) {
+        (_, Final::Vowel) => Some(reduplicate(&lengthen(stem))),
+        _ => None,
+    }
+}
+
+/// The present or imperfect endings of an athematic (-μι) verb whose
+/// short stem ends in the vowel, the vowel included: the active singular
+/// lengthens it, ο to ω, ε and α to η (διδωμι, τιθης, ἱστησι(ν)), though
+/// the imperfect's contracts ο and ε with the ending (ἐδιδουν, ἐτιθεις);
+/// elsewhere it stays short (διδομεν, τιθεμαι). None for a vowel that
+/// ends no -μι stem or a tva outside the present system.
+pub fn athematic(vowel: char, tva: Tva) -> Option<[String; 6]> {
+    let long = match vowel {
+        'ο' => 'ω',
+        'ε' | 'α' => 'η',
+        'υ' => 'υ',
+        _ => return None,
+    };
+    let short = |ending: &str| format!("{}{}", vowel, ending);
+    let endings = match (tva.tense, tva.voice) {
+        (Tense::Present, Voice::Active) => {
+            let [first, second, third] = ["μι", "ς", "σι(ν)"].map(|e| format!("{}{}", long, e));
+            let plural = match vowel {
+                'α' => "ασι(ν)".to_string(),
+                _ => short("ασι(ν)"),
+            };
+            [first, second, third, short("μεν"), short("τε"), plural]
+        }
+        (Tense::Imperfect, Voice::Active) => {
+            let sg = match vowel {
+                'ο' => ["ουν", "ους", "ου"],
+                'ε' => ["ην", "εις", "ει"],
+                'α' => ["ην", "ης", "η"],
+                _ => ["υν", "υς", "υ"],
+            };
+            let [first, second, third] = sg.map(String::from);
+            [
+                first,
+                second,
+                third,
+                short("μεν"),
+                short("τε"),
+                short("σαν"),
+            ]
+        }
+        (Tense::Present, _) => ["μαι", "σαι", "ται", "μεθα", "σθε", "νται"].map(short),
+        (Tense::Imperfect, _) => ["μην", "σο", "το", "μεθα", "σθε", "ντο"].map(short),
+        _ => return None,
+    };
+    Some(endings)
+}
+
+/// A perfect middle form of a stem ending in a consonant, which
+/// assimilates to the ending's first sound: γεγραμ + σαι -> γεγραψαι,
+/// πεπραγ + ται -> πεπρακται, πεπεισ + σθε -> πεπεισθε, ἠγγελ + σθε ->
+/// ἠγγελθε. The stem may end as the principal part leaves it (μ of
+/// -μμαι, γ, σ) or in the consonant of the present. None for a vowel
+/// stem, a consonant with no rule (ν), or an ending that does not begin
+/// with μ, σ or τ; a 3pl is periphrastic and not made here.
+pub fn assimilate(stem: &str, ending: &str) -> Option<String> {
+    let last = stem.chars().last()?;
+    let base = &stem[..stem.len() - last.len_utf8()];
+    let class = match last {
+        'μ' | 'π' | 'β' | 'φ' => Final::Labial,
+        'γ' | 'κ' | 'χ' => Final::Guttural,
+        'σ' | 'τ' | 'δ' | 'θ' | 'ζ' => Final::Dental,
+        'λ' | 'ρ' => Final::Liquid,
+        _ => return None,
+    };
+    let liquid = last.to_string();
+    let (joined, rest) = if let Some(rest) = ending.strip_prefix("σθ") {
+        match class {
+            Final::Labial => ("φθ", rest),
+            Final::Guttural => ("χθ", rest),
+            Final::Dental => ("σθ", rest),
+            _ => (liquid.as_str(), &ending['σ'.len_utf8()..]),
+        }
+    } else if let Some(rest) = ending.strip_prefix('σ') {
+        match class {
+            Final::Labial => ("ψ", rest),
+            Final::Guttural => ("ξ", rest),
+            Final::Dental => ("σ", rest),
+            _ => (liquid.as_str(), ending),
+        }
+    } else if ending.starts_with('μ') || ending.starts_with('τ') {
+        let first = ending.starts_with('μ');
+        match class {
+            Final::Labial if first => ("μ", ending),
+            Final::Labial => ("π", ending),
+            Final::Guttural if first => ("γ", ending),
+            Final::Guttural => ("κ", ending),
+            Final::Dental => ("σ", ending),
+            _ => (liquid.as_str(), ending),
+        }
+    } else {
+        return None;
+    };
+    Some(format!("{}{}{}", base, joined, rest))
+}
+
+// Contracted endings a lemma may be given in, and the uncontracted ones
+// each may stand for.
+const CONTRACTED: [(&str, &[&str]); 3] = [
+    ("ῶμαι", &["άομαι"]),
+    ("οῦμαι", &["έομαι", "όομαι"]),
+    ("ῶ", &["άω", "έω", "όω"]),
+];
+
+/// The lemma uncontracted, as its class and stems are told from it: a
+/// contracted -ῶμαι can only be -άομαι (χρῶμαι, χράομαι), but -ῶ and
+/// -οῦμαι could stand for more than one class, and the lemmas they may be
+/// are given back to choose from. Any other lemma is returned as it is.
+pub fn uncontracted(lemma: &str) -> Result<String, GkVerbError> {
+    let lemma: String = lemma.trim().nfc().collect();
+    for (ending, endings) in CONTRACTED.iter() {
+        if let Some(base) = lemma.strip_suffix(ending) {
+            if base.is_empty() {
+                return Err(GkVerbError::EndingOnly(lemma));
+            }
+            let lemmas: Vec<String> = endings.iter().map(|e| [base, e].concat()).collect();
+            return match lemmas.as_slice() {
+                [one] => Ok(one.clone()),
+                _ => Err(GkVerbError::Ambiguous { lemma, lemmas }),
+            };
+        }
+    }
+    Ok(lemma)
+}
+
+/// The class a lemma's ending shows: -άω, -έω and -όω (or -ομαι) are
+/// contract verbs, -μι (or -αμαι, δύναμαι) athematic; anything else is
+/// taken as regular. An -ίζω verb is regular, its Attic future found when
+/// its stems are derived.
+pub fn class_of(lemma: &str) -> Class {
+    let lemma = strip_accents(lemma.trim());
+    let pres = lemma
+        .strip_suffix("ομαι")
+        .or_else(|| lemma.strip_suffix('ω'));
+    match pres.and_then(|pres| pres.chars().last()) {
+        Some('α' | 'ε' | 'ο') => Class::Contract,
+        _ if lemma.ends_with("μι") || lemma.ends_with("αμαι") => Class::Athematic,
+        _ => Class::Regular,
+    }
+}
+
+/// Whether a lemma is middle in form, and so a deponent's: -ομαι, or -μαι
+/// for a -μι verb (δύναμαι).
+pub fn is_deponent(lemma: &str) -> bool {
+    strip_accents(lemma.trim()).ends_with("μαι")
+}
+
+/// The tense systems [`derived`] can give a present stem, as --derive
+/// takes them.
+pub const DERIVABLE: [&str; 3] = ["fut", "aor", "perf"];
+
+// The future stem a lemma's present gives: the Attic one if it is in
+// -ίζω and has one, else the sigmatic.
+fn future_of(pres: &str) -> Result<String, GkVerbError> {
+    match attic_future(pres) {
+        Some(fut) => Ok(fut),
+        None => future(pres),
+    }
+}
+
+/// The stems of the given systems, each one of [`DERIVABLE`], derived
+/// from a present stem as a regular verb's are, e.g. fut:παυσ, aor:ἐπαυσ
+/// and perf:πεπαυκ from παυ.
+pub fn derived(pres: &str, systems: &[&str]) -> Result<Vec<Stem>, GkVerbError> {
+    let mut stems = Vec::new();
+    for system in systems {
+        stems.push(match *system {
+            "fut" => Stem::Fut(future_of(pres)?),
+            "aor" => Stem::Aor(aorist(pres)?),
+            "perf" => Stem::Perf(perfect(pres)?),
+            _ => return Err(GkVerbError::Underivable(system.to_string())),
+        });
+    }
+    Ok(stems)
+}
+
+// Cuts -ω or -ομαι off a dictionary form and derives the other stems.
+pub fn stems_from_lemma(lemma: &str) -> Result<Vec<Stem>, GkVerbError> {
+    if class_of(lemma) == Class::Athematic {
+        return Err(GkVerbError::Athematic(lemma.trim().to_string()));
+    }
+    let lemma = strip_accents(&uncontracted(lemma)?);
+    let pres = lemma
+        .strip_suffix("ομαι")
+        .or_else(|| lemma.strip_suffix('ω'))
+        .ok_or_else(|| GkVerbError::NotALemma(lemma.clone()))?;
+    if pres.is_empty() {
+        return Err(GkVerbError::EndingOnly(lemma));
+    }
+    let mut stems = vec![Stem::Pres(pres.to_string())];
+    stems.extend(derived(pres, &DERIVABLE)?);
+    if let Some(perfmid) = perfect_middle(pres) {
+        stems.push(Stem::PerfMid(perfmid));
+    }
+    stems.push(Stem::AorPass(aorist_passive(pres)?));
+    Ok(stems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assimilates_perfect_middle_consonants() {
+        let forms: Vec<_> = ["μαι", "σαι", "ται", "μεθα", "σθε"]
+            .iter()
+            .map(|ending| assimilate("γεγραμ", ending).unwrap())
+            .collect();
+        assert_eq!(
+            forms,
+            [
+                "γεγραμμαι",
+                "γεγραψαι",
+                "γεγραπται",
+                "γεγραμμεθα",
+                "γεγραφθε"
+            ]
+        );
+        assert_eq!(assimilate("πεπραγ", "σαι").as_deref(), Some("πεπραξαι"));
+        assert_eq!(assimilate("πεπραγ", "σθε").as_deref(), Some("πεπραχθε"));
+        assert_eq!(assimilate("πεπεισ", "σαι").as_deref(), Some("πεπεισαι"));
+        assert_eq!(assimilate("πεπεισ", "ται").as_deref(), Some("πεπεισται"));
+        assert_eq!(assimilate("ἠγγελ", "σθε").as_deref(), Some("ἠγγελθε"));
+        assert_eq!(assimilate("ἠγγελ", "σαι").as_deref(), Some("ἠγγελσαι"));
+        assert_eq!(
+            assimilate("γεγραμ", "μενοι").as_deref(),
+            Some("γεγραμμενοι")
+        );
+    }
+
+    #[test]
+    fn rejects_a_lemma_that_is_only_an_ending() {
+        for lemma in ["ω", "ομαι", "λύ"] {
+            assert!(stems_from_lemma(lemma).is_err(), "{}", lemma);
+        }
+        assert_eq!(
+            stems_from_lemma("ω").unwrap_err(),
+            GkVerbError::EndingOnly("ω".to_string())
+        );
+        assert_eq!(
+            uncontracted("ῶ").unwrap_err(),
+            GkVerbError::EndingOnly("ῶ".to_string())
+        );
+    }
+
+    #[test]
+    fn gives_athematic_endings_by_stem_vowel() {
+        let pai: Tva = "pai".parse().unwrap();
+        let iai: Tva = "iai".parse().unwrap();
+        let ipi: Tva = "ipi".parse().unwrap();
+        assert_eq!(
+            athematic('α', pai).unwrap(),
+            ["ημι", "ης", "ησι(ν)", "αμεν", "ατε", "ασι(ν)"]
+        );
+        assert_eq!(
+            athematic('ε', iai).unwrap(),
+            ["ην", "εις", "ει", "εμεν", "ετε", "εσαν"]
+        );
+        assert_eq!(athematic('υ', ipi).unwrap()[1], "υσο");
+        assert_eq!(athematic('ι', pai), None);
+        assert_eq!(athematic('ο', "aai".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn does_not_assimilate_without_a_rule() {
+        assert_eq!(assimilate("λελυ", "σαι"), None);
+        assert_eq!(assimilate("πεφαν", "μαι"), None);
+        assert_eq!(assimilate("γεγραμ", "νται"), None);
+    }
+
+    #[test]
+    fn contracts_an_ending_into_the_stem_vowel() {
+        assert_eq!(contract('ε', "ομεν"), "ουμεν");
+        assert_eq!(contract('ε', "ε(ν)"), "ει");
+        assert_eq!(contract('α', "εις"), "ᾳς");
+        assert_eq!(contract('α', "ουσι(ν)"), "ωσι(ν)");
+        assert_eq!(contract('ο', "ει"), "οι");
+        assert_eq!(contract('ο', "ῃ"), "οι");
+        assert_eq!(contract('α', "μαι"), "αμαι");
+    }
+
+    #[test]
+    fn uncontracts_a_lemma_where_only_one_reading_is_possible() {
+        assert_eq!(uncontracted("χρῶμαι").unwrap(), "χράομαι");
+        assert_eq!(uncontracted("λύω").unwrap(), "λύω");
+        match uncontracted("τιμῶ") {
+            Err(GkVerbError::Ambiguous { lemmas, .. }) => {
+                assert_eq!(lemmas, ["τιμάω", "τιμέω", "τιμόω"])
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn tells_the_class_from_the_lemma() {
+        assert_eq!(class_of("τιμάω"), Class::Contract);
+        assert_eq!(class_of("ποιέομαι"), Class::Contract);
+        assert_eq!(class_of("δίδωμι"), Class::Athematic);
+        assert_eq!(class_of("δύναμαι"), Class::Athematic);
+        assert_eq!(class_of("νομίζω"), Class::Regular);
+        assert!(is_deponent("ἔρχομαι"));
+        assert!(!is_deponent("λύω"));
+    }
+
+    #[test]
+    fn derives_the_stems_of_a_regular_verb() {
+        assert_eq!(future("παυ").unwrap(), "παυσ");
+        assert_eq!(future("γραφ").unwrap(), "γραψ");
+        assert_eq!(future("πραγ").unwrap(), "πραξ");
+        assert_eq!(future("πειθ").unwrap(), "πεισ");
+        assert_eq!(future("τιμα").unwrap(), "τιμησ");
+        assert_eq!(aorist("παυ").unwrap(), "ἐπαυσ");
+        assert_eq!(aorist("ἀκου").unwrap(), "ἠκουσ");
+        assert_eq!(aorist_passive("γραφ").unwrap(), "ἐγραφ");
+        assert_eq!(perfect("παυ").unwrap(), "πεπαυκ");
+        assert_eq!(perfect("γραφ").unwrap(), "γεγραφ");
+        assert_eq!(perfect("πειθ").unwrap(), "πεπεικ");
+        assert_eq!(perfect_middle("λυ").as_deref(), Some("λελυ"));
+        assert_eq!(perfect_middle("γραφ"), None);
+        assert_eq!(attic_future("νομιζ").as_deref(), Some("νομιε"));
+        assert_eq!(attic_future("κτιζ"), None);
+        assert_eq!(attic_future("σκευαζ"), None);
+        assert_eq!(future_of("σκευαζ").unwrap(), "σκευασ");
+        assert!(matches!(
+            future("μεν"),
+            Err(GkVerbError::Liquid { system: "fut", .. })
+        ));
+        assert_eq!(
+            derived("παυ", &["perfmid"]).unwrap_err(),
+            GkVerbError::Underivable("perfmid".to_string())
+        );
+    }
+
+    #[test]
+    fn derives_every_stem_from_a_lemma() {
+        let stems: Vec<String> = stems_from_lemma("παύω")
+            .unwrap()
+            .iter()
+            .map(|stem| format!("{}:{}", stem.system(), stem))
+            .collect();
+        assert_eq!(
+            stems,
+            [
+                "pres:παυ",
+                "fut:παυσ",
+                "aor:ἐπαυσ",
+                "perf:πεπαυκ",
+                "perfmid:πεπαυ",
+                "aorpass:ἐπαυ"
+            ]
+        );
+        assert_eq!(
+            stems_from_lemma("δίδωμι").unwrap_err(),
+            GkVerbError::Athematic("δίδωμι".to_string())
+        );
+        assert_eq!(
+            stems_from_lemma("λυειν").unwrap_err(),
+            GkVerbError::NotALemma("λυειν".to_string())
+        );
+    }
+}
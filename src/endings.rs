@@ -0,0 +1,176 @@
+use crate::Tva;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::sync::{Arc, OnceLock};
+
+// The endings each tva adds to its stem. A verb is conjugated with an
+// EndingSet, the grammars' Attic one unless another is plugged in, e.g. a
+// dialect's or a simplified set for beginners; the stems, augments and
+// variant spellings are the same whichever set supplies the endings. The
+// Attic endings are read from data/endings.toml, and --endings reads a
+// file of the same layout over them.
+
+const BUILTIN: &str = include_str!("../data/endings.toml");
+
+/// The six endings of each tva, in the order of [`KEYS`](crate::KEYS).
+/// An ending may mark a movable nu, "ουσι(ν)", or a 2nd sg. in -ῃ that can
+/// also be spelled -ει; the verb's nu and style then say how to write it.
+///
+/// ```
+/// use greek_writer::endings::EndingTable;
+/// use greek_writer::{conj_reqs, EndingSet, Number, Person, Tva, Verb};
+/// use std::sync::Arc;
+///
+/// // The Attic endings with the pluperfect's older 1st and 2nd sg.
+/// #[derive(Debug)]
+/// struct OldPluperfect(EndingTable);
+///
+/// impl EndingSet for OldPluperfect {
+///     fn endings(&self, tva: Tva, second: bool) -> [&str; 6] {
+///         match tva.code() {
+///             "plai" => ["ειν", "εις", "ει(ν)", "εμεν", "ετε", "εσαν"],
+///             _ => self.0.endings(tva, second),
+///         }
+///     }
+/// }
+///
+/// let mut vb = Verb::builder()
+///     .stem("perf:λελυκ")
+///     .endings(Arc::new(OldPluperfect(EndingTable::attic())))
+///     .build()
+///     .unwrap();
+/// let plai: Tva = "plai".parse().unwrap();
+/// conj_reqs(&mut vb, &[plai]).unwrap();
+/// let plai = vb.paradigm(plai).unwrap();
+/// assert_eq!(plai.get(Person::First, Number::Sg), Some("ἐλελυκειν"));
+/// ```
+pub trait EndingSet: fmt::Debug + Send + Sync {
+    /// The endings of the tva, one of [`Tva::all`]; second is set for a
+    /// second (thematic) aorist or a second aorist passive, and the future
+    /// passive built on one.
+    fn endings(&self, tva: Tva, second: bool) -> [&str; 6];
+}
+
+// An endings file: the endings of each tva by its code (or name), and
+// those of second aorists where they differ.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlEndings {
+    #[serde(default)]
+    endings: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    second: BTreeMap<String, Vec<String>>,
+}
+
+/// Endings read from TOML, as data/endings.toml lays them out.
+#[derive(Debug, Clone, Default)]
+pub struct EndingTable {
+    endings: BTreeMap<Tva, [String; 6]>,
+    second: BTreeMap<Tva, [String; 6]>,
+}
+
+impl EndingTable {
+    /// The Attic endings of the grammars, as built in.
+    pub fn attic() -> Self {
+        let mut table = EndingTable::default();
+        table
+            .read(BUILTIN)
+            .expect("built-in endings are valid TOML");
+        table
+    }
+
+    /// The Attic endings with those of a file in their place, tva by tva.
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let data = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut table = EndingTable::attic();
+        table.read(&data).map_err(|e| format!("{}: {}", path, e))?;
+        Ok(table)
+    }
+
+    /// Reads endings from TOML, each replacing the table's own for its tva.
+    pub fn read(&mut self, toml: &str) -> Result<(), Box<dyn Error>> {
+        let file: TomlEndings = toml::from_str(toml)?;
+        for (endings, table) in [
+            (file.endings, &mut self.endings),
+            (file.second, &mut self.second),
+        ] {
+            for (key, endings) in endings {
+                let tva: Tva = key.parse()?;
+                let shared = tva.shared();
+                if shared != tva {
+                    return Err(
+                        format!("{} has the endings of {}; give them there", key, shared).into(),
+                    );
+                }
+                let endings: [String; 6] = endings.try_into().map_err(|e: Vec<String>| {
+                    format!("{} has {} endings; expected 6", key, e.len())
+                })?;
+                table.insert(tva, endings);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EndingSet for EndingTable {
+    fn endings(&self, tva: Tva, second: bool) -> [&str; 6] {
+        let second = if second { self.second.get(&tva) } else { None };
+        match second.or_else(|| self.endings.get(&tva)) {
+            Some(endings) => std::array::from_fn(|i| endings[i].as_str()),
+            None => [""; 6],
+        }
+    }
+}
+
+/// The set a verb starts with, the built-in Attic endings.
+pub fn attic() -> Arc<dyn EndingSet> {
+    static ATTIC: OnceLock<Arc<EndingTable>> = OnceLock::new();
+    ATTIC.get_or_init(|| Arc::new(EndingTable::attic())).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tva(s: &str) -> Tva {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn reads_a_file_over_the_attic_endings() {
+        let mut table = EndingTable::attic();
+        assert_eq!(table.endings(tva("aai"), true)[0], "ον");
+        table
+            .read(
+                "[endings]\n\
+                 plai = [\"ειν\", \"εις\", \"ει(ν)\", \"εμεν\", \"ετε\", \"εσαν\"]\n\
+                 [second]\n\
+                 \"aorist-active\" = [\"α\", \"ες\", \"ε(ν)\", \"ομεν\", \"ετε\", \"ον\"]\n",
+            )
+            .unwrap();
+        assert_eq!(table.endings(tva("plai"), false)[0], "ειν");
+        assert_eq!(table.endings(tva("aai"), true)[0], "α");
+        // The rest are the Attic ones still.
+        assert_eq!(table.endings(tva("pai"), false)[0], "ω");
+        assert_eq!(table.endings(tva("aai"), false)[0], "α");
+    }
+
+    #[test]
+    fn rejects_a_file_it_cannot_use() {
+        let error = |toml: &str| EndingTable::attic().read(toml).unwrap_err().to_string();
+        assert_eq!(
+            error("[endings]\npai = [\"ω\", \"εις\"]\n"),
+            "pai has 2 endings; expected 6"
+        );
+        assert_eq!(
+            error("[endings]\npmi = [\"ομαι\", \"ῃ\", \"εται\", \"ομεθα\", \"εσθε\", \"ονται\"]\n"),
+            "pmi has the endings of ppi; give them there"
+        );
+        assert!(error("[endings]\nxyz = []\n").contains("xyz"));
+        assert!(error("[ending]\n").contains("unknown field"));
+    }
+}
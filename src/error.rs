@@ -0,0 +1,276 @@
+use crate::{derive, Tense, Tva, Voice};
+use std::error::Error;
+use std::fmt;
+
+/// What can go wrong building or conjugating a verb. The messages are
+/// written for the person who typed the input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GkVerbError {
+    /// A stem or form was expected but the input was empty.
+    Empty,
+    /// Latin letters or Beta Code punctuation where Greek was expected.
+    NotGreek(String),
+    /// A combining mark that is not a Greek one, or follows no letter.
+    StrayMark {
+        mark: char,
+        input: String,
+    },
+    Digit {
+        digit: char,
+        input: String,
+    },
+    Character {
+        character: char,
+        input: String,
+    },
+    /// A stem spec whose prefix names no tense system, e.g. "prs:παυ".
+    UnknownSystem(String),
+    /// A stem spec that is not a system and a stem, e.g. "pres:παυ:λυ".
+    StemSpec(String),
+    /// An aorist stem spec that is only an augment, e.g. "aor:ἐ".
+    AugmentOnly(String),
+    /// Principal parts given, but not six of them.
+    PartCount(usize),
+    /// Six principal parts given, all of them "-".
+    NoParts,
+    /// A principal part with no ending the tables know.
+    PartStem(String),
+    /// A lemma that ends in neither -ω nor -ομαι.
+    NotALemma(String),
+    /// A lemma or principal part that is only an ending, e.g. "ω", and
+    /// leaves an empty stem.
+    EndingOnly(String),
+    /// A lemma in -μι, whose stems cannot be derived like an -ω verb's.
+    Athematic(String),
+    /// A contracted lemma that could stand for more than one uncontracted
+    /// one, e.g. τιμῶ for τιμάω, τιμέω or τιμόω.
+    Ambiguous {
+        lemma: String,
+        lemmas: Vec<String>,
+    },
+    /// A liquid stem, whose stem of the system (fut, aorpass or perf)
+    /// cannot be derived regularly.
+    Liquid {
+        stem: String,
+        system: &'static str,
+    },
+    /// A tense system whose stem cannot be derived from the present, e.g.
+    /// perfmid.
+    Underivable(String),
+    /// Stems to derive for a verb with no present stem to derive them
+    /// from.
+    NoPresent(String),
+    /// A lexicon entry none of whose parts or stems could be used.
+    NoStems(String),
+    /// A tva that is neither a code nor a name; the message lists the
+    /// codes.
+    UnknownTva(String),
+    /// A name spelling a tense and voice the tables have no forms of,
+    /// e.g. "future-middle/passive".
+    ImpossibleTva {
+        code: String,
+        tense: Tense,
+        voice: Voice,
+    },
+    /// A tva the verb has no stem for, e.g. fpi of a verb given only an
+    /// aorist stem; offers are the tvas it has.
+    NoStemFor {
+        tva: Tva,
+        verb: String,
+        offers: Vec<Tva>,
+    },
+    /// A tva the voice of a principal part rules out, e.g. pai of
+    /// βούλομαι, whose present is middle in form, or ami of ἔρχομαι,
+    /// whose aorist ἦλθον is active; offers are the tvas it has.
+    Lacks {
+        tva: Tva,
+        verb: String,
+        system: &'static str,
+        voice: Voice,
+        offers: Vec<Tva>,
+    },
+    /// A consonant-stem perfect middle the endings cannot be joined to,
+    /// e.g. one in ν.
+    Unassimilable {
+        stem: String,
+        ending: String,
+    },
+    /// A future passive whose aorist passive stem begins with η, which
+    /// the present does not say was ἀ- or ἐ- before the augment.
+    AugmentVowel {
+        verb: String,
+        stem: String,
+    },
+    /// A value an option does not take, e.g. --profile wheelock; expected
+    /// lists the ones it does.
+    UnknownValue {
+        option: &'static str,
+        value: String,
+        expected: &'static [&'static str],
+    },
+    /// An error in a lexicon entry, with the entry's lemma.
+    Entry {
+        lemma: String,
+        error: Box<GkVerbError>,
+    },
+}
+
+impl fmt::Display for GkVerbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GkVerbError::Empty => write!(f, "expected a Greek stem, got nothing"),
+            GkVerbError::NotGreek(input) => write!(
+                f,
+                "did you mean to type polytonic Greek? got '{}' (for Beta Code, pass --input-encoding betacode)",
+                input
+            ),
+            GkVerbError::StrayMark { mark, input } => write!(
+                f,
+                "stray combining mark U+{:04X} in '{}'; marks must follow a Greek letter",
+                *mark as u32, input
+            ),
+            GkVerbError::Digit { digit, input } => {
+                write!(f, "unexpected digit '{}' in '{}'", digit, input)
+            }
+            GkVerbError::Character { character, input } => write!(
+                f,
+                "unexpected character '{}' in '{}'",
+                character.escape_default(),
+                input
+            ),
+            GkVerbError::UnknownSystem(spec) => write!(
+                f,
+                "unknown tense system in {}; expected pres, fut, aor, aor2, perf, perfmid, aorpass, aorpass2 or futpass",
+                spec
+            ),
+            GkVerbError::StemSpec(spec) => write!(
+                f,
+                "cannot read the stem spec {}; expected a tense system and a stem, e.g. pres:παυ",
+                spec
+            ),
+            GkVerbError::AugmentOnly(spec) => write!(
+                f,
+                "{} is only an augment; expected the stem after it too, e.g. aor:ἐπαυσ",
+                spec
+            ),
+            GkVerbError::PartCount(n) => write!(f, "expected 6 principal parts, got {}", n),
+            GkVerbError::NoParts => write!(f, "no principal parts given"),
+            GkVerbError::PartStem(part) => write!(f, "cannot find the stem of {}", part),
+            GkVerbError::NotALemma(lemma) => {
+                write!(f, "{} does not end in -ω or -ομαι", lemma)
+            }
+            GkVerbError::EndingOnly(lemma) => write!(
+                f,
+                "{} is only an ending, with no stem before it; expected a lemma such as λύω",
+                lemma
+            ),
+            GkVerbError::Athematic(lemma) => write!(
+                f,
+                "{} is a -μι verb, whose stems cannot be derived; give its principal parts in a lexicon entry",
+                lemma
+            ),
+            GkVerbError::Ambiguous { lemma, lemmas } => {
+                let last = lemmas.len().saturating_sub(1);
+                write!(
+                    f,
+                    "{} is contracted; give it uncontracted as {} or {}",
+                    lemma,
+                    lemmas[..last].join(", "),
+                    lemmas[last..].join("")
+                )
+            }
+            GkVerbError::Liquid { stem, system } => {
+                let tenses = match *system {
+                    "aorpass" => "aorist passive is",
+                    "perf" => "perfect is",
+                    _ => "future and aorist are",
+                };
+                write!(f, "{} is a liquid stem; its {} not regular", stem, tenses)
+            }
+            GkVerbError::Underivable(system) => write!(
+                f,
+                "cannot derive a {} stem; expected one of {}",
+                system,
+                derive::DERIVABLE.join(", ")
+            ),
+            GkVerbError::NoPresent(verb) => {
+                write!(f, "{} has no pres stem to derive other stems from", verb)
+            }
+            GkVerbError::NoStems(lemma) => {
+                write!(f, "{}: no usable stems in the lexicon entry", lemma)
+            }
+            GkVerbError::UnknownTva(tva) => {
+                let codes: Vec<&str> = Tva::all().map(Tva::code).collect();
+                write!(
+                    f,
+                    "unknown tva {}; expected one of {}, or a name such as aorist-active-indicative",
+                    tva,
+                    codes.join(", ")
+                )
+            }
+            GkVerbError::ImpossibleTva { code, tense, voice } => {
+                write!(f, "{} is not a tva: there is no {} {}", code, tense, voice)
+            }
+            GkVerbError::NoStemFor { tva, verb, offers } => {
+                let offers: Vec<&str> = offers.iter().map(|tva| tva.code()).collect();
+                write!(
+                    f,
+                    "{} has no {} stem for {}; it has {}",
+                    verb,
+                    tva.system(),
+                    tva,
+                    offers.join(", ")
+                )
+            }
+            GkVerbError::Lacks {
+                tva,
+                verb,
+                system,
+                voice,
+                offers,
+            } => {
+                let offers: Vec<&str> = offers.iter().map(|tva| tva.code()).collect();
+                write!(
+                    f,
+                    "{} has no {}: its {} principal part is {} in form; it has {}",
+                    verb,
+                    tva,
+                    system,
+                    voice.name(),
+                    offers.join(", ")
+                )
+            }
+            GkVerbError::Unassimilable { stem, ending } => write!(
+                f,
+                "cannot join -{} to the perfect middle stem {}; only labial, velar, dental, λ and ρ stems assimilate",
+                ending, stem
+            ),
+            GkVerbError::AugmentVowel { verb, stem } => write!(
+                f,
+                "{} has no fpi stem: the present does not say what vowel the augment of {} lengthened; give one, e.g. futpass:ἐνεχ",
+                verb, stem
+            ),
+            GkVerbError::UnknownValue {
+                option,
+                value,
+                expected,
+            } => write!(
+                f,
+                "unknown {} {}; expected one of {}",
+                option,
+                value,
+                expected.join(", ")
+            ),
+            GkVerbError::Entry { lemma, error } => write!(f, "{}: {}", lemma, error),
+        }
+    }
+}
+
+impl Error for GkVerbError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GkVerbError::Entry { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
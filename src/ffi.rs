@@ -0,0 +1,102 @@
+use crate::{conjugate_json, lexicon};
+use serde_json::json;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+// A C ABI, e.g. for Swift or Kotlin apps; include/gkverb.h declares it.
+//
+//     char *json = gkverb_conjugate("pres:παυ", "pai");
+//     ...
+//     gkverb_free(json);
+//
+// Strings are UTF-8. The answer is always a JSON string the caller must
+// hand back to gkverb_free: the paradigms as --format json prints them,
+// or {"error": "..."}.
+
+// Safety: as for gkverb_conjugate.
+unsafe fn answer(verb: *const c_char, tvas: *const c_char) -> Result<String, String> {
+    if verb.is_null() {
+        return Err("no verb given".to_string());
+    }
+    let verb = CStr::from_ptr(verb)
+        .to_str()
+        .map_err(|_| "the verb is not UTF-8".to_string())?;
+    let tvas = if tvas.is_null() {
+        ""
+    } else {
+        CStr::from_ptr(tvas)
+            .to_str()
+            .map_err(|_| "the tvas are not UTF-8".to_string())?
+    };
+    conjugate_json(verb, tvas, lexicon::builtin()).map_err(|e| e.to_string())
+}
+
+/// Conjugates a verb (a stem spec, comma-separated principal parts or a
+/// lemma) in the comma-separated tvas, or every tva if tvas is NULL or
+/// empty, and returns the paradigms as JSON.
+///
+/// # Safety
+///
+/// verb, and tvas unless NULL, must be NUL-terminated strings. The result
+/// must be freed with [`gkverb_free`].
+#[no_mangle]
+pub unsafe extern "C" fn gkverb_conjugate(verb: *const c_char, tvas: *const c_char) -> *mut c_char {
+    let text = match answer(verb, tvas) {
+        Ok(text) => text,
+        Err(e) => json!({ "error": e }).to_string(),
+    };
+    // JSON escapes any NUL, so this cannot fail.
+    CString::new(text).unwrap_or_default().into_raw()
+}
+
+/// Frees a string returned by [`gkverb_conjugate`]; NULL is ignored.
+///
+/// # Safety
+///
+/// s must have come from gkverb_conjugate and not been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn gkverb_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    // gkverb_conjugate's answer, parsed, with the string freed.
+    fn conjugate(verb: Option<&str>, tvas: Option<&str>) -> Value {
+        let verb = verb.map(|s| CString::new(s).unwrap());
+        let tvas = tvas.map(|s| CString::new(s).unwrap());
+        let ptr = |s: &Option<CString>| s.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+        unsafe {
+            let out = gkverb_conjugate(ptr(&verb), ptr(&tvas));
+            let json = CStr::from_ptr(out).to_str().unwrap().to_string();
+            gkverb_free(out);
+            serde_json::from_str(&json).unwrap()
+        }
+    }
+
+    #[test]
+    fn returns_the_paradigms_as_json() {
+        let out = conjugate(Some("pres:παυ"), Some("pai,iai"));
+        assert_eq!(out[0]["forms"][0]["form"], "παυω");
+        assert_eq!(out[1]["tva"], "iai");
+        // Every tva the verb has, with no tvas or empty ones.
+        let all = conjugate(Some("παύω"), None);
+        assert_eq!(all, conjugate(Some("παύω"), Some("")));
+        assert!(all.as_array().unwrap().len() > 2);
+    }
+
+    #[test]
+    fn returns_an_error_as_json() {
+        assert_eq!(conjugate(None, None)["error"], "no verb given");
+        assert!(conjugate(Some("pres:παυ"), Some("xyz"))["error"]
+            .as_str()
+            .unwrap()
+            .contains("xyz"));
+        unsafe { gkverb_free(std::ptr::null_mut()) };
+    }
+}
@@ -0,0 +1,225 @@
+use crate::quiz;
+use greek_writer::{cell_key, Paradigm, Tva, Verb, CELLS};
+use std::error::Error;
+use std::io::Write;
+
+// The grade subcommand: a student's answers, in a CSV file with a column
+// per cell (1sg ... 3pl), are marked against the generated paradigms. A
+// tva column says which paradigm each row answers; without one the rows
+// answer the verb's tvas in order, as a wide csv of them is laid out. Any
+// variant of a cell is a right answer, and answers are checked as quiz
+// checks them. The marks are written out as an annotated CSV, a row per
+// cell answered.
+
+pub struct Mark {
+    pub tva: Tva,
+    pub cell: String,
+    pub answer: String,
+    pub expected: Vec<String>,
+    pub right: bool,
+}
+
+// Marks every cell of every row; an empty answer is a wrong one. Answers
+// go through decode first, e.g. from Beta Code.
+pub fn grade(
+    path: &str,
+    done: &[(Verb, Vec<Tva>)],
+    checking: quiz::Checking,
+    decode: impl Fn(&str) -> String,
+) -> Result<Vec<Mark>, Box<dyn Error>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| format!("{}: {}", path, e))?;
+    let headers = rdr.headers()?.clone();
+    let tva_column = headers.iter().position(|h| h.eq_ignore_ascii_case("tva"));
+    let cells: Vec<(usize, &str)> = headers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, h)| Some((i, *CELLS.iter().find(|c| h.eq_ignore_ascii_case(c))?)))
+        .collect();
+    if cells.is_empty() {
+        return Err(format!("{}: no answer columns; name them 1sg ... 3pl", path).into());
+    }
+    let paradigms: Vec<&Paradigm> = done
+        .iter()
+        .flat_map(|(vb, reqs)| reqs.iter().filter_map(move |req| vb.paradigm(*req)))
+        .collect();
+    let mut marks = Vec::new();
+    for (i, record) in rdr.records().enumerate() {
+        // Row 1 is the header.
+        let row = i + 2;
+        let record = record.map_err(|e| format!("{}: {}", path, e))?;
+        let paradigm = match tva_column {
+            Some(col) => {
+                let tva: Tva = record
+                    .get(col)
+                    .unwrap_or_default()
+                    .parse()
+                    .map_err(|e| format!("{}: row {}: {}", path, row, e))?;
+                paradigms
+                    .iter()
+                    .find(|p| p.tva() == tva)
+                    .ok_or_else(|| format!("{}: row {}: {} was not conjugated", path, row, tva))?
+            }
+            None => paradigms.get(i).ok_or_else(|| {
+                format!(
+                    "{}: row {}: more rows than the {} tvas conjugated",
+                    path,
+                    row,
+                    paradigms.len()
+                )
+            })?,
+        };
+        for &(col, cell) in &cells {
+            let (person, number) = match cell_key(cell) {
+                Some(key) => key,
+                None => continue,
+            };
+            let expected: Vec<String> = paradigm
+                .variants(person, number)
+                .iter()
+                .map(|form| form.text.clone())
+                .collect();
+            let answer = decode(record.get(col).unwrap_or_default());
+            let right = !answer.is_empty() && quiz::check(&answer, &expected, checking);
+            marks.push(Mark {
+                tva: paradigm.tva(),
+                cell: cell.to_string(),
+                answer,
+                expected,
+                right,
+            });
+        }
+    }
+    Ok(marks)
+}
+
+// The annotated CSV: tva, cell, the answer, the forms expected and the mark.
+pub fn write(marks: &[Mark], out: impl Write) -> Result<(), Box<dyn Error>> {
+    let mut wtr = csv::Writer::from_writer(out);
+    wtr.write_record(["tva", "cell", "answer", "expected", "mark"])?;
+    for mark in marks {
+        wtr.write_record([
+            mark.tva.code(),
+            &mark.cell,
+            &mark.answer,
+            &mark.expected.join(" / "),
+            if mark.right { "correct" } else { "incorrect" },
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greek_writer::{conj_reqs, Nu};
+    use std::env;
+    use std::fs;
+
+    fn done(nu: Nu) -> Vec<(Verb, Vec<Tva>)> {
+        let mut vb = Verb::new("pres:παυ").unwrap();
+        vb.nu = nu;
+        let reqs = vec!["pai".parse().unwrap(), "iai".parse().unwrap()];
+        conj_reqs(&mut vb, &reqs).unwrap();
+        vec![(vb, reqs)]
+    }
+
+    // The marks of a student's csv, as "tva cell mark".
+    fn marks(name: &str, csv: &str, nu: Nu) -> Result<Vec<String>, Box<dyn Error>> {
+        let path =
+            env::temp_dir().join(format!("gkverb-grade-{}-{}.csv", std::process::id(), name));
+        fs::write(&path, csv).unwrap();
+        let marks = grade(
+            path.to_str().unwrap(),
+            &done(nu),
+            quiz::Checking::Lenient,
+            str::to_string,
+        );
+        fs::remove_file(&path).unwrap();
+        Ok(marks?
+            .iter()
+            .map(|m| format!("{} {} {}", m.tva.code(), m.cell, m.right))
+            .collect())
+    }
+
+    #[test]
+    fn marks_rows_by_their_tva_column() {
+        let marks = marks(
+            "tva",
+            "tva,1sg,3pl\niai,ἐπαύον,\npai,παυω,παυουσι\n",
+            Nu::Omit,
+        )
+        .unwrap();
+        assert_eq!(
+            marks,
+            [
+                "iai 1sg true",
+                "iai 3pl false",
+                "pai 1sg true",
+                "pai 3pl true"
+            ]
+        );
+    }
+
+    #[test]
+    fn takes_either_variant_of_a_cell() {
+        let csv = "tva,3pl\npai,παυουσι\npai,παυουσιν\n";
+        assert_eq!(
+            marks("omit", csv, Nu::Omit).unwrap(),
+            ["pai 3pl true", "pai 3pl false"]
+        );
+        assert_eq!(
+            marks("rows", csv, Nu::Rows).unwrap(),
+            ["pai 3pl true", "pai 3pl true"]
+        );
+    }
+
+    #[test]
+    fn marks_rows_in_order_without_a_tva_column() {
+        let marks = marks("order", "1sg,2sg\nπαυω,παυεις\nπαυω,ἐπαυες\n", Nu::Omit).unwrap();
+        assert_eq!(
+            marks,
+            [
+                "pai 1sg true",
+                "pai 2sg true",
+                "iai 1sg false",
+                "iai 2sg true"
+            ]
+        );
+        let e = self::marks("extra", "1sg\nπαυω\nἐπαυον\nπαυω\n", Nu::Omit).unwrap_err();
+        assert!(e
+            .to_string()
+            .ends_with("row 4: more rows than the 2 tvas conjugated"));
+    }
+
+    #[test]
+    fn rejects_a_csv_it_cannot_mark() {
+        let e = marks("nocells", "tva,form\npai,παυω\n", Nu::Omit).unwrap_err();
+        assert!(e
+            .to_string()
+            .ends_with("no answer columns; name them 1sg ... 3pl"));
+        let e = marks("unasked", "tva,1sg\naai,ἐπαυσα\n", Nu::Omit).unwrap_err();
+        assert!(e.to_string().ends_with("row 2: aai was not conjugated"));
+    }
+
+    #[test]
+    fn writes_a_row_per_cell_marked() {
+        let mark = Mark {
+            tva: "pai".parse().unwrap(),
+            cell: "3pl".to_string(),
+            answer: "παυουσι".to_string(),
+            expected: vec!["παυουσι".to_string(), "παυουσιν".to_string()],
+            right: true,
+        };
+        let mut out = Vec::new();
+        write(&[mark], &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "tva,cell,answer,expected,mark\npai,3pl,παυουσι,παυουσι / παυουσιν,correct\n"
+        );
+    }
+}
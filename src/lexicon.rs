@@ -0,0 +1,293 @@
+use crate::{derive, strip_accents, GkVerbError, CELLS};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::sync::OnceLock;
+use unicode_normalization::UnicodeNormalization;
+
+// Lexicons of verbs keyed by lemma. The built-in one holds the principal
+// parts of common Attic verbs, so that --lemma works for irregular verbs
+// without the user supplying stems; --lexicon adds the user's own, in the
+// same CSV layout or as TOML [[verb]] tables, and takes precedence.
+
+const BUILTIN: &str = include_str!("../data/lexicon.csv");
+
+/// One verb of a lexicon: a row of the CSV or a `[[verb]]` table of the
+/// TOML. A principal part left empty or given as "-" is one the verb
+/// lacks.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Entry {
+    /// The first principal part, the present, as in λύω.
+    pub lemma: String,
+    /// The future principal part, λύσω.
+    pub future: String,
+    /// The aorist principal part, ἔλυσα.
+    pub aorist: String,
+    /// The perfect active principal part, λέλυκα.
+    pub perfect: String,
+    /// The perfect middle principal part, λέλυμαι.
+    pub perfect_middle: String,
+    /// The aorist passive principal part, ἐλύθην.
+    pub aorist_passive: String,
+    /// An English meaning.
+    pub gloss: String,
+    /// Explicit stems such as "aor2:ἐλαβ", replacing the one cut from the
+    /// principal part of the same tense system.
+    pub stems: Items,
+    /// The conjugation class, one of [`crate::CLASSES`]; left empty, the
+    /// lemma shows it.
+    pub class: String,
+    /// Whether the verb is deponent; by default, whether the lemma is
+    /// middle in form.
+    pub deponent: Option<bool>,
+    /// A deponent whose aorist is passive in form, as βούλομαι's ἐβουλήθην;
+    /// by default, one with an aorist passive but no aorist part.
+    pub passive_deponent: Option<bool>,
+    /// Single forms to substitute, keyed like "aai.3sg".
+    pub overrides: Overrides,
+}
+
+/// A list given either as a TOML array or as one comma-separated string.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Items {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Default for Items {
+    fn default() -> Self {
+        Items::Text(String::new())
+    }
+}
+
+impl Items {
+    /// The items, trimmed, leaving out any empty ones of a string.
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            Items::Text(s) => s
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+            Items::List(v) => v.clone(),
+        }
+    }
+}
+
+/// Overrides given either as a TOML table or as "aai.3sg=ἦλθε;aai.3pl=ἦλθον".
+/// A tva without a cell takes all six forms, comma-separated in the order
+/// 1sg to 3pl: "iai=εἶχον,εἶχες,εἶχε(ν),εἴχομεν,εἴχετε,εἶχον".
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Overrides {
+    Text(String),
+    Map(BTreeMap<String, String>),
+}
+
+impl Default for Overrides {
+    fn default() -> Self {
+        Overrides::Text(String::new())
+    }
+}
+
+impl Overrides {
+    /// The forms keyed by tva and cell, "iai.3sg", a tva given whole being
+    /// spread over its six cells.
+    pub fn to_map(&self) -> BTreeMap<String, String> {
+        let pairs: Vec<(&str, &str)> = match self {
+            Overrides::Text(s) => s
+                .split(';')
+                .filter_map(|pair| {
+                    let mut kv = pair.splitn(2, '=');
+                    Some((kv.next()?.trim(), kv.next()?.trim()))
+                })
+                .collect(),
+            Overrides::Map(m) => m.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect(),
+        };
+        let mut map = BTreeMap::new();
+        for (key, form) in pairs {
+            if key.contains('.') {
+                map.insert(key.to_string(), form.to_string());
+                continue;
+            }
+            for (cell, form) in CELLS.iter().zip(form.split(',')) {
+                map.insert(format!("{}.{}", key, cell), form.trim().to_string());
+            }
+        }
+        map
+    }
+}
+
+impl Entry {
+    /// The six principal parts in the usual order, the present being the lemma.
+    pub fn parts(&self) -> [&str; 6] {
+        [
+            &self.lemma,
+            &self.future,
+            &self.aorist,
+            &self.perfect,
+            &self.perfect_middle,
+            &self.aorist_passive,
+        ]
+    }
+
+    /// Whether the verb is a deponent whose only aorist is the passive one.
+    pub fn passive_aorist_only(&self) -> bool {
+        matches!(self.aorist.as_str(), "" | "-")
+            && !matches!(self.aorist_passive.as_str(), "" | "-")
+            && !self
+                .stems
+                .to_vec()
+                .iter()
+                .any(|spec| spec.starts_with("aor"))
+    }
+
+    /// True when only the lemma is given, so the rest must be derived.
+    pub fn lemma_only(&self) -> bool {
+        self.parts()[1..].iter().all(|p| p.is_empty() || *p == "-")
+            && self.stems.to_vec().is_empty()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TomlLexicon {
+    verb: Vec<Entry>,
+}
+
+/// The built-in lexicon, data/lexicon.csv, read the first time it is
+/// asked for.
+pub fn builtin() -> &'static [Entry] {
+    static ENTRIES: OnceLock<Vec<Entry>> = OnceLock::new();
+    ENTRIES.get_or_init(|| from_csv(BUILTIN.as_bytes()).expect("built-in lexicon is valid CSV"))
+}
+
+fn from_csv(data: &[u8]) -> Result<Vec<Entry>, csv::Error> {
+    csv::ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .from_reader(data)
+        .deserialize()
+        .collect()
+}
+
+/// Reads a user lexicon, as TOML if the file name ends in .toml and as CSV
+/// otherwise.
+pub fn load(path: &str) -> Result<Vec<Entry>, Box<dyn Error>> {
+    let data = fs::read_to_string(path)?;
+    if path.ends_with(".toml") {
+        let lexicon: TomlLexicon = toml::from_str(&data)?;
+        Ok(lexicon.verb)
+    } else {
+        Ok(from_csv(data.as_bytes())?)
+    }
+}
+
+/// Finds a lemma regardless of how it was accented, unless two lemmas
+/// differ only in their accents, as εἰμί and εἶμι do; then the one
+/// accented as typed wins. Earlier entries win, so a user lexicon placed
+/// first shadows the built-in one.
+pub fn find<'a>(entries: &'a [Entry], lemma: &str) -> Option<&'a Entry> {
+    let typed: String = lemma.trim().nfc().collect();
+    let wanted = strip_accents(&typed);
+    let mut found = entries
+        .iter()
+        .filter(|entry| strip_accents(&entry.lemma) == wanted);
+    let first = found.next()?;
+    Some(
+        std::iter::once(first)
+            .chain(found)
+            .find(|entry| entry.lemma.nfc().eq(typed.chars()))
+            .unwrap_or(first),
+    )
+}
+
+/// Finds a lemma as [`find`] does, or, for a contracted one such as τιμῶ,
+/// whichever of its uncontracted readings the lexicon has.
+pub fn find_lemma<'a>(entries: &'a [Entry], lemma: &str) -> Option<&'a Entry> {
+    find(entries, lemma).or_else(|| {
+        let readings = match derive::uncontracted(lemma.trim()) {
+            Ok(lemma) => vec![lemma],
+            Err(GkVerbError::Ambiguous { lemmas, .. }) => lemmas,
+            Err(_) => return None,
+        };
+        readings.iter().find_map(|lemma| find(entries, lemma))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // A lexicon file of the given name and contents in a fresh directory.
+    fn file(name: &str, data: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gkverb-lexicon-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_a_csv_lexicon() {
+        let path = file(
+            "verbs.csv",
+            "# a comment\n\
+             lemma,future,aorist,perfect,perfect_middle,aorist_passive,gloss,stems\n\
+             λαμβάνω,λήψομαι,ἔλαβον,εἴληφα,εἴλημμαι,ἐλήφθην,take,aor2:ἐλαβ\n",
+        );
+        let entries = load(path.to_str().unwrap()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].parts()[2], "ἔλαβον");
+        assert_eq!(entries[0].stems.to_vec(), ["aor2:ἐλαβ"]);
+        assert!(!entries[0].lemma_only());
+    }
+
+    #[test]
+    fn loads_a_toml_lexicon() {
+        let path = file(
+            "verbs.toml",
+            "[[verb]]\n\
+             lemma = \"βούλομαι\"\n\
+             future = \"βουλήσομαι\"\n\
+             aorist_passive = \"ἐβουλήθην\"\n\
+             stems = [\"pres:βουλ\"]\n\
+             [verb.overrides]\n\
+             \"pmi.2sg\" = \"βούλει\"\n",
+        );
+        let entries = load(path.to_str().unwrap()).unwrap();
+        assert_eq!(entries[0].lemma, "βούλομαι");
+        assert_eq!(entries[0].stems.to_vec(), ["pres:βουλ"]);
+        assert!(entries[0].passive_aorist_only());
+        assert_eq!(entries[0].overrides.to_map()["pmi.2sg"], "βούλει");
+    }
+
+    #[test]
+    fn reports_a_malformed_lexicon() {
+        let path = file("bad.toml", "[[verb]]\nlemma = 1\n");
+        assert!(load(path.to_str().unwrap()).is_err());
+        assert!(load("no/such/lexicon.csv").is_err());
+    }
+
+    #[test]
+    fn spreads_a_whole_tva_override_over_its_cells() {
+        let overrides = Overrides::Text(
+            "iai=εἶχον,εἶχες,εἶχε(ν),εἴχομεν,εἴχετε,εἶχον;aai.3sg=ἔσχε".to_string(),
+        );
+        let map = overrides.to_map();
+        assert_eq!(map.len(), 7);
+        assert_eq!(map["iai.3sg"], "εἶχε(ν)");
+        assert_eq!(map["aai.3sg"], "ἔσχε");
+    }
+
+    #[test]
+    fn finds_a_lemma_however_it_is_accented() {
+        let entries = builtin();
+        assert_eq!(find(entries, "λυω").unwrap().lemma, "λύω");
+        assert_eq!(find_lemma(entries, "ποιῶ").unwrap().lemma, "ποιέω");
+        assert!(find(entries, "ξυζω").is_none());
+    }
+}
@@ -0,0 +1,1706 @@
+//! The conjugation engine behind the gkverb command: verbs built from stem
+//! specs, principal parts or lemmas, conjugated tva by tva, and the modules
+//! that print and parse their forms. With the wasm feature it also builds
+//! for the browser; see wasm.rs. With the serde feature a [`Verb`], its
+//! stems and paradigms can be serialized, e.g. to cache them on disk.
+//!
+//! A tva is a three- or four-letter code for a tense, voice and mood, e.g.
+//! pai (present active indicative) or api (aorist passive indicative); see
+//! [`ALL_TVAS`] and [`Tva`].
+//!
+//! ```
+//! use greek_writer::{conj_reqs, lexicon, Number, Person, Tva, Verb};
+//!
+//! let mut vb = Verb::from_lemma("λύω", lexicon::builtin()).unwrap();
+//! let aai: Tva = "aai".parse().unwrap();
+//! conj_reqs(&mut vb, &[aai]).unwrap();
+//! let aorist = vb.paradigm(aai).unwrap();
+//! assert_eq!(aorist.get(Person::Third, Number::Pl), Some("ἐλυσαν"));
+//! assert!(aorist.to_string().contains("3pl  ἐλυσαν"));
+//! ```
+//!
+//! A [`Paradigm`] or [`Verb`] displays as the table format prints it; see
+//! [`Verb::to_table_string`] for the other layouts.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
+
+pub use builder::VerbBuilder;
+pub use endings::EndingSet;
+pub use error::GkVerbError;
+pub use paradigm::{cell_key, Form, Number, Paradigm, Person, KEYS};
+pub use tva::{Mood, Tense, Tva, Voice};
+
+/// Beta Code to and from Unicode Greek.
+pub mod betacode;
+/// Verbs put together stem by stem and option by option.
+pub mod builder;
+/// Regular stems derived from a lemma.
+pub mod derive;
+/// The endings each tva adds to its stem.
+pub mod endings;
+/// The errors building a verb can give.
+pub mod error;
+/// A C ABI for calling the engine from other languages.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+/// The lexicon of principal parts, built in or read from a file.
+pub mod lexicon;
+/// Paradigms printed as tables, csv, json and other formats.
+pub mod output;
+/// The forms of one tva, by person and number.
+pub mod paradigm;
+/// Analysing forms by finding them in generated paradigms.
+pub mod parse;
+/// The gkverb Python module.
+#[cfg(feature = "python")]
+pub mod python;
+/// Romanization of generated forms.
+pub mod translit;
+/// Tense, voice and mood, and the tva codes that name them.
+pub mod tva;
+/// wasm-bindgen wrappers for running in a browser.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// A stem and the tense system it belongs to, as in a stem spec such as
+/// pres:παυ or aor2:ἐλαβ.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Stem {
+    Pres(String),
+    Fut(String),
+    Aor(String),
+    Aor2(String),
+    Perf(String),
+    PerfMid(String),
+    AorPass(String),
+    AorPass2(String),
+    /// The stem of the future passive, for a verb whose unaugmented
+    /// aorist passive stem the present does not settle: futpass:ἐνεχ for
+    /// ἠνέχθην, ἐνεχθήσομαι.
+    FutPass(String),
+}
+
+impl Stem {
+    /// The tense system this stem belongs to, spelled as in "pres:παυ".
+    /// Second (thematic) aorists belong to the same systems as first ones.
+    pub fn system(&self) -> &'static str {
+        match self {
+            Stem::Pres(_) => "pres",
+            Stem::Fut(_) => "fut",
+            Stem::Aor(_) | Stem::Aor2(_) => "aor",
+            Stem::Perf(_) => "perf",
+            Stem::PerfMid(_) => "perfmid",
+            Stem::AorPass(_) | Stem::AorPass2(_) => "aorpass",
+            Stem::FutPass(_) => "futpass",
+        }
+    }
+
+    /// The stem itself, without its system.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Stem::Pres(val)
+            | Stem::Fut(val)
+            | Stem::Aor(val)
+            | Stem::Aor2(val)
+            | Stem::Perf(val)
+            | Stem::PerfMid(val)
+            | Stem::AorPass(val)
+            | Stem::AorPass2(val)
+            | Stem::FutPass(val) => val,
+        }
+    }
+}
+
+impl fmt::Display for Stem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How to render endings carrying a movable nu, written "(ν)" in the tables.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Nu {
+    Omit,
+    Show,
+    Both,
+    Rows,
+}
+
+/// The values --nu takes.
+pub const NUS: [&str; 4] = ["omit", "show", "both", "rows"];
+
+impl Nu {
+    /// From a value of --nu, one of [`NUS`].
+    pub fn from_arg(s: &str) -> Result<Self, GkVerbError> {
+        match s {
+            "omit" => Ok(Nu::Omit),
+            "show" => Ok(Nu::Show),
+            "both" => Ok(Nu::Both),
+            "rows" => Ok(Nu::Rows),
+            _ => Err(GkVerbError::UnknownValue {
+                option: "nu",
+                value: s.to_string(),
+                expected: &NUS,
+            }),
+        }
+    }
+}
+
+/// Which spelling of the 2nd sg. middle/passive, -ῃ or -ει, to emit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Style {
+    Eta,
+    Ei,
+    Both,
+}
+
+/// The values --style takes.
+pub const STYLES: [&str; 3] = ["eta", "ei", "both"];
+
+impl Style {
+    /// From a value of --style, one of [`STYLES`].
+    pub fn from_arg(s: &str) -> Result<Self, GkVerbError> {
+        match s {
+            "eta" => Ok(Style::Eta),
+            "ei" => Ok(Style::Ei),
+            "both" => Ok(Style::Both),
+            _ => Err(GkVerbError::UnknownValue {
+                option: "style",
+                value: s.to_string(),
+                expected: &STYLES,
+            }),
+        }
+    }
+}
+
+/// The conjugation class of a verb, as a lexicon entry gives it or its
+/// lemma shows. A contract verb's present and imperfect are contracted,
+/// an athematic verb's take the -μι endings; liquid verbs are conjugated
+/// as regular.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Class {
+    #[default]
+    Regular,
+    Contract,
+    Athematic,
+    Liquid,
+}
+
+/// The classes a lexicon entry may give.
+pub const CLASSES: [&str; 4] = ["regular", "contract", "athematic", "liquid"];
+
+impl Class {
+    /// From a lexicon entry's class, one of [`CLASSES`].
+    pub fn from_arg(s: &str) -> Result<Self, GkVerbError> {
+        match s {
+            "regular" => Ok(Class::Regular),
+            "contract" => Ok(Class::Contract),
+            "athematic" => Ok(Class::Athematic),
+            "liquid" => Ok(Class::Liquid),
+            _ => Err(GkVerbError::UnknownValue {
+                option: "class",
+                value: s.to_string(),
+                expected: &CLASSES,
+            }),
+        }
+    }
+}
+
+/// The names --profile takes.
+pub const PROFILES: [&str; 5] = ["plain", "athenaze", "jact", "hq", "mastronarde"];
+
+/// A textbook's choice of variant endings: its nu movable and its 2nd sg.
+/// middle/passive. The forms are written unaccented, so a profile has no
+/// accents to choose. "plain" is the tool's own default; "jact", the
+/// Reading Greek course, shares athenaze's endings.
+#[derive(Debug, Clone, Copy)]
+pub struct Profile {
+    pub nu: Nu,
+    pub style: Style,
+}
+
+impl Profile {
+    /// From a textbook's name as given to --profile, one of [`PROFILES`].
+    pub fn from_arg(s: &str) -> Result<Self, GkVerbError> {
+        Ok(match s {
+            "plain" => Profile {
+                nu: Nu::Omit,
+                style: Style::Eta,
+            },
+            "athenaze" | "jact" => Profile {
+                nu: Nu::Both,
+                style: Style::Ei,
+            },
+            "hq" => Profile {
+                nu: Nu::Both,
+                style: Style::Eta,
+            },
+            "mastronarde" => Profile {
+                nu: Nu::Both,
+                style: Style::Both,
+            },
+            _ => {
+                return Err(GkVerbError::UnknownValue {
+                    option: "profile",
+                    value: s.to_string(),
+                    expected: &PROFILES,
+                })
+            }
+        })
+    }
+}
+
+/// A verb: its stems, the options for its variant endings, and the
+/// paradigms [`conj_reqs`] has filled in.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Verb {
+    /// The dictionary form, when the verb was given by lemma or principal
+    /// parts; empty for a bare stem.
+    pub lemma: String,
+    /// An English meaning, from the lexicon.
+    pub gloss: String,
+    pub stems: Vec<Stem>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub class: Class,
+    /// Whether the verb's present is middle in form, so that it has no
+    /// active forms but in a system whose principal part is active.
+    pub deponent: bool,
+    /// The voice of each principal part the verb was given, by tense
+    /// system: a system whose part is middle (ἀκούσομαι) has no active
+    /// forms, and a deponent's whose part is active (ἦλθον) no middle.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub voices: BTreeMap<String, Voice>,
+    /// Whether the verb is a passive deponent, as βούλομαι is: its aorist
+    /// (ami) is built on the aorist passive stem with the passive endings,
+    /// ἐβουλήθην, and it has no aorist passive besides.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub passive_deponent: bool,
+    /// Irregular forms from the lexicon, keyed like "aai.3sg".
+    pub overrides: BTreeMap<String, String>,
+    pub nu: Nu,
+    pub style: Style,
+    /// The endings the verb is conjugated with.
+    #[cfg_attr(feature = "serde", serde(skip, default = "endings::attic"))]
+    pub endings: Arc<dyn EndingSet>,
+    /// What building the verb from its lexicon entry passed over, e.g. a
+    /// principal part with no ending the tables know, for the caller to
+    /// show or not.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub warnings: Vec<String>,
+    paradigms: BTreeMap<Tva, Paradigm>,
+}
+
+impl Verb {
+    /// A builder for a verb of several stems and options.
+    pub fn builder() -> VerbBuilder {
+        VerbBuilder::default()
+    }
+
+    /// A verb of one stem, from a stem spec such as pres:παυ.
+    pub fn new(s: &str) -> Result<Self, GkVerbError> {
+        Ok(Verb::with_stems(vec![Verb::parse_stem(s)?]))
+    }
+
+    /// A verb of several stems, from comma-separated stem specs such as
+    /// "pres:παυ,fut:παυσ,aor:ἐπαυσ"; a later stem of a system replaces an
+    /// earlier one.
+    pub fn from_specs(specs: &str) -> Result<Self, GkVerbError> {
+        specs
+            .split(',')
+            .fold(Verb::builder(), |builder, spec| builder.stem(spec))
+            .build()
+    }
+
+    /// A verb of the given stems, with nothing conjugated yet.
+    pub fn with_stems(stems: Vec<Stem>) -> Self {
+        Self {
+            lemma: String::new(),
+            gloss: String::new(),
+            stems,
+            class: Class::Regular,
+            deponent: false,
+            voices: BTreeMap::new(),
+            passive_deponent: false,
+            overrides: BTreeMap::new(),
+            nu: Nu::Omit,
+            style: Style::Eta,
+            endings: endings::attic(),
+            warnings: Vec::new(),
+            paradigms: BTreeMap::new(),
+        }
+    }
+
+    /// Takes the six principal parts, e.g. "παύω,παύσω,ἔπαυσα,πέπαυκα,πέπαυμαι,ἐπαύθην",
+    /// and cuts each back to its stem. Accents are dropped, since the endings
+    /// are unaccented. A part given as "-" is left out.
+    pub fn from_parts(parts: &[&str]) -> Result<Self, GkVerbError> {
+        if parts.len() != 6 {
+            return Err(GkVerbError::PartCount(parts.len()));
+        }
+        let mut stems = Vec::new();
+        let mut voices = BTreeMap::new();
+        for (i, part) in parts.iter().enumerate() {
+            if let Some(stem) = Verb::part_stem(i, part)? {
+                voices.insert(stem.system().to_string(), Verb::part_voice(part));
+                stems.push(stem);
+            }
+        }
+        if stems.is_empty() {
+            return Err(GkVerbError::NoParts);
+        }
+        let mut vb = Verb::with_stems(stems);
+        vb.lemma = parts[0].nfc().collect();
+        vb.deponent = derive::is_deponent(&vb.lemma);
+        vb.voices = voices;
+        Ok(vb)
+    }
+
+    /// Builds a verb from a lexicon entry: its principal parts (or, if it gives
+    /// only the lemma, regularly derived stems), then any explicit stems.
+    /// A part of a shape the tables cannot conjugate (a root aorist such as
+    /// ἔβην, say) is left out, and said so in the verb's warnings, unless
+    /// the overrides give its forms.
+    pub fn from_entry(entry: &lexicon::Entry) -> Result<Self, GkVerbError> {
+        let overrides: BTreeMap<String, String> = entry
+            .overrides
+            .to_map()
+            .into_iter()
+            .map(|(key, form)| (key, strip_accents(&form)))
+            .collect();
+        // The systems of the tvas the overrides give forms of.
+        let overridden: Vec<&str> = overrides
+            .keys()
+            .filter_map(|key| key.split('.').next()?.parse::<Tva>().ok())
+            .map(Tva::system)
+            .collect();
+        let mut stems = Vec::new();
+        let mut warnings = Vec::new();
+        let mut voices = BTreeMap::new();
+        let explicit = entry
+            .stems
+            .to_vec()
+            .iter()
+            .map(|spec| Verb::parse_stem(spec))
+            .collect::<Result<Vec<Stem>, _>>()
+            .map_err(|e| in_entry(entry, e))?;
+        // A present the overrides give is irregular, and nothing can be
+        // derived from it.
+        if entry.lemma_only() && !overridden.contains(&"pres") {
+            stems = derive::stems_from_lemma(&entry.lemma).map_err(|e| in_entry(entry, e))?;
+        } else {
+            for (i, part) in entry.parts().iter().enumerate() {
+                match Verb::part_stem(i, part) {
+                    Ok(Some(stem)) => {
+                        voices.insert(stem.system().to_string(), Verb::part_voice(part));
+                        stems.push(stem);
+                    }
+                    Ok(None) => {}
+                    // A part whose stem the entry gives, or that is itself
+                    // one of the forms it gives, is not missed.
+                    Err(_) if explicit.iter().any(|s| s.system() == PART_SYSTEMS[i]) => {}
+                    Err(_) if overrides.values().any(|form| *form == strip_accents(part)) => {}
+                    Err(e) => warnings.push(format!("{}, so it is left out", e)),
+                }
+            }
+        }
+        for stem in explicit {
+            stems.retain(|s| s.system() != stem.system());
+            voices.remove(stem.system());
+            stems.push(stem);
+        }
+        if stems.is_empty() && overridden.is_empty() {
+            return Err(GkVerbError::NoStems(entry.lemma.clone()));
+        }
+        let class = match entry.class.as_str() {
+            "" => derive::class_of(&entry.lemma),
+            class => Class::from_arg(class).map_err(|e| in_entry(entry, e))?,
+        };
+        if class == Class::Liquid {
+            warnings.push("class liquid is not supported yet; conjugating as regular".to_string());
+        }
+        let mut vb = Verb::with_stems(stems);
+        vb.lemma = entry.lemma.nfc().collect();
+        vb.gloss = entry.gloss.replace(';', ",");
+        vb.class = class;
+        vb.warnings = warnings;
+        vb.deponent = entry
+            .deponent
+            .unwrap_or_else(|| derive::is_deponent(&entry.lemma));
+        vb.voices = voices;
+        vb.passive_deponent = entry
+            .passive_deponent
+            .unwrap_or_else(|| vb.deponent && entry.passive_aorist_only());
+        vb.overrides = overrides;
+        Ok(vb)
+    }
+
+    // The voice a principal part is in: middle for one in -μαι or -μην
+    // (ἀκούσομαι, ἐγενόμην), otherwise active.
+    fn part_voice(part: &str) -> Voice {
+        let part = strip_accents(part);
+        if part.ends_with("μαι") || part.ends_with("μην") {
+            Voice::Middle
+        } else {
+            Voice::Active
+        }
+    }
+
+    // Cuts the ending off the principal part in position i (0 = present).
+    fn part_stem(i: usize, part: &str) -> Result<Option<Stem>, GkVerbError> {
+        if part.is_empty() || part == "-" {
+            return Ok(None);
+        }
+        check_greek(part)?;
+        // An athematic present's stem is its short grade: δίδωμι -> διδο,
+        // δείκνυμι -> δεικνυ, δύναμαι -> δυνα. One in -ημι may be of ε or
+        // α (τίθημι, ἵστημι), and its stem must be given.
+        if i == 0 {
+            let part = strip_accents(part);
+            if let Some(stem) = part.strip_suffix("ωμι") {
+                return Ok(Some(Stem::Pres(format!("{}ο", stem))));
+            }
+            if let Some(stem) = part
+                .strip_suffix("μι")
+                .filter(|stem| stem.ends_with('υ'))
+                .or_else(|| part.strip_suffix("μαι").filter(|s| s.ends_with('α')))
+            {
+                return Ok(Some(Stem::Pres(stem.to_string())));
+            }
+        }
+        // A future in -ῶ or -οῦμαι is contracted, and its stem keeps the ε
+        // the endings contract with: μενῶ -> μενε, νομιῶ -> νομιε.
+        if i == 1 {
+            let part: String = part.nfc().collect();
+            if let Some(stem) = part
+                .strip_suffix('ῶ')
+                .or_else(|| part.strip_suffix("οῦμαι"))
+            {
+                return Ok(Some(Stem::Fut(format!("{}ε", strip_accents(stem)))));
+            }
+        }
+        let part = strip_accents(part);
+        let cuts: &[Cut] = match i {
+            0 => &[("ομαι", Stem::Pres), ("ω", Stem::Pres)],
+            1 => &[("ουμαι", Stem::Fut), ("ομαι", Stem::Fut), ("ω", Stem::Fut)],
+            2 => &[
+                ("αμην", Stem::Aor),
+                ("α", Stem::Aor),
+                ("ομην", Stem::Aor2),
+                ("ον", Stem::Aor2),
+            ],
+            3 => &[("α", Stem::Perf)],
+            4 => &[("μαι", Stem::PerfMid)],
+            _ => &[("θην", Stem::AorPass), ("ην", Stem::AorPass2)],
+        };
+        cuts.iter()
+            .find_map(|(ending, stem)| part.strip_suffix(ending).map(|s| stem(s.to_string())))
+            .map(Some)
+            .ok_or(GkVerbError::PartStem(part))
+    }
+
+    /// A verb from its dictionary form: its lexicon entry if it has one,
+    /// otherwise the stems of a regular verb. Either way, what the lemma
+    /// shows of its class is kept where the entry does not say: a contract
+    /// verb's present is contracted, and a lemma in -ομαι is a deponent's.
+    /// A contracted lemma is read as whichever reading of it the lexicon
+    /// has, or else uncontracted where only one reading is possible.
+    pub fn from_lemma(lemma: &str, entries: &[lexicon::Entry]) -> Result<Self, GkVerbError> {
+        check_greek(lemma.trim())?;
+        if let Some(entry) = lexicon::find_lemma(entries, lemma) {
+            return Verb::from_entry(entry);
+        }
+        let lemma = derive::uncontracted(lemma)?;
+        let mut vb = Verb::with_stems(derive::stems_from_lemma(&lemma)?);
+        vb.class = derive::class_of(&lemma);
+        vb.deponent = derive::is_deponent(&lemma);
+        vb.lemma = lemma;
+        Ok(vb)
+    }
+
+    /// A verb typed in the browser, the REPL or a wasm call: a stem spec
+    /// such as pres:παυ, several comma-separated ones, six comma-separated
+    /// principal parts, or a lemma.
+    pub fn from_input(input: &str, entries: &[lexicon::Entry]) -> Result<Self, GkVerbError> {
+        let input = input.trim();
+        if input.contains(',') && input.contains(':') {
+            Verb::from_specs(input)
+        } else if input.contains(',') {
+            let parts: Vec<&str> = input.split(',').map(str::trim).collect();
+            Verb::from_parts(&parts)
+        } else if input.contains(':') {
+            Verb::new(input)
+        } else {
+            Verb::from_lemma(input, entries)
+        }
+    }
+
+    // With a single stem every tva uses it; with principal parts, the stem of
+    // the tva's own tense system.
+    fn find_stem(&self, tva: Tva) -> &Stem {
+        let tva = self.built_as(tva);
+        if self.stems.len() == 1 {
+            return &self.stems[0];
+        }
+        if (tva.tense, tva.voice) == (Tense::Future, Voice::Passive) {
+            if let Some(stem) = self.stems.iter().find(|s| matches!(s, Stem::FutPass(_))) {
+                return stem;
+            }
+        }
+        self.stems
+            .iter()
+            .find(|s| s.system() == tva.system())
+            .or_else(|| self.stems.first())
+            .unwrap_or(&NO_STEM)
+    }
+
+    /// The future passive is built on its futpass stem or else the
+    /// unaugmented aorist passive stem; every other tva borrows its stem
+    /// as given. An aorist passive stem that cannot be unaugmented is
+    /// given as it is, though the verb then has no stem for fpi.
+    /// A tva the overrides give in full, with no stem to build on, has
+    /// none.
+    pub fn stem_for(&self, tva: Tva) -> Cow<'_, str> {
+        if self.given(tva) && !self.has_stem_for(tva) {
+            return Cow::Borrowed("");
+        }
+        let stem = self.find_stem(tva);
+        match stem {
+            Stem::AorPass(s) | Stem::AorPass2(s)
+                if (tva.tense, tva.voice) == (Tense::Future, Voice::Passive)
+                    && self.stems.len() > 1 =>
+            {
+                match self.unaugmented(s) {
+                    Some(unaugmented) => Cow::Owned(unaugmented),
+                    None => Cow::Borrowed(s.as_str()),
+                }
+            }
+            stem => Cow::Borrowed(stem.as_str()),
+        }
+    }
+
+    // An aorist stem without its augment, the present restoring the
+    // vowel the augment lengthened.
+    fn unaugmented(&self, stem: &str) -> Option<String> {
+        let pres = self.stems.iter().find(|s| s.system() == "pres");
+        Verb::unaugment(stem, pres.map(Stem::as_str))
+    }
+
+    // Whether a tva takes the thematic endings of a second aorist; a
+    // futpass stem takes those of the verb's aorist passive.
+    fn is_second(&self, tva: Tva) -> bool {
+        match self.find_stem(tva) {
+            Stem::FutPass(_) => self.stems.iter().any(|s| matches!(s, Stem::AorPass2(_))),
+            stem => matches!(stem, Stem::Aor2(_) | Stem::AorPass2(_)),
+        }
+    }
+
+    /// Whether the verb has a stem the tva can be built on: one of the
+    /// tense system it needs or, for a verb of a single stem, one the tva
+    /// shares its stem with, as the aorist passive does a first aorist's.
+    pub fn has_stem_for(&self, tva: Tva) -> bool {
+        let tva = self.built_as(tva);
+        // A verb of principal parts has only the tvas of their systems.
+        let typed = self.voices.is_empty();
+        match self.stems.as_slice() {
+            [Stem::Aor(_)] if typed => tva.tense == Tense::Aorist,
+            [Stem::Fut(_)] if typed => tva.tense == Tense::Future,
+            [Stem::FutPass(_)] if typed => {
+                (tva.tense, tva.voice) == (Tense::Future, Voice::Passive)
+            }
+            _ if (tva.tense, tva.voice) == (Tense::Future, Voice::Passive) => {
+                match self.find_stem(tva) {
+                    Stem::FutPass(_) => true,
+                    Stem::AorPass(s) | Stem::AorPass2(s) => self.unaugmented(s).is_some(),
+                    _ => false,
+                }
+            }
+            stems => stems.iter().any(|s| s.system() == tva.system()),
+        }
+    }
+
+    /// The tvas the verb has stems for, less those it lacks: the active of
+    /// a system whose principal part is middle, as a deponent's present
+    /// is, and a deponent's middle where the part is active; ἔρχομαι has
+    /// ppi, fmi and aai.
+    pub fn default_reqs(&self) -> Vec<Tva> {
+        Tva::all()
+            .filter(|tva| self.has_stem_for(*tva) || self.given(*tva))
+            .filter(|tva| !self.lacks(*tva))
+            .filter(|tva| {
+                !(self.passive_deponent && tva.system() == "aorpass" && tva.tense == Tense::Aorist)
+            })
+            .collect()
+    }
+
+    // Whether the overrides give all six forms of the tva, as a lexicon
+    // entry does for a paradigm no stem makes, e.g. εἰμί's present or a
+    // root aorist such as ἔβην.
+    fn given(&self, tva: Tva) -> bool {
+        CELLS.iter().all(|cell| {
+            self.overrides
+                .contains_key(&format!("{}.{}", tva.shared(), cell))
+        })
+    }
+
+    // Whether the voice of the tva's principal part, or failing one the
+    // present's, rules the tva out.
+    fn lacks(&self, tva: Tva) -> bool {
+        self.ruled_out_by(tva).is_some()
+    }
+
+    // The system and voice of the principal part that rules the tva out,
+    // if one does: a middle part rules out the active, and a deponent's
+    // active part the middle.
+    fn ruled_out_by(&self, tva: Tva) -> Option<(&'static str, Voice)> {
+        let ruled_out = match self.voices.get(tva.system()) {
+            Some(Voice::Middle) => tva.is_active(),
+            Some(Voice::Active) => self.deponent && tva.voice == Voice::Middle,
+            _ => self.deponent && tva.is_active(),
+        };
+        match self.voices.get(tva.system()) {
+            _ if !ruled_out => None,
+            Some(voice) => Some((tva.system(), *voice)),
+            None => Some(("pres", Voice::Middle)),
+        }
+    }
+
+    /// The tva whose stem and endings make the forms of another: a passive
+    /// deponent's aorist is made as an aorist passive, though its paradigm
+    /// stays the verb's aorist; every other tva is made as itself.
+    pub fn built_as(&self, tva: Tva) -> Tva {
+        if self.passive_deponent && (tva.tense, tva.voice) == (Tense::Aorist, Voice::Middle) {
+            Tva {
+                voice: Voice::Passive,
+                ..tva
+            }
+        } else {
+            tva
+        }
+    }
+
+    // The verb as named in messages: its lemma or its first stem.
+    fn name(&self) -> String {
+        match self.stems.first() {
+            Some(stem) if self.lemma.is_empty() => format!("{}:{}", stem.system(), stem),
+            _ => self.lemma.clone(),
+        }
+    }
+
+    /// Adds the stems of the given systems, each one of
+    /// [`derive::DERIVABLE`], derived from the verb's present stem as a
+    /// regular verb's are; a system the verb already has a stem for keeps
+    /// it.
+    ///
+    /// ```
+    /// use greek_writer::Verb;
+    ///
+    /// let mut vb = Verb::new("pres:παυ").unwrap();
+    /// vb.derive(&["fut", "aor"]).unwrap();
+    /// let stems: Vec<String> = vb.stems.iter().map(|s| s.to_string()).collect();
+    /// assert_eq!(stems, ["παυ", "παυσ", "ἐπαυσ"]);
+    /// ```
+    pub fn derive(&mut self, systems: &[&str]) -> Result<(), GkVerbError> {
+        let pres = match self.stems.iter().find(|s| matches!(s, Stem::Pres(_))) {
+            Some(pres) => pres.as_str().to_string(),
+            None => return Err(GkVerbError::NoPresent(self.name())),
+        };
+        for stem in derive::derived(&pres, systems)? {
+            if !self.stems.iter().any(|s| s.system() == stem.system()) {
+                self.stems.push(stem);
+            }
+        }
+        Ok(())
+    }
+
+    /// The tva, or why the verb cannot be conjugated in it, as
+    /// [`conj_reqs`] would report it.
+    pub fn check(&self, tva: Tva) -> Result<Tva, GkVerbError> {
+        if let Some((system, voice)) = self.ruled_out_by(tva) {
+            return Err(GkVerbError::Lacks {
+                tva,
+                verb: self.name(),
+                system,
+                voice,
+                offers: self.default_reqs(),
+            });
+        }
+        if self.has_stem_for(tva) {
+            return self.check_assimilation(tva);
+        }
+        if self.given(tva) {
+            return Ok(tva);
+        }
+        if let Stem::AorPass(stem) | Stem::AorPass2(stem) = self.find_stem(tva) {
+            if tva.tense == Tense::Future && self.stems.len() > 1 {
+                return Err(GkVerbError::AugmentVowel {
+                    verb: self.name(),
+                    stem: stem.clone(),
+                });
+            }
+        }
+        Err(GkVerbError::NoStemFor {
+            tva,
+            verb: self.name(),
+            offers: self.default_reqs(),
+        })
+    }
+
+    // The tva, or the first of its endings a consonant-stem perfect
+    // middle cannot be joined to.
+    fn check_assimilation(&self, tva: Tva) -> Result<Tva, GkVerbError> {
+        if !self.assimilates(tva) {
+            return Ok(tva);
+        }
+        let stem = self.stem_for(tva);
+        let endings = self.endings.endings(tva.shared(), false);
+        match endings
+            .iter()
+            .find(|e| !e.starts_with('ν') && derive::assimilate(&stem, e).is_none())
+        {
+            Some(ending) => Err(GkVerbError::Unassimilable {
+                stem: stem.into_owned(),
+                ending: ending.to_string(),
+            }),
+            None => Ok(tva),
+        }
+    }
+
+    /// The tva's paradigm, if it has been conjugated.
+    pub fn paradigm(&self, tva: Tva) -> Option<&Paradigm> {
+        self.paradigms.get(&tva)
+    }
+
+    /// The tvas conjugated so far, with their paradigms, in the order of
+    /// [`ALL_TVAS`].
+    pub fn paradigms(&self) -> impl Iterator<Item = (Tva, &Paradigm)> {
+        self.paradigms
+            .iter()
+            .map(|(tva, paradigm)| (*tva, paradigm))
+    }
+
+    /// The conjugated paradigms as the table format prints them, in the
+    /// order of [`ALL_TVAS`] and laid out as given.
+    pub fn to_table_string(&self, layout: output::Layout) -> String {
+        let tvas: Vec<Tva> = self.paradigms.keys().copied().collect();
+        output::aligned(&output::verb_tables(self, &tvas, layout))
+    }
+
+    /// Every form of every conjugated paradigm, tva by tva in the order of
+    /// [`ALL_TVAS`], each paradigm's as [`Paradigm::forms`] gives them.
+    pub fn forms(&self) -> impl Iterator<Item = &Form> {
+        self.paradigms.values().flat_map(Paradigm::forms)
+    }
+
+    /// Keeps only the cells of the given persons and numbers in every
+    /// conjugated paradigm, e.g. those a class is learning.
+    pub fn retain_cells(&mut self, keys: &[(Person, Number)]) {
+        for paradigm in self.paradigms.values_mut() {
+            paradigm.retain(keys);
+        }
+    }
+
+    /// Rewrites every generated form, e.g. into another encoding.
+    pub fn map_forms(&mut self, f: impl Fn(&str) -> String) {
+        for paradigm in self.paradigms.values_mut() {
+            paradigm.map(&f);
+        }
+    }
+
+    /// Adds a rewritten copy of every form, e.g. a romanization.
+    pub fn romanize(&mut self, f: impl Fn(&str) -> String) {
+        for paradigm in self.paradigms.values_mut() {
+            paradigm.romanize(&f);
+        }
+    }
+
+    /// Labels every form with what it is, spelled out in full.
+    pub fn label_forms(&mut self) {
+        for paradigm in self.paradigms.values_mut() {
+            paradigm.label();
+        }
+    }
+
+    /// Rewrites each form from its augment, stem and ending, e.g. to colour
+    /// them; forms that cannot be segmented are left alone.
+    pub fn map_segments(&mut self, f: impl Fn(&str, &str, &str) -> String) {
+        let mut paradigms = std::mem::take(&mut self.paradigms);
+        for (tva, paradigm) in paradigms.iter_mut() {
+            // The stem is split once for all the tva's forms.
+            let (full, split) = self.split_stem(*tva);
+            let (aug, stem) = full.split_at(split);
+            paradigm.map(|form| match unmarked(form).strip_prefix(&*full) {
+                Some(ending) => f(aug, stem, ending),
+                None => form.to_string(),
+            });
+        }
+        self.paradigms = paradigms;
+    }
+
+    // Puts any lexicon overrides for this tva in place of the generated
+    // forms.
+    fn apply_overrides(&mut self, tva: Tva) {
+        // An override may mark a movable nu or -ῃ, written as self.nu and
+        // self.style say.
+        let forms: Vec<_> = CELLS
+            .iter()
+            .zip(KEYS)
+            .filter_map(|(cell, key)| {
+                let form = self.overrides.get(&format!("{}.{}", tva.shared(), cell))?;
+                Some((key, self.variants("", form)))
+            })
+            .collect();
+        if let Some(paradigm) = self.paradigms.get_mut(&tva) {
+            for ((person, number), forms) in forms {
+                paradigm.set_variants(person, number, forms);
+            }
+        }
+    }
+
+    /// A stem as typed, e.g. "aor2:ἐλαβ", once its Greek has been checked.
+    /// A stem with no system, e.g. "παυ", is a present stem.
+    pub fn parse_stem(s: &str) -> Result<Stem, GkVerbError> {
+        // Stems are kept in NFC, so that e.g. a decomposed ἀ typed as α plus
+        // a combining breathing compares equal to the precomposed one.
+        let s: String = s.trim().nfc().collect();
+        let (system, stem) = s.split_once(':').unwrap_or(("pres", s.as_str()));
+        if stem.contains(':') {
+            return Err(GkVerbError::StemSpec(s.clone()));
+        }
+        let stem = stem.to_string();
+        let kind: fn(String) -> Stem = match system {
+            "pres" => Stem::Pres,
+            "fut" => Stem::Fut,
+            "aor" => Stem::Aor,
+            "aor2" => Stem::Aor2,
+            "perf" => Stem::Perf,
+            "perfmid" => Stem::PerfMid,
+            "aorpass" => Stem::AorPass,
+            "aorpass2" => Stem::AorPass2,
+            "futpass" => Stem::FutPass,
+            _ => return Err(GkVerbError::UnknownSystem(s.clone())),
+        };
+        check_greek(&stem)?;
+        let stem = kind(stem);
+        // An aorist stem carries its augment, which is no stem by itself.
+        if matches!(stem.system(), "aor" | "aorpass")
+            && AUGMENTS.iter().any(|(aug, _)| *aug == stem.as_str())
+        {
+            return Err(GkVerbError::AugmentOnly(s));
+        }
+        Ok(stem)
+    }
+
+    // Builds the rows for a paradigm, expanding variant endings per self.nu
+    // and self.style.
+    fn inflect(&self, tva: Tva, stem: &str, endings: &[&str]) -> Paradigm {
+        let cells = endings
+            .iter()
+            .map(|ending| self.variants(stem, ending))
+            .collect();
+        Paradigm::new(tva, cells)
+    }
+
+    fn variants(&self, stem: &str, ending: &str) -> Vec<String> {
+        if let Some(bare) = ending.strip_suffix('ῃ') {
+            return match self.style {
+                Style::Eta => vec![[stem, ending].concat()],
+                Style::Ei => vec![[stem, bare, "ει"].concat()],
+                Style::Both => vec![[stem, ending].concat(), [stem, bare, "ει"].concat()],
+            };
+        }
+        let bare = match ending.strip_suffix("(ν)") {
+            Some(bare) => bare,
+            None => return vec![[stem, ending].concat()],
+        };
+        match self.nu {
+            Nu::Omit => vec![[stem, bare].concat()],
+            Nu::Show => vec![[stem, bare, "ν"].concat()],
+            Nu::Both => vec![[stem, ending].concat()],
+            Nu::Rows => vec![[stem, bare].concat(), [stem, bare, "ν"].concat()],
+        }
+    }
+
+    // Adds the tva's paradigm, its endings from the verb's ending set.
+    fn conj(&mut self, tva: Tva) {
+        // The overrides then fill in every cell.
+        if self.given(tva) && !self.has_stem_for(tva) {
+            self.paradigms
+                .insert(tva, Paradigm::new(tva, vec![Vec::new(); 6]));
+            return;
+        }
+        let stem = self.full_stem(tva);
+        let endings = self
+            .endings
+            .endings(self.built_as(tva).shared(), self.is_second(tva));
+        if self.assimilates(tva) {
+            let cells = endings
+                .iter()
+                .map(|ending| self.assimilated(tva, &stem, ending))
+                .collect();
+            self.paradigms.insert(tva, Paradigm::new(tva, cells));
+            return;
+        }
+        let mut paradigm = match self.contracted(tva) {
+            Some(vowel) => {
+                let endings = match derive::athematic(vowel, self.built_as(tva).shared()) {
+                    Some(endings) if self.class == Class::Athematic => endings,
+                    _ => endings.map(|ending| derive::contract(vowel, ending)),
+                };
+                let endings: Vec<&str> = endings.iter().map(String::as_str).collect();
+                self.inflect(tva, &stem, &endings)
+            }
+            None => self.inflect(tva, &stem, &endings),
+        };
+        paradigm.set_voice(self.built_as(tva).voice);
+        self.paradigms.insert(tva, paradigm);
+    }
+
+    // Whether the tva is of the perfect middle system and its stem ends in
+    // a consonant, which assimilates to the endings.
+    fn assimilates(&self, tva: Tva) -> bool {
+        tva.system() == "perfmid"
+            && self
+                .stem_for(tva)
+                .chars()
+                .last()
+                .is_some_and(|c| !derive::is_vowel(c))
+    }
+
+    // A form of a consonant-stem perfect or pluperfect middle: the stem
+    // assimilated to the ending (γεγραψαι, ἐγεγραπτο), or in the 3pl,
+    // which would put ν after the consonant, the participle with εἰσί(ν)
+    // or ἦσαν (γεγραμμενοι εἰσι(ν)).
+    fn assimilated(&self, tva: Tva, stem: &str, ending: &str) -> Vec<String> {
+        if ending.starts_with('ν') {
+            let participle = derive::assimilate(&self.stem_for(tva), "μενοι").unwrap_or_default();
+            let copula = match tva.tense {
+                Tense::Pluperfect => " ἠσαν",
+                _ => " εἰσι(ν)",
+            };
+            return self.variants(&participle, copula);
+        }
+        vec![derive::assimilate(stem, ending).unwrap_or_default()]
+    }
+
+    // The vowel a contracted stem ends in, which contracts with each
+    // ending: that of a contract verb's present (τιμα, ποιε, δηλο), or ε in
+    // a contracted future's μενε (μενῶ, μενεῖς) or an Attic future's
+    // νομιε, α in βιβα (βιβῶ, βιβᾷς). An athematic present's vowel
+    // (διδο, τιθε, ἱστα, δεικνυ) is likewise left to its endings.
+    fn contracted(&self, tva: Tva) -> Option<char> {
+        let vowels: &[char] = match tva.tense {
+            Tense::Present | Tense::Imperfect if self.class == Class::Contract => &['α', 'ε', 'ο'],
+            Tense::Present | Tense::Imperfect if self.class == Class::Athematic => {
+                &['α', 'ε', 'ο', 'υ']
+            }
+            Tense::Future if tva.voice != Voice::Passive => &['ε', 'α'],
+            _ => return None,
+        };
+        self.stem_for(tva)
+            .chars()
+            .last()
+            .filter(|c| vowels.contains(c))
+    }
+
+    // The stem a tva's endings are added to, augment included and any
+    // contracted vowel left to the endings.
+    fn full_stem(&self, tva: Tva) -> Cow<'_, str> {
+        let stem = self.augmented(tva);
+        match self.contracted(tva) {
+            Some(vowel) => {
+                let end = stem.len() - vowel.len_utf8();
+                match stem {
+                    Cow::Borrowed(stem) => Cow::Borrowed(&stem[..end]),
+                    Cow::Owned(mut stem) => {
+                        stem.truncate(end);
+                        Cow::Owned(stem)
+                    }
+                }
+            }
+            None => stem,
+        }
+    }
+
+    // The tva's stem with the augment it takes.
+    fn augmented(&self, tva: Tva) -> Cow<'_, str> {
+        let stem = self.stem_for(tva);
+        match tva.tense {
+            Tense::Imperfect => {
+                let (aug, rest) = Verb::aug_and_stem(&stem);
+                Cow::Owned(aug + &rest)
+            }
+            Tense::Pluperfect => Cow::Owned(Verb::pluperfect(&stem)),
+            _ => stem,
+        }
+    }
+
+    /// Splits a generated form into augment, stem and ending, e.g. ἐπαυον ->
+    /// ("ἐ", "παυ", "ον"); the augment is empty in unaugmented tenses. A
+    /// temporal augment is the lengthened initial vowel (ἠ-γ-ον). A form
+    /// already segmented is read without its marks. A form not built on the
+    /// tva's stem, such as an override, gives None.
+    pub fn segments(&self, tva: Tva, form: &str) -> Option<(String, String, String)> {
+        let form = unmarked(form);
+        let (full, split) = self.split_stem(tva);
+        let ending = form.strip_prefix(&*full)?;
+        let (aug, stem) = full.split_at(split);
+        Some((aug.to_string(), stem.to_string(), ending.to_string()))
+    }
+
+    // The tva's full stem, and where in it the augment ends.
+    fn split_stem(&self, tva: Tva) -> (Cow<'_, str>, usize) {
+        let full = self.full_stem(tva);
+        // A contracted vowel is left out of the full stem but not of the
+        // stems the augment is found by comparing.
+        let augmented = self.augmented(tva);
+        let bare = match tva.tense {
+            Tense::Imperfect => Cow::Owned(Verb::aug_and_stem(&self.stem_for(tva)).1),
+            Tense::Pluperfect => self.stem_for(tva),
+            Tense::Aorist => match self.unaugmented(&augmented) {
+                Some(bare) => Cow::Owned(bare),
+                None => return (full, 0),
+            },
+            _ => return (full, 0),
+        };
+        // The augment is what the stem has in front of the tail it shares
+        // with the unaugmented stem.
+        let common: usize = augmented
+            .chars()
+            .rev()
+            .zip(bare.chars().rev())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a.len_utf8())
+            .sum();
+        let split = if bare == augmented || common == 0 {
+            0
+        } else {
+            (augmented.len() - common).min(full.len())
+        };
+        (full, split)
+    }
+
+    // A perfect that begins with a vowel (ἐνηνοχ, εἰληφ) already looks
+    // augmented and takes no further augment in the pluperfect.
+    fn pluperfect(stem: &str) -> String {
+        match stem.chars().next() {
+            Some(c) if derive::is_vowel(c) => stem.to_string(),
+            _ => {
+                let (aug, rest) = Verb::aug_and_stem(stem);
+                format!("{}{}", aug, rest)
+            }
+        }
+    }
+
+    fn aug_and_stem(stem: &str) -> (String, String) {
+        let (_, aug, rest) = Verb::split_initial(stem);
+        (aug, rest)
+    }
+
+    // Splits a stem into its initial, what that initial becomes when
+    // augmented, and the rest: παυ -> ("", "ἐ", "παυ"), ἀγ -> ("ἀ", "ἠ", "γ"),
+    // ῥιπτ -> ("ῥ", "ἐρρ", "ιπτ"). It works on the decomposed stem, so a
+    // breathing is found whether it was typed precomposed or as a combining
+    // mark; accents on the initial are dropped.
+    fn split_initial(stem: &str) -> (String, String, String) {
+        let chars: Vec<char> = stem.nfc().collect::<String>().nfd().collect();
+        let is_mark = |c: &char| ('\u{0300}'..='\u{036f}').contains(c);
+        let marks_from = |i: usize| {
+            chars[i..]
+                .iter()
+                .take_while(|c| is_mark(c))
+                .copied()
+                .collect::<Vec<char>>()
+        };
+        let nfc = |cs: &[char]| cs.iter().collect::<String>().nfc().collect::<String>();
+        let first = match chars.first() {
+            Some(&c) => c,
+            None => return (String::new(), "ἐ".to_string(), String::new()),
+        };
+        let first_marks = marks_from(1);
+        let mut end = 1 + first_marks.len();
+        if !derive::is_vowel(first) {
+            if first == 'ρ' {
+                return (nfc(&chars[..end]), "ἐρρ".to_string(), nfc(&chars[end..]));
+            }
+            return (String::new(), "ἐ".to_string(), nfc(&chars));
+        }
+        // A following ι or υ makes a diphthong unless the first vowel carries
+        // the breathing or the second a diaeresis.
+        let mut second = None;
+        let mut marks = first_marks.clone();
+        if first_marks.is_empty() && matches!(first, 'α' | 'ε' | 'ο') {
+            if let Some(&next) = chars.get(end) {
+                let next_marks = marks_from(end + 1);
+                if matches!(next, 'ι' | 'υ') && !next_marks.contains(&'\u{0308}') {
+                    second = Some(next);
+                    end += 1 + next_marks.len();
+                    marks = next_marks;
+                }
+            }
+        }
+        let breathing: String = marks
+            .iter()
+            .filter(|c| matches!(c, '\u{0313}' | '\u{0314}'))
+            .collect();
+        let subscript = marks.contains(&'\u{0345}');
+        let (vowel, glide, iota) = match (first, second) {
+            ('α', None) | ('ε', None) => ('η', None, subscript),
+            ('ο', None) => ('ω', None, subscript),
+            ('α', Some('ι')) => ('η', None, true),
+            ('ο', Some('ι')) => ('ω', None, true),
+            ('α', Some('υ')) | ('ε', Some('υ')) => ('η', Some('υ'), false),
+            (v, g) => (v, g, subscript),
+        };
+        let mut aug = String::new();
+        aug.push(vowel);
+        match glide {
+            Some(g) => {
+                aug.push(g);
+                aug.push_str(&breathing);
+            }
+            None => {
+                aug.push_str(&breathing);
+                if iota {
+                    aug.push('\u{0345}');
+                }
+            }
+        }
+        (nfc(&chars[..end]), aug.nfc().collect(), nfc(&chars[end..]))
+    }
+
+    /// The reverse of aug_and_stem, for stems taken from augmented principal
+    /// parts. Where the present stem is known its initial vowel is restored;
+    /// otherwise ἠ- may hide ἀ- or ἐ-, and a stem that could be either
+    /// gives None.
+    ///
+    /// ```
+    /// use greek_writer::Verb;
+    ///
+    /// assert_eq!(Verb::unaugment("ἠχθ", Some("ἀγ")).as_deref(), Some("ἀχθ"));
+    /// assert_eq!(Verb::unaugment("ὠφθ", None).as_deref(), Some("ὀφθ"));
+    /// assert_eq!(Verb::unaugment("ἠνεχθ", Some("φερ")), None);
+    /// ```
+    pub fn unaugment(stem: &str, pres: Option<&str>) -> Option<String> {
+        if let Some(pres) = pres {
+            let (initial, aug, _) = Verb::split_initial(pres);
+            if let Some(tail) = stem.strip_prefix(aug.as_str()) {
+                return Some(format!("{}{}", initial, tail));
+            }
+        }
+        match Verb::unaugmentings(stem).as_slice() {
+            [one] => Some(one.clone()),
+            _ => None,
+        }
+    }
+
+    /// Every stem an augmented stem could have been before the augment,
+    /// without the present to choose among them: ἠγ -> ἀγ or ἐγ, ὠφθ ->
+    /// ὀφθ, ἐλυθ -> λυθ. A stem that begins with no augment is its own.
+    pub fn unaugmentings(stem: &str) -> Vec<String> {
+        for (aug, unaugs) in AUGMENTS.iter() {
+            if let Some(rest) = stem.strip_prefix(aug) {
+                return unaugs
+                    .iter()
+                    .map(|unaug| format!("{}{}", unaug, rest))
+                    .collect();
+            }
+        }
+        vec![stem.to_string()]
+    }
+}
+
+/// The conjugated paradigms as the table format prints them, persons down
+/// the side.
+impl fmt::Display for Verb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table_string(output::Layout::Paradigm))
+    }
+}
+
+// Each augment, and what the stem may have begun with before it.
+const AUGMENTS: [(&str, &[&str]); 12] = [
+    ("ἐρρ", &["ῥ"]),
+    ("ηὐ", &["αὐ", "εὐ"]),
+    ("ηὑ", &["αὑ", "εὑ"]),
+    ("ᾐ", &["αἰ", "εἰ"]),
+    ("ᾑ", &["αἱ", "εἱ"]),
+    ("ἠ", &["ἀ", "ἐ"]),
+    ("ἡ", &["ἁ", "ἑ"]),
+    ("ᾠ", &["οἰ"]),
+    ("ᾡ", &["οἱ"]),
+    ("ὠ", &["ὀ"]),
+    ("ὡ", &["ὁ"]),
+    ("ἐ", &[""]),
+];
+
+// The tense system of each principal part, in order.
+const PART_SYSTEMS: [&str; 6] = ["pres", "fut", "aor", "perf", "perfmid", "aorpass"];
+
+// What a verb with no stem for a tva finds, as one whose every form the
+// overrides give does.
+static NO_STEM: Stem = Stem::Pres(String::new());
+
+// A principal-part ending and the kind of stem left when it is cut off.
+type Cut = (&'static str, fn(String) -> Stem);
+
+/// What --segmented puts between augment, stem and ending.
+pub const SEGMENT_MARK: &str = "-";
+
+/// The tenses named in parses, for choosing forms by tense.
+pub const TENSES: [&str; 6] = [
+    "present",
+    "imperfect",
+    "future",
+    "aorist",
+    "perfect",
+    "pluperfect",
+];
+
+/// Person and number of each of the six cells, in order.
+pub const CELLS: [&str; 6] = ["1sg", "2sg", "3sg", "1pl", "2pl", "3pl"];
+
+/// Every tva, in the order they are printed.
+pub const ALL_TVAS: [&str; 14] = [
+    "pai", "ppi", "iai", "ipi", "fai", "fmi", "fpi", "aai", "ami", "api", "pfai", "pfpi", "plai",
+    "plpi",
+];
+
+/// The present, imperfect, perfect and pluperfect middle: the forms of
+/// ppi, ipi, pfpi and plpi, labelled as middle.
+pub const MIDDLE_TVAS: [&str; 4] = ["pmi", "imi", "pfmi", "plmi"];
+
+/// Conjugates the verb in each of the tvas, applying any lexicon overrides.
+/// A tva the verb has no stem for is an error, and then none is conjugated.
+pub fn conj_reqs(vb: &mut Verb, reqs: &[Tva]) -> Result<(), GkVerbError> {
+    for req in reqs {
+        vb.check(*req)?;
+    }
+    for &req in reqs {
+        vb.conj(req);
+        vb.apply_overrides(req);
+    }
+    Ok(())
+}
+
+/// The tvas of a comma-separated list such as "pai,aai", or every tva the
+/// verb has for "all" or nothing.
+pub fn parse_tvas(vb: &Verb, tvas: &str) -> Result<Vec<Tva>, GkVerbError> {
+    match tvas.trim() {
+        "" => Ok(vb.default_reqs()),
+        tvas if tvas.eq_ignore_ascii_case("all") => Ok(vb.default_reqs()),
+        tvas => tvas.split(',').map(|tva| tva.trim().parse()).collect(),
+    }
+}
+
+/// Conjugates a verb, given as [`Verb::from_input`] takes it, in the tvas
+/// listed as [`parse_tvas`] reads them, and gives the paradigms as
+/// --format json prints them.
+pub fn conjugate_json(
+    input: &str,
+    tvas: &str,
+    entries: &[lexicon::Entry],
+) -> Result<String, GkVerbError> {
+    let mut vb = Verb::from_input(input, entries)?;
+    let reqs = parse_tvas(&vb, tvas)?;
+    conj_reqs(&mut vb, &reqs)?;
+    Ok(output::json(&[(vb, reqs)]))
+}
+
+// A form without the marks --segmented puts between its parts.
+fn unmarked(form: &str) -> Cow<'_, str> {
+    if form.contains(SEGMENT_MARK) {
+        Cow::Owned(form.replace(SEGMENT_MARK, ""))
+    } else {
+        Cow::Borrowed(form)
+    }
+}
+
+/// Drops acute, grave and circumflex accents but keeps breathings, iota
+/// subscript and diaeresis.
+pub fn strip_accents(s: &str) -> String {
+    s.nfd()
+        .filter(|c| !matches!(c, '\u{0300}' | '\u{0301}' | '\u{0342}'))
+        .nfc()
+        .collect()
+}
+
+/// A form as compared when diacritics are not to count: composed, without
+/// accents and, if strip_breathings is set, without breathings either, and
+/// ending in ς however its final sigma was typed. Iota subscript and
+/// diaeresis are kept.
+///
+/// ```
+/// use greek_writer::{forms_equal_lenient, normalize_for_match};
+///
+/// assert_eq!(normalize_for_match("ἔλυσας", false), "ἐλυσας");
+/// assert_eq!(normalize_for_match("ἔλυσας", true), "ελυσας");
+/// assert!(forms_equal_lenient("ἐλύσω", "ελυσω", true));
+/// assert!(!forms_equal_lenient("ἐλύσω", "ελυσω", false));
+/// ```
+pub fn normalize_for_match(form: &str, strip_breathings: bool) -> String {
+    let form: String = form
+        .trim()
+        .nfd()
+        .filter(|c| !matches!(c, '\u{0300}' | '\u{0301}' | '\u{0342}'))
+        .filter(|c| !(strip_breathings && matches!(c, '\u{0313}' | '\u{0314}')))
+        .nfc()
+        .collect();
+    match form.strip_suffix('σ') {
+        Some(rest) => format!("{}ς", rest),
+        None => form,
+    }
+}
+
+/// Whether two forms are the same but for accents and, if strip_breathings
+/// is set, breathings; see [`normalize_for_match`].
+pub fn forms_equal_lenient(a: &str, b: &str, strip_breathings: bool) -> bool {
+    normalize_for_match(a, strip_breathings) == normalize_for_match(b, strip_breathings)
+}
+
+/// Rejects input that is not polytonic Greek, which would otherwise be glued
+/// to the endings and printed as a plausible-looking paradigm. The usual
+/// mistake is typing Beta Code or a transliteration without saying so.
+pub fn check_greek(s: &str) -> Result<(), GkVerbError> {
+    if s.is_empty() {
+        return Err(GkVerbError::Empty);
+    }
+    let input = || s.to_string();
+    let mut after_letter = false;
+    for c in s.nfd() {
+        if ('\u{0300}'..='\u{036f}').contains(&c) {
+            if !after_letter || !GREEK_MARKS.contains(&c) {
+                return Err(GkVerbError::StrayMark {
+                    mark: c,
+                    input: input(),
+                });
+            }
+        } else if c.is_ascii_alphabetic() || "()/\\=|*+".contains(c) {
+            return Err(GkVerbError::NotGreek(input()));
+        } else if c.is_ascii_digit() {
+            return Err(GkVerbError::Digit {
+                digit: c,
+                input: input(),
+            });
+        } else if ('\u{0370}'..='\u{03ff}').contains(&c) && c.is_alphabetic() {
+            after_letter = true;
+        } else {
+            return Err(GkVerbError::Character {
+                character: c,
+                input: input(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// An error in a lexicon entry, prefixed with its lemma.
+fn in_entry(entry: &lexicon::Entry, error: GkVerbError) -> GkVerbError {
+    GkVerbError::Entry {
+        lemma: entry.lemma.clone(),
+        error: Box::new(error),
+    }
+}
+
+// Breathings, accents, diaeresis and iota subscript.
+const GREEK_MARKS: [char; 7] = [
+    '\u{0300}', '\u{0301}', '\u{0308}', '\u{0313}', '\u{0314}', '\u{0342}', '\u{0345}',
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The forms of a tva, cell by cell, variants separated by " / ".
+    fn forms(vb: &mut Verb, tva: &str) -> Vec<String> {
+        let tva: Tva = tva.parse().unwrap();
+        conj_reqs(vb, &[tva]).unwrap();
+        let paradigm = vb.paradigm(tva).unwrap();
+        KEYS.iter()
+            .map(|&(person, number)| {
+                let variants: Vec<&str> = paradigm
+                    .variants(person, number)
+                    .iter()
+                    .map(|form| form.text.as_str())
+                    .collect();
+                variants.join(" / ")
+            })
+            .collect()
+    }
+
+    fn parts(parts: &str) -> Verb {
+        Verb::from_parts(&parts.split(',').collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn assimilates_consonant_perfect_middles() {
+        let mut vb = parts("γράφω,γράψω,ἔγραψα,γέγραφα,γέγραμμαι,ἐγράφην");
+        assert_eq!(
+            forms(&mut vb, "pfpi"),
+            [
+                "γεγραμμαι",
+                "γεγραψαι",
+                "γεγραπται",
+                "γεγραμμεθα",
+                "γεγραφθε",
+                "γεγραμμενοι εἰσι"
+            ]
+        );
+        assert_eq!(
+            forms(&mut vb, "plpi"),
+            [
+                "ἐγεγραμμην",
+                "ἐγεγραψο",
+                "ἐγεγραπτο",
+                "ἐγεγραμμεθα",
+                "ἐγεγραφθε",
+                "γεγραμμενοι ἠσαν"
+            ]
+        );
+        let mut vb = Verb::new("perfmid:πεπραγ").unwrap();
+        assert_eq!(forms(&mut vb, "pfpi")[1], "πεπραξαι");
+    }
+
+    #[test]
+    fn rejects_a_perfect_middle_with_no_assimilation_rule() {
+        let vb = Verb::new("perfmid:πεφαν").unwrap();
+        assert!(matches!(
+            vb.check("pfpi".parse().unwrap()),
+            Err(GkVerbError::Unassimilable { .. })
+        ));
+    }
+
+    #[test]
+    fn unaugments_the_future_passive() {
+        let mut vb = parts("ὁράω,ὄψομαι,εἶδον,ἑώρακα,ἑώραμαι,ὤφθην");
+        assert_eq!(forms(&mut vb, "fpi")[0], "ὀφθησομαι");
+        let mut vb = parts("ἄγω,ἄξω,ἤγαγον,ἦχα,ἦγμαι,ἤχθην");
+        assert_eq!(forms(&mut vb, "fpi")[0], "ἀχθησομαι");
+    }
+
+    #[test]
+    fn does_not_guess_an_ambiguous_augment() {
+        let vb = parts("φέρω,οἴσω,ἤνεγκα,ἐνήνοχα,ἐνήνεγμαι,ἠνέχθην");
+        let fpi: Tva = "fpi".parse().unwrap();
+        assert!(!vb.default_reqs().contains(&fpi));
+        assert!(matches!(
+            vb.check(fpi),
+            Err(GkVerbError::AugmentVowel { .. })
+        ));
+        let mut vb = Verb::from_specs("pres:φερ,aorpass:ἠνεχ,futpass:ἐνεχ").unwrap();
+        assert_eq!(forms(&mut vb, "fpi")[0], "ἐνεχθησομαι");
+    }
+
+    fn default_codes(vb: &Verb) -> Vec<&'static str> {
+        vb.default_reqs().into_iter().map(Tva::code).collect()
+    }
+
+    #[test]
+    fn takes_each_systems_voice_from_its_part() {
+        let entries = lexicon::builtin();
+        let vb = Verb::from_lemma("ἔρχομαι", entries).unwrap();
+        assert_eq!(
+            default_codes(&vb),
+            ["ppi", "ipi", "fmi", "aai", "pfai", "plai"]
+        );
+        let vb = Verb::from_lemma("γίγνομαι", entries).unwrap();
+        assert!(default_codes(&vb).contains(&"pfai"));
+        assert!(!default_codes(&vb).contains(&"aai"));
+        let vb = parts("ἀκούω,ἀκούσομαι,ἤκουσα,ἀκήκοα,-,ἠκούσθην");
+        assert!(!default_codes(&vb).contains(&"fai"));
+        assert!(default_codes(&vb).contains(&"fmi"));
+        assert!(default_codes(&vb).contains(&"ami"));
+    }
+
+    #[test]
+    fn conjugates_athematic_presents() {
+        let entries = lexicon::builtin();
+        let mut vb = Verb::from_lemma("δίδωμι", entries).unwrap();
+        vb.nu = Nu::Both;
+        assert_eq!(
+            forms(&mut vb, "pai"),
+            [
+                "διδωμι",
+                "διδως",
+                "διδωσι(ν)",
+                "διδομεν",
+                "διδοτε",
+                "διδοασι(ν)"
+            ]
+        );
+        assert_eq!(forms(&mut vb, "iai")[..3], ["ἐδιδουν", "ἐδιδους", "ἐδιδου"]);
+        assert_eq!(forms(&mut vb, "aai")[3], "ἐδομεν");
+        let mut vb = Verb::from_lemma("τίθημι", entries).unwrap();
+        assert_eq!(forms(&mut vb, "iai")[..3], ["ἐτιθην", "ἐτιθεις", "ἐτιθει"]);
+        let mut vb = Verb::from_lemma("δύναμαι", entries).unwrap();
+        assert_eq!(forms(&mut vb, "ppi")[1], "δυνασαι");
+    }
+
+    #[test]
+    fn applies_lexicon_overrides() {
+        let entries = lexicon::builtin();
+        let mut vb = Verb::from_lemma("ἔχω", entries).unwrap();
+        assert_eq!(
+            forms(&mut vb, "iai"),
+            ["εἰχον", "εἰχες", "εἰχε", "εἰχομεν", "εἰχετε", "εἰχον"]
+        );
+        vb.nu = Nu::Rows;
+        assert_eq!(forms(&mut vb, "iai")[2], "εἰχε / εἰχεν");
+        let mut vb = Verb::from_lemma("φέρω", entries).unwrap();
+        assert_eq!(forms(&mut vb, "fpi")[0], "ἐνεχθησομαι");
+    }
+
+    #[test]
+    fn rejects_an_empty_or_augment_only_stem() {
+        assert_eq!(Verb::new("aor:").unwrap_err(), GkVerbError::Empty);
+        assert_eq!(
+            Verb::new("aor:ἐ").unwrap_err(),
+            GkVerbError::AugmentOnly("aor:ἐ".to_string())
+        );
+        assert!(Verb::new("aorpass2:ἐ").is_err());
+        assert!(Verb::new("aor:ἐρρ").is_err());
+        assert!(Verb::new("aor:ἠ").is_err());
+        assert!(Verb::new("aor:ἐλυσ").is_ok());
+        assert!(Verb::new("pres:ἐ").is_ok());
+    }
+
+    #[test]
+    fn lists_every_reading_of_an_augment() {
+        assert_eq!(Verb::unaugmentings("ἠγ"), ["ἀγ", "ἐγ"]);
+        assert_eq!(Verb::unaugmentings("ὡρ"), ["ὁρ"]);
+        assert_eq!(Verb::unaugmentings("ἐλυ"), ["λυ"]);
+        assert_eq!(Verb::unaugmentings("λυ"), ["λυ"]);
+    }
+
+    #[test]
+    fn rejects_an_unknown_profile() {
+        let athenaze = Profile::from_arg("athenaze").unwrap();
+        assert_eq!((athenaze.nu, athenaze.style), (Nu::Both, Style::Ei));
+        let jact = Profile::from_arg("jact").unwrap();
+        assert_eq!((jact.nu, jact.style), (athenaze.nu, athenaze.style));
+        let err = Profile::from_arg("wheelock").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown profile wheelock; expected one of plain, athenaze, jact, hq, mastronarde"
+        );
+    }
+
+    #[test]
+    fn reports_a_bad_option_value() {
+        assert_eq!(Nu::from_arg("rows"), Ok(Nu::Rows));
+        assert_eq!(
+            Nu::from_arg("never").unwrap_err().to_string(),
+            "unknown nu never; expected one of omit, show, both, rows"
+        );
+        assert_eq!(Style::from_arg("ei"), Ok(Style::Ei));
+        assert!(Style::from_arg("eta-ei").is_err());
+        assert_eq!(Class::from_arg("athematic"), Ok(Class::Athematic));
+        assert!(Class::from_arg("irregular").is_err());
+    }
+
+    #[test]
+    fn returns_what_an_entry_passed_over() {
+        let entry = lexicon::Entry {
+            lemma: "βαίνω".to_string(),
+            aorist: "ἔβην".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            Verb::from_entry(&entry).unwrap().warnings,
+            ["cannot find the stem of ἐβην, so it is left out"]
+        );
+        let entry = lexicon::Entry {
+            lemma: "μένω".to_string(),
+            aorist: "ἔμεινα".to_string(),
+            class: "liquid".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(Verb::from_entry(&entry).unwrap().warnings.len(), 1);
+        assert!(Verb::from_lemma("λύω", lexicon::builtin())
+            .unwrap()
+            .warnings
+            .is_empty());
+    }
+
+    #[test]
+    fn takes_the_paradigms_no_stem_makes_from_the_overrides() {
+        let entries = lexicon::builtin();
+        let tvas =
+            |codes: &[&str]| -> Vec<Tva> { codes.iter().map(|c| c.parse().unwrap()).collect() };
+        let mut vb = Verb::from_lemma("εἰμί", entries).unwrap();
+        assert!(vb.warnings.is_empty());
+        assert_eq!(vb.default_reqs(), tvas(&["pai", "iai", "fmi"]));
+        let reqs = vb.default_reqs();
+        conj_reqs(&mut vb, &reqs).unwrap();
+        let pai = vb.paradigm(reqs[0]).unwrap();
+        assert_eq!(pai.get(Person::Third, Number::Sg), Some("ἐστι"));
+        assert_eq!(vb.stem_for(reqs[0]), "");
+        let fmi = vb.paradigm(reqs[2]).unwrap();
+        assert_eq!(fmi.get(Person::Third, Number::Sg), Some("ἐσται"));
+        assert_eq!(Verb::from_lemma("εἶμι", entries).unwrap().lemma, "εἶμι");
+        assert_eq!(
+            Verb::from_lemma("οἶδα", entries).unwrap().default_reqs(),
+            tvas(&["fmi", "pfai", "plai"])
+        );
+        let mut vb = Verb::from_lemma("βαίνω", entries).unwrap();
+        assert!(vb.warnings.is_empty());
+        let aai = tvas(&["aai"]);
+        conj_reqs(&mut vb, &aai).unwrap();
+        assert_eq!(
+            vb.paradigm(aai[0]).unwrap().get(Person::Third, Number::Pl),
+            Some("ἐβησαν")
+        );
+        let vb = Verb::from_lemma("γιγνώσκω", entries).unwrap();
+        assert!(vb.default_reqs().contains(&aai[0]));
+        assert!(vb.check("ami".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn reads_a_contracted_lemma_as_the_lexicon_does() {
+        let vb = Verb::from_lemma("τιμῶ", lexicon::builtin()).unwrap();
+        assert_eq!(vb.lemma, "τιμάω");
+        assert!(matches!(
+            Verb::from_lemma("τιμῶ", &[]),
+            Err(GkVerbError::Ambiguous { .. })
+        ));
+    }
+
+    #[test]
+    fn refuses_the_active_of_a_deponent() {
+        let entries = lexicon::builtin();
+        let mut vb = Verb::from_lemma("βούλομαι", entries).unwrap();
+        let pai: Tva = "pai".parse().unwrap();
+        let fai: Tva = "fai".parse().unwrap();
+        match conj_reqs(&mut vb, &[pai, fai]) {
+            Err(GkVerbError::Lacks { tva, offers, .. }) => {
+                assert_eq!(tva, pai);
+                assert_eq!(offers, vb.default_reqs());
+                assert!(!offers.contains(&pai) && !offers.contains(&fai));
+            }
+            other => panic!("{:?}", other),
+        }
+        let vb = Verb::from_lemma("ἔρχομαι", entries).unwrap();
+        assert!(vb.check(pai).is_err());
+        assert!(vb.check(fai).is_err());
+        assert!(vb.check("ami".parse().unwrap()).is_err());
+        assert!(vb.check("aai".parse().unwrap()).is_ok());
+        assert_eq!(
+            vb.check(fai).unwrap_err().to_string(),
+            "ἔρχομαι has no fai: its fut principal part is middle in form; it has ppi, ipi, fmi, aai, pfai, plai"
+        );
+    }
+}
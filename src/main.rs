@@ -1,384 +1,2579 @@
-use clap::{App, Arg};
-use csv::Writer;
+use clap::{App, AppSettings, Arg, ArgMatches, Shell, SubCommand};
+use csv::WriterBuilder;
+use greek_writer::endings::EndingTable;
+use greek_writer::{
+    betacode, cell_key, check_greek, conj_reqs, derive, lexicon, output, parse, parse_tvas,
+    strip_accents, translit, EndingSet, Form, GkVerbError, Nu, Paradigm, Profile, Style, Tva, Verb,
+    ALL_TVAS, CELLS, MIDDLE_TVAS, NUS, PROFILES, SEGMENT_MARK, STYLES, TENSES,
+};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::error::Error;
 use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use unicode_normalization::UnicodeNormalization;
+
+mod batch;
+mod config;
+mod grade;
+mod progress;
+mod quiz;
+mod repl;
+mod selftest;
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "tui")]
+mod tui;
+mod verify;
+mod worksheet;
+#[cfg(feature = "xlsx")]
+mod xlsx;
 
 // gkverb
 // Usage:
 //
 // Conjugate a given tense, voice and mood of a verb and print it.
 // gkverb -- stem p --tva pai
+// Read stem specs from stdin, one per line, each optionally with its tvas.
+// printf 'pres:παυ pai\naor:ἐλυσ\n' | gkverb --stem - --all
+// Conjugate several stems in one run; with -c they all go into one csv.
+// gkverb --stem pres:παυ --stem pres:λυ --stem aor:ἐπαυσ --all -c
 // Conjugate a given tense, voice and mood of a verb and write it to csv.
 // gkverb --stem a --tva api --outfile FILE.csv
-// Conjugate all the parts of all the verbs from a csv file and write the forms to csv.
-// gkverb --infile FILE.csv --outfile FILE.csv
-
-fn main() -> Result<(), Box<dyn Error>> {
-    let matches = App::new("greek-verb-writer")
-        .arg(
-            Arg::with_name("stem")
-                .help("Tense and stem, e.g. pres:παυ")
-                .short("s")
-                .long("stem")
-                .multiple(false)
-                .required(true)
-                .takes_value(true),
-        )
-        .arg(
-            Arg::with_name("tva")
-                .help("Tense, voice and mood, e.g. pai,ppi")
-                .short("t")
-                .long("tva")
-                .multiple(true)
-                .use_delimiter(true)
-                .required(false)
-                .takes_value(true),
-        )
-        .arg(
-            Arg::with_name("all")
-                .help("Print all combinations of tense, voice and mood for the given stem")
-                .short("a")
-                .long("all")
-                .multiple(false)
-                .required_unless("tva")
-                .takes_value(false),
-        )
-        .arg(
-            Arg::with_name("to-csv")
-                .help("Print to csv")
-                .short("c")
-                .long("to-csv"),
-        )
-        .get_matches();
-
-    if let Some(stm) = matches.value_of("stem") {
-        let stem = stm;
-        let mut vb = Verb::new(stem);
-        // vb.conj_pai();
-        // vb.pai.print();
-
-        let reqs: Vec<&str> = if let Some(tvas) = matches.values_of("tva") {
-            tvas.collect()
-        } else {
-            match vb.stem {
-                Stem::Pres(_) => vec!["pai", "ppi", "iai", "ipi"],
-                Stem::Fut(_) => vec!["fai", "fmi", "fpi"],
-                Stem::Aor(_) => vec!["aai", "ami", "api"],
-                Stem::Perf(_) => vec!["pfai", "pfpi", "plai", "plpi"],
+// Write only the csv, reporting each verb and its tvas as it goes.
+// gkverb --infile verbs.csv --outfile FILE.csv --all --quiet -v
+// Overwrite FILE.csv if it is already there.
+// gkverb --stem a --tva api --outfile FILE.csv --force
+// Stream the csv to stdout, e.g. into xsv or mlr.
+// gkverb --lemma λύω --all --outfile - --csv-format long | xsv table
+// Add to the end of FILE.csv, building it up over several runs.
+// gkverb --lemma λύω --all --outfile FILE.csv --append
+// Conjugate a regular verb from its present stem, deriving the future,
+// aorist and perfect.
+// gkverb --stem pres:παυ --derive fut,aor,perf --all
+// Conjugate one verb from all its stems at once.
+// gkverb --verb "pres:παυ,fut:παυσ,aor:ἐπαυσ,perf:πεπαυκ" --all
+// Conjugate every tense system of a verb from its six principal parts.
+// gkverb --pp "παύω,παύσω,ἔπαυσα,πέπαυκα,πέπαυμαι,ἐπαύθην" --all
+// Conjugate a verb from its dictionary form alone; verbs in the built-in
+// lexicon use their real principal parts, others are derived as regular.
+// gkverb --lemma παύω --all
+// Type stems, lemmas or principal parts in Beta Code.
+// gkverb --input-encoding betacode --lemma a)kou/w --all
+// Write the generated forms in Beta Code.
+// gkverb --stem pres:παυ --all --output-encoding betacode
+// Print the bare comma-separated rows instead of labelled tables, e.g. for
+// further processing.
+// gkverb --lemma λύω --all --format plain
+// Mark augment, stem and ending with hyphens (ἐ-παυ-ον) in every format.
+// gkverb --lemma παύω --all --segmented
+// Colour the augment, stem and ending, e.g. when projecting in class; by
+// default only on a terminal and unless NO_COLOR is set.
+// gkverb --lemma λύω --all --color always
+// Print tab-separated rows, or write a tab-separated file (a .tsv outfile
+// is tab-separated unless --delimiter says otherwise).
+// gkverb --lemma λύω --all --format tsv
+// gkverb --lemma λύω --all --outfile λύω.tsv
+// Put a verb's tvas side by side, persons down the side, as csv on stdout.
+// gkverb --lemma λύω --tva pai,iai,aai --layout by-person -c
+// Put the active, middle and passive of each tense side by side, one
+// table per tense.
+// gkverb --lemma λύω --all --layout by-voice
+// Write one labelled row per form (lemma, stem, tense, voice, mood, person,
+// number, form, and for a verb given by lemma its gloss from the lexicon)
+// for filtering in a spreadsheet.
+// gkverb --lemma λύω --all --outfile λύω.csv --csv-format long
+// Print paradigms as JSON, with tense, voice, mood and each form's person
+// and number, and the verb's gloss if the lexicon has one.
+// gkverb --lemma λύω --all --format json
+// Stream an object per form, one per line, e.g. into jq or a data loader.
+// gkverb --lemmas verbs.txt --all --format jsonl | jq -c 'select(.tva == "api")'
+// Print each paradigm as a Markdown table, for course notes and wikis.
+// gkverb --lemma λύω --tva pai,aai --format markdown
+// Print booktabs tables for a handout typeset with XeLaTeX.
+// gkverb --lemma λύω --all --format latex
+// Print HTML tables, classed by tense, voice and mood, for a course page.
+// gkverb --lemma λύω --all --format html
+// Link each lemma to its Logeion entry and each form to Perseus's
+// analysis of it, for an interactive course page; markdown too.
+// gkverb --lemma λύω --all --format html --links
+// Write one Anki note per form (form, parse, lemma, gloss, tags) for import.
+// gkverb --lemma λύω --all --format anki > λύω.txt
+// Write a TEI document, each form's @ana pointing to its morphology.
+// gkverb --lemma λύω --all --format tei > λύω.xml
+// Write a CoNLL-U fragment, each form a one-token sentence with its
+// Universal Dependencies features, to seed treebank annotation.
+// gkverb --lemma λύω --all --format conllu > λύω.conllu
+// Print term/definition pairs to paste into Quizlet's import box; the
+// term and definition are tab-separated unless --delimiter says otherwise.
+// gkverb --lemma λύω --all --format quizlet --delimiter ";"
+// Write words for a Memrise course, a level per tense system, each headed
+// "# Level N: ..." and its form/definition lines ready for bulk add.
+// gkverb --lemmas week3.txt --all --format memrise > memrise.txt
+// Write Moodle quiz questions in GIFT: for each cell, a short answer
+// asking for the form and a multiple choice asking for its parse.
+// gkverb --lemma λύω --tva pai,aai --format gift > λύω.gift
+// Write a workbook, one sheet per verb or, with --sheets system, one per
+// tense system (needs a build with --features xlsx).
+// gkverb --lemma λύω --all --format xlsx --outfile λύω.xlsx --sheets system
+// Write a spreadsheet for Kahoot's import, a question per form with three
+// of the verb's other forms as wrong answers (--features xlsx).
+// gkverb --lemmas week3.txt --tva aai,api --format kahoot --outfile week3.xlsx --time-limit 30
+// Write every form, with its lemma, tense, voice, mood, person, number
+// and dialect, to a table of an SQLite database (needs a build with
+// --features sqlite); --append adds to a database already there.
+// gkverb --lemma λύω --all --format sqlite --outfile forms.sqlite
+// Write every form of every verb in a lexicon, in all the tvas it has, to
+// a new database, e.g. for a morphological analyzer (--features sqlite).
+// gkverb build-db --lexicon verbs.toml --out forms.sqlite
+// Browse paradigms in the terminal, toggling the ending options live
+// (needs a build with --features tui).
+// gkverb --tui --lemma παύω
+// Add a romanized copy of each row, e.g. pauō, paueis, ...
+// gkverb --lemma παύω --all --transliterate ala-lc
+// Label each form in full, e.g. "3rd person plural, aorist passive
+// indicative", in whatever format is written.
+// gkverb --lemma λύω --tva api --labels
+// Leave every accent out, of lemmas as of forms, e.g. for Anki cards.
+// gkverb --lemma λύω --all --format anki --strip-accents
+// Look verbs up in your own lexicon (CSV or TOML) before the built-in one.
+// gkverb --lexicon verbs.toml --lemma φέρω --all
+// Conjugate with your own endings for some tvas, laid out as in
+// data/endings.toml.
+// gkverb --endings my_endings.toml --lemma λύω --all
+// Take default settings (profile, nu, delimiter, ...) from a config file;
+// ~/.config/gkverb/config.toml is read if it exists.
+// gkverb --config class.toml --lemma λύω --all
+// Print, or quiz, just the persons and numbers learnt so far.
+// gkverb --lemma λύω --all --persons 1sg,2sg,3sg
+// Make a gap-fill worksheet with a third of the forms blanked at random;
+// the same --seed gives the same worksheet again.
+// gkverb worksheet --lemma λύω --tva pai,aai --blank 33 --format latex
+// Blank every 3rd person, or every plural, instead.
+// gkverb worksheet --lemma λύω --all --pattern 3rd --format html -o hw.html
+// Either way an answer key, the blanks filled in and highlighted, is
+// written beside the worksheet (here hw-key.html), or where --key says.
+// Print twenty random forms with their parses, e.g. for pop-quiz slides;
+// --tense, --voice and --mood narrow the choice.
+// gkverb random --stem pres:παυ -n 20 --tense imperfect
+// Print a synopsis of one person and number in every tense and voice.
+// gkverb synopsis --pp "παύω,παύσω,ἔπαυσα,πέπαυκα,πέπαυμαι,ἐπαύθην" --person 3 --number sg
+// Print the six principal parts of a verb, labelled, or write them as csv.
+// gkverb parts --lemma λείπω
+// gkverb parts --lemma ἄγω --format csv
+// List the tva codes with their names and the stem each needs.
+// gkverb list
+// List the possible analyses of a form, e.g. to check a student's answer.
+// gkverb parse ἐπαύοντο
+// Parse a vocabulary list, one form per line or a column of a csv file,
+// writing a csv row per analysis ("unrecognized" if there is none).
+// gkverb parse --infile words.csv --column form --outfile parsed.csv
+// Find which verb and cell a form belongs to among the lexicon's verbs,
+// or in a database from build-db (--features sqlite).
+// gkverb find ἐπαύσαντο --lexicon verbs.toml
+// gkverb find ἐπαύσαντο --db forms.sqlite
+// Parse every word of a text that is a form of a lexicon verb, line by
+// line, as a reader's help with a set text.
+// gkverb scan text.txt --lexicon verbs.toml
+// Be quizzed on ten forms of a verb; accents are not checked, since the
+// forms are generated without them, and --breathings lenient lets
+// breathings go as well.
+// gkverb quiz --lemma παύω --all --count 10
+// Results are kept, so that --due asks just the forms due for review.
+// gkverb quiz --lemma παύω --all --due
+// Mark a student's csv of paradigms (a tva column and one per cell), giving
+// a score and the answers marked in student-graded.csv; with --nu rows a
+// movable nu is right whether written or not.
+// gkverb grade --answers student.csv --stem pres:παυ --tva pai,iai
+// Check the tables against the reference paradigms in data/paradigms.toml,
+// or a teacher's own in a file of the same layout.
+// gkverb selftest
+// gkverb selftest --fixtures my-paradigms.toml
+// List the forms Morpheus does not recognize, for the whole lexicon, from
+// a dump of the forms it knows or by asking a Morpheus service (--features
+// verify).
+// gkverb verify --dump morpheus-forms.txt
+// gkverb verify --endpoint "https://example.org/analysis/word?lang=grc&word={form}" --lemma λύω
+// Keep the lexicon loaded and type commands such as "pai λυ" or
+// "parse ἔλυσαν" one after another; help lists them.
+// gkverb repl
+// Answer requests such as /conjugate?stem=pres:παυ&tva=pai and
+// /parse?form=ἔλυσαν with JSON, e.g. for a web page (--features serve).
+// gkverb serve --port 8080
+// Install completions for bash (or zsh, fish, powershell, elvish).
+// gkverb completions bash > ~/.local/share/bash-completion/completions/gkverb
+// Conjugate all the verbs in a csv file (with a stem, pp or lemma column,
+// and optionally a tva column such as "api,fpi" or "all") and write the
+// forms to csv.
+// gkverb --infile FILE.csv --outfile FILE.csv --all
+// Check such a file before a long run: every problem is reported with its
+// row number, and nothing is written.
+// gkverb --infile FILE.csv --all --check
+// Conjugate every verb of a frequency list, one lemma per line, that the
+// lexicon has; the rest (nouns, say) are skipped and listed.
+// gkverb --lemmas dcc-core.txt --all --outfile core.csv
+
+fn main() {
+    if let Err(e) = run() {
+        // A reader that stops early, such as head, is not a failure.
+        if broken_pipe(&*e) {
+            return;
+        }
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+// Whether the error, or one it came from, is a write to a closed pipe.
+fn broken_pipe(e: &(dyn Error + 'static)) -> bool {
+    let mut e = Some(e);
+    while let Some(err) = e {
+        let io = match err.downcast_ref::<csv::Error>().map(csv::Error::kind) {
+            Some(csv::ErrorKind::Io(io)) => Some(io),
+            _ => err.downcast_ref::<io::Error>(),
+        };
+        if io.is_some_and(|io| io.kind() == io::ErrorKind::BrokenPipe) {
+            return true;
+        }
+        e = err.source();
+    }
+    false
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let matches = app(false).get_matches();
+
+    if let Some(matches) = matches.subcommand_matches("completions") {
+        return print_completions(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("list") {
+        return list_tvas(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("synopsis") {
+        return print_synopsis(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("parts") {
+        return print_parts(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("parse") {
+        return parse_forms(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("find") {
+        return find_forms(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("scan") {
+        return scan_text(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("random") {
+        return random_forms(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("worksheet") {
+        return make_worksheet(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("quiz") {
+        return run_quiz(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("grade") {
+        return run_grade(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("repl") {
+        return run_repl(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("selftest") {
+        return run_selftest(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("verify") {
+        return verify_forms(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("build-db") {
+        return build_db(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("serve") {
+        return run_server(matches);
+    }
+
+    let config = config::load(matches.value_of("config"))?;
+    if matches.is_present("check") {
+        return check_batch(&matches, &config);
+    }
+    if matches.is_present("tui") {
+        let verb = matches
+            .value_of("lemma")
+            .or_else(|| matches.value_of("verb"))
+            .or_else(|| matches.value_of("pp"))
+            .or_else(|| matches.value_of("stem"))
+            .unwrap_or_default();
+        let encoding = setting(&matches, &config, "input-encoding").unwrap_or("unicode");
+        let entries = load_lexicon(setting(&matches, &config, "lexicon"))?;
+        return browse(&decode_input(verb, encoding), entries);
+    }
+    let chunks = batches(&matches, &config)?;
+    let layout = output::Layout::from_arg(setting(&matches, &config, "layout"))?;
+    // A workbook is written to the outfile in place of the csv.
+    if setting(&matches, &config, "format") == Some("xlsx") {
+        let path = match matches.value_of("outfile") {
+            Some("-") | None => return Err("--format xlsx needs an --outfile to write to".into()),
+            Some(path) => path,
+        };
+        if fs::metadata(path).is_ok() && !matches.is_present("force") {
+            return Err(format!("{} already exists; pass --force to overwrite it", path).into());
+        }
+        let mut done = Vec::new();
+        for chunk in chunks {
+            done.extend(chunk?);
+        }
+        return write_xlsx(
+            &done,
+            layout,
+            matches.value_of("sheets") == Some("system"),
+            path,
+        );
+    }
+    // As is a Kahoot quiz.
+    if setting(&matches, &config, "format") == Some("kahoot") {
+        let path = match matches.value_of("outfile") {
+            Some("-") | None => return Err("--format kahoot needs an --outfile to write to".into()),
+            Some(path) => path,
+        };
+        if fs::metadata(path).is_ok() && !matches.is_present("force") {
+            return Err(format!("{} already exists; pass --force to overwrite it", path).into());
+        }
+        let mut done = Vec::new();
+        for chunk in chunks {
+            done.extend(chunk?);
+        }
+        // One of Kahoot's limits, as --time-limit allows no others.
+        let time_limit = matches.value_of("time-limit").unwrap_or("20").parse()?;
+        return write_kahoot(&done, time_limit, path);
+    }
+    // As is a database, chunk by chunk, with --append adding to one there.
+    if setting(&matches, &config, "format") == Some("sqlite") {
+        let path = match matches.value_of("outfile") {
+            Some("-") | None => return Err("--format sqlite needs an --outfile to write to".into()),
+            Some(path) => path,
+        };
+        if cfg!(not(feature = "sqlite")) {
+            return Err(NO_SQLITE.into());
+        }
+        if fs::metadata(path).is_ok() {
+            if matches.is_present("force") {
+                fs::remove_file(path).map_err(|e| format!("cannot overwrite {}: {}", path, e))?;
+            } else if !matches.is_present("append") {
+                return Err(format!(
+                    "{} already exists; pass --force to overwrite it or --append to add to it",
+                    path
+                )
+                .into());
             }
+        }
+        // The dialect is that of the endings: attic, or the --endings file's
+        // name, ionic for ionic.toml.
+        let dialect = match setting(&matches, &config, "endings") {
+            Some(endings) => Path::new(endings)
+                .file_stem()
+                .map_or(endings.into(), |stem| stem.to_string_lossy()),
+            None => "attic".into(),
         };
-        conj_reqs(&mut vb, &reqs);
-        print_reqs(&vb, &reqs);
-        if matches.is_present("to-csv") {
-            to_csv(&vb, &reqs)?;
+        for chunk in chunks {
+            write_sqlite(&chunk?, &dialect, path)?;
+        }
+        return Ok(());
+    }
+    let mut csv = None;
+    if matches.is_present("to-csv") || matches.is_present("outfile") || matches.is_present("infile")
+    {
+        // Without an --outfile the csv goes to stdout.
+        let path = matches.value_of("outfile").unwrap_or("-");
+        // A .tsv file is tab-separated unless a delimiter is given.
+        let delimiter = match (matches.occurrences_of("delimiter"), config.delimiter) {
+            (0, _) if path.ends_with(".tsv") => "\t".to_string(),
+            (0, Some(c)) => c.to_string(),
+            _ => match matches.value_of("delimiter").unwrap_or(",") {
+                "tab" | "\\t" => "\t".to_string(),
+                d => d.to_string(),
+            },
+        };
+        if delimiter.len() != 1 {
+            return Err(format!(
+                "the delimiter must be one ASCII character, not {}",
+                delimiter
+            )
+            .into());
+        }
+        let existing = if matches.is_present("append") {
+            Existing::Append
+        } else if matches.is_present("force") {
+            Existing::Overwrite
+        } else {
+            Existing::Refuse
+        };
+        // A file being appended to already has its header.
+        let started = existing == Existing::Append
+            && fs::metadata(path).map(|m| m.len() > 0).unwrap_or(false);
+        csv = Some(CsvOut {
+            path,
+            existing,
+            delimiter: delimiter.as_bytes()[0],
+            long: setting(&matches, &config, "csv-format") == Some("long"),
+            gloss: ["lemma", "infile", "lemmas"]
+                .iter()
+                .any(|arg| matches.is_present(arg)),
+            layout,
+            header: !started && !matches.is_present("no-header") && config.header != Some(false),
+            columns: None,
+            wtr: None,
+            rows: 0,
+        });
+    }
+    // With the csv going to stdout, or with --quiet, no paradigms are
+    // printed there.
+    let format = match &csv {
+        Some(csv) if csv.path == "-" => None,
+        _ if matches.is_present("quiet") => None,
+        _ => setting(&matches, &config, "format"),
+    };
+    let mut stdout = io::stdout().lock();
+    // Only verbs still to be printed are kept once their csv rows are out;
+    // JSON Lines are printed chunk by chunk, and none are kept.
+    let mut done = Vec::new();
+    for chunk in chunks {
+        let chunk = chunk?;
+        if let Some(csv) = &mut csv {
+            csv.write(&chunk)?;
+        }
+        match format {
+            Some("jsonl") => write!(stdout, "{}", output::jsonl(&chunk))?,
+            Some(_) => done.extend(chunk),
+            None => {}
+        }
+    }
+    if let Some(csv) = csv {
+        let (path, rows) = (csv.path, csv.finish()?);
+        if matches.occurrences_of("verbose") > 0 {
+            let path = if path == "-" { "stdout" } else { path };
+            eprintln!("wrote {} rows to {}", rows, path);
+        }
+    }
+    // Colour goes only to the screen, never into files.
+    let color = match matches.value_of("color") {
+        Some("always") => true,
+        Some("never") => false,
+        _ => env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && io::stdout().is_terminal(),
+    };
+    if color && matches!(format, Some("table") | Some("plain")) {
+        for (vb, _) in done.iter_mut() {
+            vb.map_segments(|aug, stem, ending| {
+                let parts: Vec<String> = [
+                    (AUGMENT_COLOR, aug),
+                    (STEM_COLOR, stem),
+                    (ENDING_COLOR, ending),
+                ]
+                .iter()
+                .filter(|(_, part)| !part.is_empty())
+                .map(|(color, part)| format!("{}{}{}", color, part, RESET_COLOR))
+                .collect();
+                parts.join(if matches.is_present("segmented") {
+                    SEGMENT_MARK
+                } else {
+                    ""
+                })
+            });
+        }
+    }
+    let links = matches.is_present("links");
+    match format {
+        None => {}
+        Some("json") => writeln!(stdout, "{}", output::json(&done))?,
+        Some("jsonl") => {}
+        Some("markdown") => write!(stdout, "{}", output::markdown(&done, layout, links))?,
+        Some("latex") => write!(stdout, "{}", output::latex(&done, layout))?,
+        Some("html") => write!(stdout, "{}", output::html(&done, layout, links))?,
+        Some("anki") => write!(stdout, "{}", output::anki(&done))?,
+        Some("tsv") => write!(stdout, "{}", output::tsv(&done, layout))?,
+        Some("tei") => write!(stdout, "{}", output::tei(&done))?,
+        Some("conllu") => write!(stdout, "{}", output::conllu(&done))?,
+        Some("memrise") => write!(stdout, "{}", output::memrise(&done))?,
+        Some("gift") => write!(stdout, "{}", output::gift(&done))?,
+        Some("quizlet") => {
+            // Quizlet's own default is a tab between term and definition.
+            let delimiter = match (matches.occurrences_of("delimiter"), config.delimiter) {
+                (0, Some(c)) => c.to_string(),
+                (0, None) => "\t".to_string(),
+                _ => match matches.value_of("delimiter").unwrap_or("\t") {
+                    "tab" | "\\t" => "\t".to_string(),
+                    d => d.to_string(),
+                },
+            };
+            write!(stdout, "{}", output::quizlet(&done, &delimiter))?
         }
+        Some("plain") => write!(stdout, "{}", output::plain(&done, layout))?,
+        _ => write!(stdout, "{}", output::table(&done, layout))?,
     }
     Ok(())
 }
 
-#[derive(Debug)]
-enum Stem {
-    Pres(String),
-    Fut(String),
-    Aor(String),
-    Perf(String),
+// A stem spec and the tvas asked for with it, if any.
+type Spec = (String, Vec<String>);
+
+// A verb and the tvas asked for with it, if any.
+type Todo = (Verb, Vec<String>);
+
+// The verbs of a run as they are read.
+type Verbs<'a> = Box<dyn Iterator<Item = Result<Todo, Box<dyn Error>>> + 'a>;
+
+// A verb conjugated in the tvas asked for.
+type Done = (Verb, Vec<Tva>);
+
+// Verbs conjugated together, or what stopped them.
+type Chunk = Result<Vec<Done>, Box<dyn Error>>;
+
+// How many verbs are conjugated at a time; each chunk's csv rows are
+// written before the next is read.
+const CHUNK: usize = 256;
+
+// ANSI colours for the augment, stem and ending of a form on screen.
+const AUGMENT_COLOR: &str = "\x1b[31m";
+const STEM_COLOR: &str = "\x1b[34m";
+const ENDING_COLOR: &str = "\x1b[32m";
+const RESET_COLOR: &str = "\x1b[0m";
+
+// What to do with a csv file that is already there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Existing {
+    Refuse,
+    Overwrite,
+    Append,
 }
 
-impl fmt::Display for Stem {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Stem::Pres(val) => write!(f, "{}", val),
-            Stem::Fut(val) => write!(f, "{}", val),
-            Stem::Aor(val) => write!(f, "{}", val),
-            Stem::Perf(val) => write!(f, "{}", val),
-        }
+// Writes rows to a file, or to stdout if the path is "-".
+fn to_csv(
+    grid: &[Vec<String>],
+    path: &str,
+    existing: Existing,
+    delimiter: u8,
+) -> Result<(), Box<dyn Error>> {
+    let mut wtr = csv_writer(path, existing, delimiter)?;
+    for row in grid {
+        wtr.write_record(row)?;
     }
+    wtr.flush()?;
+    Ok(())
+}
+
+fn csv_writer(
+    path: &str,
+    existing: Existing,
+    delimiter: u8,
+) -> Result<csv::Writer<Box<dyn Write>>, Box<dyn Error>> {
+    let mut options = OpenOptions::new();
+    match existing {
+        Existing::Refuse => options.write(true).create_new(true),
+        Existing::Overwrite => options.write(true).create(true).truncate(true),
+        Existing::Append => options.append(true).create(true),
+    };
+    let file: Box<dyn Write> = if path == "-" {
+        Box::new(io::stdout())
+    } else {
+        Box::new(options.open(path).map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => format!(
+                "{} already exists; pass --force to overwrite it or --append to add to it",
+                path
+            ),
+            _ => format!("cannot write {}: {}", path, e),
+        })?)
+    };
+    // Rows can differ in length, e.g. by person with verbs of different tvas.
+    Ok(WriterBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_writer(file))
 }
 
-#[derive(Debug)]
-enum Conjugated {
-    Some(Vec<String>),
-    None,
+// The csv file of a run, written a chunk of verbs at a time. The file is
+// opened, and its header written, with the first chunk, so a run that
+// fails before then leaves no file behind.
+struct CsvOut<'a> {
+    path: &'a str,
+    existing: Existing,
+    delimiter: u8,
+    long: bool,
+    // Whether the verbs were looked up in a lexicon, so that a long csv
+    // has a gloss column.
+    gloss: bool,
+    layout: output::Layout,
+    header: bool,
+    // By person, the first verb's tvas, which are the columns every verb
+    // must fill.
+    columns: Option<Vec<String>>,
+    wtr: Option<csv::Writer<Box<dyn Write>>>,
+    rows: usize,
 }
 
-impl Conjugated {
-    fn print(&self) {
-        match self {
-            Conjugated::Some(v) => {
-                let mut s = String::new();
-                for part in v {
-                    s.push_str(format!(", {}", part).as_ref());
+impl CsvOut<'_> {
+    fn write(&mut self, done: &[Done]) -> Result<(), Box<dyn Error>> {
+        if self.layout == output::Layout::ByPerson && !self.long {
+            for verb in done {
+                let columns = output::grid_header(std::slice::from_ref(verb), self.layout);
+                let first = self.columns.get_or_insert_with(|| columns.clone());
+                if columns != *first {
+                    let (vb, reqs) = verb;
+                    return Err(format!(
+                        "by person the columns are the first verb's tvas, {}, but {} has {}",
+                        first[2..].join(", "),
+                        reqs.first()
+                            .map_or(String::new(), |tva| output::name(vb, *tva)),
+                        columns[2..].join(", ")
+                    )
+                    .into());
                 }
-                println!("{}", &s[2..]);
             }
-            Conjugated::None => {}
         }
+        let wtr = match &mut self.wtr {
+            Some(wtr) => wtr,
+            None => {
+                let mut wtr = csv_writer(self.path, self.existing, self.delimiter)?;
+                if self.header {
+                    wtr.write_record(if self.long {
+                        output::long_header(done, self.gloss)
+                    } else {
+                        output::grid_header(done, self.layout)
+                    })?;
+                }
+                self.wtr.insert(wtr)
+            }
+        };
+        let grid = if self.long {
+            output::long_grid(done, self.gloss)
+        } else {
+            output::grid(done, self.layout)
+        };
+        self.rows += grid.len();
+        for row in grid {
+            wtr.write_record(row)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+
+    // Writes the header if no verb came, and gives the rows written.
+    fn finish(mut self) -> Result<usize, Box<dyn Error>> {
+        if self.wtr.is_none() {
+            self.write(&[])?;
+        }
+        Ok(self.rows)
     }
 }
 
-#[derive(Debug)]
-struct Verb {
-    stem: Stem,
-    pai: Conjugated,
-    ppi: Conjugated,
-    iai: Conjugated,
-    ipi: Conjugated,
-    fai: Conjugated,
-    fmi: Conjugated,
-    fpi: Conjugated,
-    aai: Conjugated,
-    ami: Conjugated,
-    api: Conjugated,
+#[cfg(feature = "tui")]
+fn browse(verb: &str, entries: Vec<lexicon::Entry>) -> Result<(), Box<dyn Error>> {
+    tui::browse(verb, entries)
+}
+
+#[cfg(not(feature = "tui"))]
+fn browse(_verb: &str, _entries: Vec<lexicon::Entry>) -> Result<(), Box<dyn Error>> {
+    Err("this gkverb was built without the paradigm browser; rebuild with --features tui".into())
+}
+
+#[cfg(feature = "serve")]
+fn serve(
+    host: &str,
+    port: u16,
+    entries: Vec<lexicon::Entry>,
+    nu: Nu,
+    style: Style,
+) -> Result<(), Box<dyn Error>> {
+    serve::serve(host, port, entries, nu, style)
+}
+
+#[cfg(not(feature = "serve"))]
+fn serve(
+    _host: &str,
+    _port: u16,
+    _entries: Vec<lexicon::Entry>,
+    _nu: Nu,
+    _style: Style,
+) -> Result<(), Box<dyn Error>> {
+    Err("this gkverb was built without the HTTP server; rebuild with --features serve".into())
+}
+
+#[cfg(feature = "sqlite")]
+fn write_sqlite(done: &[Done], dialect: &str, path: &str) -> Result<(), Box<dyn Error>> {
+    sqlite::write(done, dialect, path).map_err(|e| format!("cannot write {}: {}", path, e))?;
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+fn find_in_db(path: &str, form: &str) -> Result<Vec<parse::Analysis>, Box<dyn Error>> {
+    sqlite::find(path, form)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn find_in_db(_path: &str, _form: &str) -> Result<Vec<parse::Analysis>, Box<dyn Error>> {
+    Err(NO_SQLITE.into())
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn write_sqlite(_done: &[Done], _dialect: &str, _path: &str) -> Result<(), Box<dyn Error>> {
+    Err(NO_SQLITE.into())
+}
+
+// Said before an existing database is replaced, not after.
+const NO_SQLITE: &str =
+    "this gkverb was built without SQLite support; rebuild with --features sqlite";
+
+#[cfg(feature = "xlsx")]
+fn write_xlsx(
+    done: &[Done],
+    layout: output::Layout,
+    by_system: bool,
+    path: &str,
+) -> Result<(), Box<dyn Error>> {
+    xlsx::write(done, layout, by_system, path)
+        .map_err(|e| format!("cannot write {}: {}", path, e))?;
+    Ok(())
+}
+
+#[cfg(feature = "xlsx")]
+fn write_kahoot(done: &[Done], time_limit: u32, path: &str) -> Result<(), Box<dyn Error>> {
+    xlsx::write_kahoot(done, time_limit, path)
+        .map_err(|e| format!("cannot write {}: {}", path, e))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "xlsx"))]
+fn write_kahoot(_done: &[Done], _time_limit: u32, _path: &str) -> Result<(), Box<dyn Error>> {
+    Err("this gkverb was built without xlsx support; rebuild with --features xlsx".into())
+}
+
+#[cfg(not(feature = "xlsx"))]
+fn write_xlsx(
+    _done: &[Done],
+    _layout: output::Layout,
+    _by_system: bool,
+    _path: &str,
+) -> Result<(), Box<dyn Error>> {
+    Err("this gkverb was built without xlsx support; rebuild with --features xlsx".into())
 }
 
-impl Verb {
-    fn new(s: &str) -> Self {
-        let stm = Verb::get_stem_type(s);
-        Self {
-            stem: stm,
-            pai: Conjugated::None,
-            ppi: Conjugated::None,
-            iai: Conjugated::None,
-            ipi: Conjugated::None,
-            fai: Conjugated::None,
-            fmi: Conjugated::None,
-            fpi: Conjugated::None,
-            aai: Conjugated::None,
-            ami: Conjugated::None,
-            api: Conjugated::None,
+fn make_worksheet(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let done = conjugate(matches, &config)?;
+    let layout = output::Layout::from_arg(setting(matches, &config, "layout"))?;
+    let tables: Vec<output::Table> = done
+        .iter()
+        .flat_map(|(vb, reqs)| output::verb_tables(vb, reqs, layout))
+        .collect();
+
+    let blanks = match matches.values_of("pattern") {
+        Some(terms) => {
+            let terms: Vec<&str> = terms.collect();
+            if let Some(term) = terms.iter().find(|t| worksheet::picks(t, "1sg").is_none()) {
+                return Err(format!(
+                    "unknown --pattern {}; give persons (1st, 3), numbers (sg, plural) or cells (2pl)",
+                    term
+                )
+                .into());
+            }
+            worksheet::Blanks::Pattern(terms)
+        }
+        None => match matches.value_of("blank").unwrap_or("50").parse() {
+            Ok(percent) if percent <= 100 => worksheet::Blanks::Percent(percent),
+            _ => return Err("--blank must be a percentage from 0 to 100".into()),
+        },
+    };
+    // Without a seed, one is picked and reported so the sheet can be remade.
+    let seed = seed(matches)?;
+    if matches.value_of("seed").is_none() {
+        if let worksheet::Blanks::Percent(_) = blanks {
+            eprintln!(
+                "worksheet seed: {} (pass --seed {} to make it again)",
+                seed, seed
+            );
         }
     }
+    let blanked = worksheet::blank(&tables, &blanks, seed);
 
-    fn get_stem_type(s: &str) -> Stem {
-        let v: Vec<&str> = s.split(":").collect();
-        match v[0] {
-            t if t == "pres" => Stem::Pres(v[1].to_string()),
-            t if t == "fut" => Stem::Fut(v[1].to_string()),
-            t if t == "aor" => Stem::Aor(v[1].to_string()),
-            t if t == "perf" => Stem::Perf(v[1].to_string()),
-            _ => Stem::Pres(v[0].to_string()),
+    let render = |key| match matches.value_of("format") {
+        Some("latex") => worksheet::latex(&tables, &blanked, key),
+        Some("html") => worksheet::html(&tables, &blanked, key),
+        _ => worksheet::markdown(&tables, &blanked, key),
+    };
+    let outfile = matches.value_of("outfile").filter(|path| *path != "-");
+    match outfile {
+        Some(path) => {
+            fs::write(path, render(false)).map_err(|e| format!("cannot write {}: {}", path, e))?
         }
+        None => write!(stdout, "{}", render(false))?,
     }
+    // The key goes beside the worksheet, hw.html giving hw-key.html, unless
+    // named with --key.
+    let key = match (matches.value_of("key"), outfile) {
+        (Some(path), _) => Some(path.to_string()),
+        (None, Some(path)) => {
+            let path = Path::new(path);
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let name = match path.extension() {
+                Some(ext) => format!("{}-key.{}", stem, ext.to_string_lossy()),
+                None => format!("{}-key", stem),
+            };
+            Some(path.with_file_name(name).display().to_string())
+        }
+        (None, None) => None,
+    };
+    if let Some(path) = key {
+        fs::write(&path, render(true)).map_err(|e| format!("cannot write {}: {}", path, e))?;
+        eprintln!("answer key written to {}", path);
+    }
+    Ok(())
+}
 
-    fn conj_pai(&mut self) {
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["ω", "εις", "ει", "ομεν", "ετε", "ουσι"].iter() {
-            let part = format!("{}{}", &self.stem, ending);
-            v.push(part);
+fn run_quiz(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let mut done = conjugate(matches, &config)?;
+    // Answers are typed, and checked, in Unicode or as --input-encoding says.
+    if setting(matches, &config, "output-encoding") == Some("betacode") {
+        for (vb, _) in done.iter_mut() {
+            vb.map_forms(betacode::to_unicode);
         }
-        self.pai = Conjugated::Some(v);
     }
+    let mut progress = progress::load(matches.value_of("progress"))?;
+    let today = progress::today();
+    let mut questions = quiz::questions(&done, seed(matches)?);
+    if matches.is_present("due") {
+        questions.retain(|q| progress.is_due(&q.item, today));
+        if questions.is_empty() {
+            writeln!(stdout, "Nothing is due for review.")?;
+            return Ok(());
+        }
+    }
+    let count = matches.value_of("count").unwrap_or("10");
+    let count: usize = count
+        .parse()
+        .map_err(|_| format!("--count must be a whole number, not {}", count))?;
+    questions.truncate(count);
+    let encoding = setting(matches, &config, "input-encoding").unwrap_or("unicode");
+    let results = quiz::run(
+        &questions,
+        io::stdin().lock(),
+        io::stdout(),
+        quiz::Checking::from_arg(matches.value_of("breathings")),
+        |answer| decode_input(answer, encoding),
+    )?;
+    for (question, right) in questions.iter().zip(results) {
+        progress.record(&question.item, right, today);
+    }
+    progress.save()?;
+    Ok(())
+}
 
-    fn conj_ppi(&mut self) {
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["ομαι", "ῃ", "εται", "ομεθα", "εσθε", "ονται"].iter()
-        {
-            let part = format!("{}{}", &self.stem, ending);
-            v.push(part);
+// Marks a student's answers, writes them annotated and prints the score.
+fn run_grade(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let mut done = conjugate(matches, &config)?;
+    if setting(matches, &config, "output-encoding") == Some("betacode") {
+        for (vb, _) in done.iter_mut() {
+            vb.map_forms(betacode::to_unicode);
+        }
+    }
+    let answers = matches.value_of("answers").unwrap_or_default();
+    let encoding = setting(matches, &config, "input-encoding").unwrap_or("unicode");
+    let marks = grade::grade(
+        answers,
+        &done,
+        quiz::Checking::from_arg(matches.value_of("breathings")),
+        |answer| decode_input(answer, encoding),
+    )?;
+    let right = marks.iter().filter(|mark| mark.right).count();
+    let score = format!(
+        "{} of {} right ({}%)",
+        right,
+        marks.len(),
+        (right * 100).checked_div(marks.len()).unwrap_or(0)
+    );
+    // The marks go beside the answers, student.csv giving
+    // student-graded.csv, unless named with --outfile.
+    let outfile = match matches.value_of("outfile") {
+        Some(path) => path.to_string(),
+        None => {
+            let path = Path::new(answers);
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            path.with_file_name(format!("{}-graded.csv", stem))
+                .display()
+                .to_string()
         }
-        self.ppi = Conjugated::Some(v);
+    };
+    if outfile == "-" {
+        grade::write(&marks, io::stdout())?;
+        eprintln!("{}", score);
+    } else {
+        let file =
+            fs::File::create(&outfile).map_err(|e| format!("cannot write {}: {}", outfile, e))?;
+        grade::write(&marks, file)?;
+        writeln!(stdout, "{}", score)?;
+        eprintln!("marked answers written to {}", outfile);
+    }
+    Ok(())
+}
+
+fn run_repl(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let config = config::load(matches.value_of("config"))?;
+    let entries = load_lexicon(setting(matches, &config, "lexicon"))?;
+    let profile = profile(matches, &config)?;
+    let mut session = repl::Session::new(entries, profile.nu, profile.style);
+    repl::run(
+        &mut session,
+        io::stdin().lock(),
+        io::stdout(),
+        io::stdin().is_terminal(),
+    )?;
+    Ok(())
+}
+
+fn run_server(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let port = matches.value_of("port").unwrap_or("8080");
+    let port: u16 = port
+        .parse()
+        .map_err(|_| format!("--port must be a port number, not {}", port))?;
+    let config = config::load(matches.value_of("config"))?;
+    let entries = load_lexicon(setting(matches, &config, "lexicon"))?;
+    let profile = profile(matches, &config)?;
+    let host = matches.value_of("host").unwrap_or("127.0.0.1");
+    serve(host, port, entries, profile.nu, profile.style)
+}
+
+fn print_completions(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let shell: Shell = matches.value_of("shell").unwrap_or("bash").parse()?;
+    let own = env::current_exe()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "gkverb".to_string());
+    let name = matches.value_of("bin-name").unwrap_or(&own);
+    app(true).gen_completions_to(name, shell, &mut io::stdout());
+    Ok(())
+}
+
+fn print_synopsis(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let done = conjugate(matches, &config)?;
+    let cell = format!(
+        "{}{}",
+        matches.value_of("person").unwrap_or("3"),
+        matches.value_of("number").unwrap_or("sg")
+    );
+    let tables: Vec<output::Table> = done
+        .iter()
+        .filter_map(|(vb, reqs)| output::synopsis(vb, reqs, &cell))
+        .collect();
+    match matches.value_of("format") {
+        Some("markdown") => write!(stdout, "{}", output::markdown_tables(&tables))?,
+        Some("latex") => write!(stdout, "{}", output::latex_tables(&tables))?,
+        Some("html") => write!(stdout, "{}", output::html_tables(&tables))?,
+        _ => write!(stdout, "{}", output::aligned(&tables))?,
     }
+    Ok(())
+}
 
-    fn conj_iai(&mut self) {
-        let s = &self.stem.to_string();
-        let (aug, stem) = Verb::aug_and_stem(s);
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["ον", "ες", "ε", "ομεν", "ετε", "ον"].iter() {
-            let part = format!("{}{}{}", aug, stem, ending);
-            v.push(part);
+fn print_parts(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let done = conjugate(matches, &config)?;
+    let tables: Vec<output::Table> = done
+        .iter()
+        .filter_map(|(vb, reqs)| output::principal_parts(vb, reqs))
+        .collect();
+    match matches.value_of("format") {
+        Some("markdown") => write!(stdout, "{}", output::markdown_tables(&tables))?,
+        Some("latex") => write!(stdout, "{}", output::latex_tables(&tables))?,
+        Some("html") => write!(stdout, "{}", output::html_tables(&tables))?,
+        Some("csv") => {
+            let mut wtr = WriterBuilder::new().from_writer(io::stdout());
+            wtr.write_record(["lemma", "part", "form"])?;
+            for (vb, reqs) in &done {
+                let table = match output::principal_parts(vb, reqs) {
+                    Some(table) => table,
+                    None => continue,
+                };
+                let lemma = output::name(vb, reqs[0]);
+                for (part, forms) in &table.rows {
+                    wtr.write_record([lemma.as_str(), part, &forms[0]])?;
+                }
+            }
+            wtr.flush()?;
         }
-        self.iai = Conjugated::Some(v);
+        _ => write!(stdout, "{}", output::aligned(&tables))?,
     }
+    Ok(())
+}
 
-    fn conj_ipi(&mut self) {
-        let s = &self.stem.to_string();
-        let (aug, stem) = Verb::aug_and_stem(s);
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["ομην", "ου", "ετο", "ομεθα", "εσθε", "οντο"].iter() {
-            let part = format!("{}{}{}", aug, stem, ending);
-            v.push(part);
+fn list_tvas(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let tables = [output::tva_table()];
+    match matches.value_of("format") {
+        Some("markdown") => write!(stdout, "{}", output::markdown_tables(&tables))?,
+        Some("latex") => write!(stdout, "{}", output::latex_tables(&tables))?,
+        Some("html") => write!(stdout, "{}", output::html_tables(&tables))?,
+        _ => write!(stdout, "{}", output::aligned(&tables))?,
+    }
+    Ok(())
+}
+
+// The diff of every reference paradigm the tables do not reproduce; any
+// such is an error, so that the run fails.
+fn run_selftest(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let entries = load_lexicon(setting(matches, &config, "lexicon"))?;
+    let references = match matches.values_of("fixtures") {
+        Some(paths) => {
+            let mut references = Vec::new();
+            for path in paths {
+                references.extend(selftest::load(path)?);
+            }
+            references
         }
-        self.ipi = Conjugated::Some(v);
+        None => selftest::builtin(),
+    };
+    let mut failed = 0;
+    for reference in &references {
+        let diff = selftest::diff(reference, &entries);
+        if !diff.is_empty() {
+            write!(stdout, "{}", diff)?;
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{} of {} paradigms differ", failed, references.len()).into());
     }
+    writeln!(stdout, "{} paradigms, all as expected", references.len())?;
+    Ok(())
+}
 
-    fn conj_fai(&mut self) {
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["ω", "εις", "ει", "ομεν", "ετε", "ουσι"].iter() {
-            let part = format!("{}{}", &self.stem, ending);
-            v.push(part);
+// A line per form Morpheus does not recognize, with its parse, then how
+// many there were of how many. Without --lemma every verb of the lexicon
+// is checked, one whose entry cannot be used being reported and skipped.
+fn verify_forms(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let entries = load_lexicon(setting(matches, &config, "lexicon"))?;
+    let analyzer = match matches.value_of("dump") {
+        Some(path) => verify::Analyzer::dump(path)?,
+        None => {
+            verify::Analyzer::Service(matches.value_of("endpoint").unwrap_or_default().to_string())
         }
-        self.fai = Conjugated::Some(v);
+    };
+    let given: Vec<Tva> = match matches.values_of("tva") {
+        Some(tvas) => tvas.map(str::parse).collect::<Result<_, _>>()?,
+        None => Vec::new(),
+    };
+    let verbs: Vec<(String, Result<Verb, GkVerbError>)> = match matches.values_of("lemma") {
+        Some(lemmas) => lemmas
+            .map(|lemma| {
+                (
+                    lemma.to_string(),
+                    Verb::from_lemma(lemma, &entries).map(warned),
+                )
+            })
+            .collect(),
+        None => {
+            // A lemma in the user's lexicon hides the built-in entry.
+            let mut seen = HashSet::new();
+            entries
+                .iter()
+                .filter(|entry| seen.insert(strip_accents(&entry.lemma)))
+                .map(|entry| (entry.lemma.clone(), Verb::from_entry(entry)))
+                .collect()
+        }
+    };
+    let mut done = Vec::new();
+    for (lemma, vb) in verbs {
+        let mut vb = match vb {
+            Ok(vb) => vb,
+            Err(e) => {
+                eprintln!("{}: skipping: {}", lemma, e);
+                continue;
+            }
+        };
+        let reqs: Vec<Tva> = if given.is_empty() {
+            vb.default_reqs()
+        } else {
+            given
+                .iter()
+                .copied()
+                .filter(|tva| vb.has_stem_for(*tva))
+                .collect()
+        };
+        conj_reqs(&mut vb, &reqs)?;
+        done.push((vb, reqs));
+    }
+    let cards = output::cards(&done);
+    let missing = verify::unrecognized(&analyzer, &cards)?;
+    for card in &missing {
+        writeln!(stdout, "{}: {} of {}", card.form, card.parse, card.lemma)?;
     }
+    eprintln!("{} of {} forms not recognized", missing.len(), cards.len());
+    Ok(())
+}
 
-    fn conj_fmi(&mut self) {
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["ομαι", "ῃ", "εται", "ομεθα", "εσθε", "ονται"].iter()
-        {
-            let part = format!("{}{}", &self.stem, ending);
-            v.push(part);
-        }
-        self.fmi = Conjugated::Some(v);
-    }
-
-    fn conj_fpi(&mut self) {
-        let mut v: Vec<String> = Vec::new();
-        for ending in [
-            "θησομαι",
-            "θησῃ",
-            "θησεται",
-            "θησομεθα",
-            "θησεσθε",
-            "θησονται",
-        ]
+// Every form of every verb of the lexicon, the --lexicon file's or else
+// the built-in one, in all the tvas it has stems for, written to a new
+// database CHUNK verbs at a time with a count of those done on stderr.
+// Verbs that cannot be built are skipped and listed at the end.
+fn build_db(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    if cfg!(not(feature = "sqlite")) {
+        return Err(NO_SQLITE.into());
+    }
+    let config = config::load(matches.value_of("config"))?;
+    let path = matches.value_of("out").unwrap_or_default();
+    if fs::metadata(path).is_ok() {
+        if !matches.is_present("force") {
+            return Err(format!("{} already exists; pass --force to overwrite it", path).into());
+        }
+        fs::remove_file(path).map_err(|e| format!("cannot overwrite {}: {}", path, e))?;
+    }
+    let entries = match setting(matches, &config, "lexicon") {
+        Some(lexicon) => lexicon::load(lexicon)?,
+        None => lexicon::builtin().to_vec(),
+    };
+    let mut seen = HashSet::new();
+    let entries: Vec<&lexicon::Entry> = entries
         .iter()
-        {
-            let part = format!("{}{}", &self.stem, ending);
-            v.push(part);
+        .filter(|entry| seen.insert(strip_accents(&entry.lemma)))
+        .collect();
+    let profile = profile(matches, &config)?;
+    let endings: Option<Arc<dyn EndingSet>> = match setting(matches, &config, "endings") {
+        Some(path) => Some(Arc::new(EndingTable::load(path)?)),
+        None => None,
+    };
+    let dialect = match setting(matches, &config, "endings") {
+        Some(endings) => Path::new(endings)
+            .file_stem()
+            .map_or(endings.into(), |stem| stem.to_string_lossy()),
+        None => "attic".into(),
+    };
+    let build = |entry: &&lexicon::Entry| -> Result<Done, GkVerbError> {
+        let mut vb = Verb::from_entry(entry)?;
+        vb.nu = profile.nu;
+        vb.style = profile.style;
+        if let Some(endings) = &endings {
+            vb.endings = endings.clone();
+        }
+        let reqs = vb.default_reqs();
+        conj_reqs(&mut vb, &reqs)?;
+        Ok((vb, reqs))
+    };
+    let report = !matches.is_present("quiet");
+    let terminal = io::stderr().is_terminal();
+    let (mut verbs, mut forms) = (0, 0);
+    let mut skipped = Vec::new();
+    for chunk in entries.chunks(CHUNK) {
+        let built: Vec<Result<Done, GkVerbError>> = chunk.par_iter().map(build).collect();
+        let mut done = Vec::new();
+        for vb in built {
+            match vb {
+                Ok(vb) => done.push(vb),
+                Err(e) => skipped.push(e.to_string()),
+            }
+        }
+        write_sqlite(&done, &dialect, path)?;
+        verbs += chunk.len();
+        forms += done.iter().map(|(vb, _)| vb.forms().count()).sum::<usize>();
+        if report {
+            let line = format!("{} of {} verbs, {} forms", verbs, entries.len(), forms);
+            if terminal {
+                eprint!("\r{}", line);
+            } else {
+                eprintln!("{}", line);
+            }
         }
-        self.fpi = Conjugated::Some(v);
     }
+    if report && terminal {
+        eprintln!();
+    }
+    for skip in &skipped {
+        eprintln!("skipped {}", skip);
+    }
+    eprintln!(
+        "wrote {} forms of {} verbs to {}",
+        forms,
+        entries.len() - skipped.len(),
+        path
+    );
+    Ok(())
+}
 
-    fn conj_aai(&mut self) {
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["α", "ας", "ε", "αμεν", "ατε", "αν"].iter() {
-            let part = format!("{}{}", &self.stem, ending);
-            v.push(part);
+// A line per analysis of each form; verbs not in the lexicon are marked
+// as guessed. Forms from --infile get a csv row per analysis instead, with
+// "unrecognized" for a form none is found for.
+fn parse_forms(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let encoding = setting(matches, &config, "input-encoding").unwrap_or("unicode");
+    let parser = parse::Parser::new(&load_lexicon(setting(matches, &config, "lexicon"))?);
+    if let Some(path) = matches.value_of("infile") {
+        let mut grid = vec![vec![
+            "form".to_string(),
+            "lemma".to_string(),
+            "parse".to_string(),
+            "source".to_string(),
+        ]];
+        for form in batch::forms(path, matches.value_of("column"))? {
+            let decoded = decode_input(&form, encoding);
+            let analyses = match check_greek(&decoded) {
+                Ok(()) => parser.analyses(&decoded),
+                Err(_) => Vec::new(),
+            };
+            if analyses.is_empty() {
+                grid.push(vec![
+                    form.clone(),
+                    String::new(),
+                    "unrecognized".to_string(),
+                    String::new(),
+                ]);
+            }
+            for analysis in analyses {
+                let source = if analysis.known { "lexicon" } else { "regular" };
+                grid.push(vec![
+                    form.clone(),
+                    analysis.lemma.clone(),
+                    analysis.parse(),
+                    source.to_string(),
+                ]);
+            }
+        }
+        let existing = if matches.is_present("force") {
+            Existing::Overwrite
+        } else {
+            Existing::Refuse
+        };
+        let outfile = matches.value_of("outfile").unwrap_or("-");
+        return to_csv(&grid, outfile, existing, b',');
+    }
+    for form in matches.values_of("form").into_iter().flatten() {
+        let form = decode_input(form, encoding);
+        check_greek(&form)?;
+        let analyses = parser.analyses(&form);
+        if analyses.is_empty() {
+            writeln!(stdout, "{}: no analysis found", form)?;
+        }
+        for analysis in analyses {
+            writeln!(stdout, "{}", analysis.describe(&form))?;
         }
-        self.aai = Conjugated::Some(v);
     }
+    Ok(())
+}
 
-    fn conj_ami(&mut self) {
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["αμην", "ω", "ατο", "αμεθα", "ασθε", "αντο"].iter() {
-            let part = format!("{}{}", &self.stem, ending);
-            v.push(part);
+// The verb and cell of each form, looked up among the lexicon's verbs
+// only, in a database from build-db if one is given and otherwise in
+// their paradigms, generated for the purpose.
+fn find_forms(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let encoding = setting(matches, &config, "input-encoding").unwrap_or("unicode");
+    let parser = match matches.value_of("db") {
+        Some(_) => None,
+        None => Some(parse::Parser::new(&load_lexicon(setting(
+            matches, &config, "lexicon",
+        ))?)),
+    };
+    for form in matches.values_of("form").into_iter().flatten() {
+        let form = decode_input(form, encoding);
+        check_greek(&form)?;
+        let found = match (&parser, matches.value_of("db")) {
+            (Some(parser), _) => parser.lookup(&form),
+            (None, Some(db)) => find_in_db(db, &form)?,
+            (None, None) => Vec::new(),
+        };
+        if found.is_empty() {
+            writeln!(stdout, "{}: not found", form)?;
+        }
+        for analysis in found {
+            writeln!(stdout, "{}", analysis.describe(&form))?;
         }
-        self.ami = Conjugated::Some(v);
     }
+    Ok(())
+}
 
-    fn conj_api(&mut self) {
-        let mut v: Vec<String> = Vec::new();
-        for ending in ["θην", "θης", "θη", "θημεν", "θητε", "θησαν"].iter() {
-            let part = format!("{}{}", &self.stem, ending);
-            v.push(part);
+// Every word of a text that is a form of one of the lexicon's verbs, with
+// its line number and parse, in the order they come. Each different word
+// is looked up once; a capital is looked up as a small letter.
+fn scan_text(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let path = matches.value_of("text").unwrap_or_default();
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let parser = parse::Parser::new(&load_lexicon(setting(matches, &config, "lexicon"))?);
+    let mut found: HashMap<String, Vec<parse::Analysis>> = HashMap::new();
+    let (mut words, mut matched) = (0, 0);
+    for (line, word) in parse::words(&text) {
+        words += 1;
+        let analyses = found
+            .entry(word.to_string())
+            .or_insert_with(|| parser.lookup(&word.to_lowercase()));
+        if !analyses.is_empty() {
+            matched += 1;
         }
-        self.api = Conjugated::Some(v);
+        for analysis in analyses.iter() {
+            writeln!(stdout, "{}: {}", line, analysis.describe(word))?;
+        }
+    }
+    if !matches.is_present("quiet") {
+        eprintln!(
+            "{} of {} words are forms of the lexicon's verbs",
+            matched, words
+        );
     }
+    Ok(())
+}
 
-    fn aug_and_stem(mut stem: &str) -> (&str, &str) {
-        let aug: &str = match stem {
-            stm if stm.starts_with("ἀ") => {
-                stem = stem.split("ἀ").collect::<Vec<&str>>()[1];
-                "ἠ"
-            }
-            stm if stm.starts_with("ἂ") => {
-                stem = stem.split("ἂ").collect::<Vec<&str>>()[1];
-                "ἠ"
-            }
-            stm if stm.starts_with("ἆ") => {
-                stem = stem.split("ἆ").collect::<Vec<&str>>()[1];
-                "ἠ"
-            }
-            stm if stm.starts_with("ἁ") => {
-                stem = stem.split("ἁ").collect::<Vec<&str>>()[1];
-                "ἡ"
+// Tab-separated form and parse pairs, chosen without repeats.
+fn random_forms(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let config = config::load(matches.value_of("config"))?;
+    let done = conjugate(matches, &config)?;
+    let wanted = |name: &str, label: &str| match matches.values_of(name) {
+        Some(mut values) => values.any(|v| label.split('/').any(|l| l == v)),
+        None => true,
+    };
+    let mut forms: Vec<&Form> = done
+        .iter()
+        .flat_map(|(vb, reqs)| {
+            reqs.iter()
+                .filter_map(move |req| vb.paradigm(*req))
+                .flat_map(Paradigm::forms)
+        })
+        .filter(|form| {
+            wanted("tense", form.tense.name())
+                && wanted("voice", form.voice.name())
+                && wanted("mood", form.mood.name())
+        })
+        .collect();
+    forms.shuffle(&mut StdRng::seed_from_u64(seed(matches)?));
+    let count = matches.value_of("count").unwrap_or("10");
+    let count: usize = count
+        .parse()
+        .map_err(|_| format!("--count must be a whole number, not {}", count))?;
+    for form in forms.iter().take(count) {
+        writeln!(stdout, "{}\t{}", form.text, output::parse(form))?;
+    }
+    Ok(())
+}
+
+// The --seed given, else one from the clock.
+fn seed(matches: &ArgMatches) -> Result<u64, String> {
+    match matches.value_of("seed") {
+        Some(seed) => seed
+            .parse()
+            .map_err(|_| format!("--seed must be a whole number, not {}", seed)),
+        None => Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)),
+    }
+}
+
+// The command line. When completing, --tva also lists the tva codes, for
+// the shell to offer.
+fn app<'a, 'b>(completing: bool) -> App<'a, 'b> {
+    App::new("greek-verb-writer")
+    .setting(AppSettings::SubcommandsNegateReqs)
+    .args(&verb_args(true, completing))
+    .arg(
+        Arg::with_name("format")
+            .help("How paradigms are printed: an aligned table, plain comma-separated rows, or a document format")
+            .long("format")
+            .possible_values(&output::FORMATS)
+            .default_value("table")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("tui")
+            .help("Browse paradigms in the terminal, changing verb and options live (needs a build with --features tui)")
+            .long("tui"),
+    )
+    .arg(
+        Arg::with_name("sheets")
+            .help("With --format xlsx, a sheet per verb or per tense system")
+            .long("sheets")
+            .possible_values(&["verb", "system"])
+            .default_value("verb")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("links")
+            .help("With --format html or markdown, link each lemma to Logeion and each form to Perseus")
+            .long("links"),
+    )
+    .arg(
+        Arg::with_name("time-limit")
+            .help("With --format kahoot, the seconds given to answer each question")
+            .long("time-limit")
+            .possible_values(&["5", "10", "20", "30", "60", "90", "120", "240"])
+            .default_value("20")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("color")
+            .help("Colour augment, stem and ending on screen; auto honours NO_COLOR")
+            .long("color")
+            .possible_values(&["auto", "always", "never"])
+            .default_value("auto")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("check")
+            .help("Check every row of the --infile, reporting problems by row number, and write nothing")
+            .long("check")
+            .requires("infile"),
+    )
+    .arg(
+        Arg::with_name("quiet")
+            .help("Print no paradigms, e.g. when only the csv file is wanted")
+            .short("q")
+            .long("quiet"),
+    )
+    .arg(
+        Arg::with_name("verbose")
+            .help("Report each verb and its tvas on stderr as it is conjugated; -vv adds each tva's stem")
+            .short("v")
+            .long("verbose")
+            .multiple(true),
+    )
+    .arg(
+        Arg::with_name("to-csv")
+            .help("Print csv to stdout, or write it to the --outfile")
+            .short("c")
+            .long("to-csv"),
+    )
+    .arg(
+        Arg::with_name("outfile")
+            .help("The csv file to write, or - for stdout; implies --to-csv")
+            .short("o")
+            .long("outfile")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("csv-format")
+            .help("wide: a row per paradigm; long: a labelled row per form")
+            .long("csv-format")
+            .possible_values(&["wide", "long"])
+            .default_value("wide")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("no-header")
+            .help("Leave out the csv file's header row")
+            .long("no-header"),
+    )
+    .arg(
+        Arg::with_name("force")
+            .help("Overwrite the csv file if it already exists")
+            .short("f")
+            .long("force"),
+    )
+    .arg(
+        Arg::with_name("append")
+            .help("Add to the end of the csv file, or to the sqlite database, if it already exists")
+            .long("append")
+            .conflicts_with("force"),
+    )
+    .arg(
+        Arg::with_name("delimiter")
+            .help("Field delimiter of the csv file; \"tab\" for tab-separated")
+            .long("delimiter")
+            .default_value(",")
+            .takes_value(true),
+    )
+    .subcommand(
+        SubCommand::with_name("worksheet")
+            .about("Paradigm tables with cells left blank, for printable homework")
+            .args(&verb_args(false, completing))
+            .arg(
+                Arg::with_name("blank")
+                    .help("Percentage of the forms to blank at random")
+                    .long("blank")
+                    .default_value("50")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("pattern")
+                    .help("Blank every cell of these persons or numbers instead, e.g. 3rd or pl,1sg")
+                    .long("pattern")
+                    .use_delimiter(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("seed")
+                    .help("Seed for the random blanks, to make the same worksheet again")
+                    .long("seed")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .help("The worksheet's document format")
+                    .long("format")
+                    .possible_values(&worksheet::FORMATS)
+                    .default_value("markdown")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("outfile")
+                    .help("Write the worksheet to this file instead of stdout, and its answer key beside it")
+                    .short("o")
+                    .long("outfile")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("key")
+                    .help("Write the answer key to this file")
+                    .long("key")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("quiz")
+            .about("Asks for forms by their parse and gives a score at the end")
+            .args(&verb_args(false, completing))
+            .arg(
+                Arg::with_name("count")
+                    .help("How many questions to ask")
+                    .long("count")
+                    .short("n")
+                    .default_value("10")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("breathings")
+                    .help("Whether a wrong or missing breathing makes an answer wrong; accents never do")
+                    .long("breathings")
+                    .possible_values(&["strict", "lenient"])
+                    .default_value("strict")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("seed")
+                    .help("Seed for the order of the questions")
+                    .long("seed")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("due")
+                    .help("Ask only the forms due for review")
+                    .long("due"),
+            )
+            .arg(
+                Arg::with_name("progress")
+                    .help("Keep results in this file instead of ~/.local/share/gkverb/progress.csv")
+                    .long("progress")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("grade")
+            .about("Marks a student's csv of answers against the generated paradigms")
+            .args(&verb_args(false, completing))
+            .arg(
+                Arg::with_name("answers")
+                    .help("A csv of answers, a column per cell (1sg ... 3pl) and optionally a tva column")
+                    .long("answers")
+                    .required(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("breathings")
+                    .help("Whether a wrong or missing breathing makes an answer wrong; accents never do")
+                    .long("breathings")
+                    .possible_values(&["strict", "lenient"])
+                    .default_value("strict")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("outfile")
+                    .help("Write the marked answers to this csv, - for stdout, instead of beside the answers")
+                    .short("o")
+                    .long("outfile")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("find")
+            .about("Lists the verb and cell each form belongs to, among the lexicon's verbs")
+            .arg(
+                Arg::with_name("form")
+                    .help("The forms to find")
+                    .multiple(true)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("lexicon")
+                    .help("A lexicon file (.csv or .toml) searched before the built-in one")
+                    .long("lexicon")
+                    .conflicts_with("db")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("db")
+                    .help("Look the forms up in this database from build-db instead (needs a build with --features sqlite)")
+                    .long("db")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("input-encoding")
+                    .help("How the forms are typed")
+                    .long("input-encoding")
+                    .possible_values(&["unicode", "betacode"])
+                    .default_value("unicode")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("config")
+                    .help("Read default settings from this file instead of ~/.config/gkverb/config.toml")
+                    .long("config")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("scan")
+            .about("Finds the forms of the lexicon's verbs in a Greek text and parses them")
+            .arg(
+                Arg::with_name("text")
+                    .help("The text, a file of Unicode Greek")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("lexicon")
+                    .help("A lexicon file (.csv or .toml) searched before the built-in one")
+                    .long("lexicon")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("quiet")
+                    .help("Leave out the count of words matched")
+                    .short("q")
+                    .long("quiet"),
+            )
+            .arg(
+                Arg::with_name("config")
+                    .help("Read default settings from this file instead of ~/.config/gkverb/config.toml")
+                    .long("config")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("random")
+            .about("Prints randomly chosen forms with their parses")
+            .args(&verb_args(false, completing))
+            .arg(
+                Arg::with_name("count")
+                    .help("How many forms to print")
+                    .long("count")
+                    .short("n")
+                    .default_value("10")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("tense")
+                    .help("Only forms of these tenses")
+                    .long("tense")
+                    .possible_values(&TENSES)
+                    .use_delimiter(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("voice")
+                    .help("Only forms of these voices; middle/passive forms count as both")
+                    .long("voice")
+                    .possible_values(&["active", "middle", "passive"])
+                    .use_delimiter(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("mood")
+                    .help("Only forms of these moods")
+                    .long("mood")
+                    .possible_values(&["indicative"])
+                    .use_delimiter(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("seed")
+                    .help("Seed for the choice of forms")
+                    .long("seed")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("parse")
+            .about("Lists the possible analyses of a form")
+            .arg(
+                Arg::with_name("form")
+                    .help("The forms to parse")
+                    .multiple(true)
+                    .required_unless("infile"),
+            )
+            .arg(
+                Arg::with_name("infile")
+                    .help("A file of forms, one per line or in a csv column, or - for stdin; the analyses are written as csv")
+                    .long("infile")
+                    .conflicts_with("form")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("column")
+                    .help("The csv column holding the forms, if not the first")
+                    .long("column")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("outfile")
+                    .help("The csv file to write the analyses to, or - for stdout")
+                    .short("o")
+                    .long("outfile")
+                    .default_value("-")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("force")
+                    .help("Overwrite the csv file if it already exists")
+                    .short("f")
+                    .long("force"),
+            )
+            .arg(
+                Arg::with_name("lexicon")
+                    .help("A lexicon file (.csv or .toml) searched before the built-in one")
+                    .long("lexicon")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("input-encoding")
+                    .help("How the forms are typed")
+                    .long("input-encoding")
+                    .possible_values(&["unicode", "betacode"])
+                    .default_value("unicode")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("config")
+                    .help("Read default settings from this file instead of ~/.config/gkverb/config.toml")
+                    .long("config")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("synopsis")
+            .about("One person and number in every tense and voice, as a synopsis chart")
+            .args(&verb_args(false, completing))
+            .arg(
+                Arg::with_name("person")
+                    .help("The person of the synopsis")
+                    .long("person")
+                    .possible_values(&["1", "2", "3"])
+                    .required(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("number")
+                    .help("The number of the synopsis")
+                    .long("number")
+                    .possible_values(&["sg", "pl"])
+                    .required(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .help("How the chart is printed")
+                    .long("format")
+                    .possible_values(&["table", "markdown", "latex", "html"])
+                    .default_value("table")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("parts")
+            .about("The six principal parts of a verb, labelled, as a chart")
+            .args(&verb_args(false, completing))
+            .arg(
+                Arg::with_name("format")
+                    .help("How the chart is printed; csv gives a row of lemma, part and form per part")
+                    .long("format")
+                    .possible_values(&["table", "markdown", "latex", "html", "csv"])
+                    .default_value("table")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("list")
+            .about("Lists every tva code with its name and the stem it is built on")
+            .arg(
+                Arg::with_name("format")
+                    .help("How the list is printed")
+                    .long("format")
+                    .possible_values(&["table", "markdown", "latex", "html"])
+                    .default_value("table")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("selftest")
+            .about("Checks the tables against reference paradigms, printing a diff of any cell that differs")
+            .arg(
+                Arg::with_name("fixtures")
+                    .help("A .toml file of reference paradigms laid out as data/paradigms.toml, checked instead of the built-in ones; repeat for several")
+                    .long("fixtures")
+                    .multiple(true)
+                    .number_of_values(1)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("lexicon")
+                    .help("A lexicon file (.csv or .toml) searched before the built-in one")
+                    .long("lexicon")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("config")
+                    .help("Read default settings from this file instead of ~/.config/gkverb/config.toml")
+                    .long("config")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("verify")
+            .about("Lists the generated forms Morpheus does not recognize, for the whole lexicon or the verbs given")
+            .arg(
+                Arg::with_name("dump")
+                    .help("A file of the forms Morpheus recognizes, one per line, in Beta Code or Unicode")
+                    .long("dump")
+                    .required_unless("endpoint")
+                    .conflicts_with("endpoint")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("endpoint")
+                    .help("A Morpheus service URL with {form} where the form goes (needs a build with --features verify)")
+                    .long("endpoint")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("lemma")
+                    .help("Only these verbs, by dictionary form; repeat for several")
+                    .long("lemma")
+                    .multiple(true)
+                    .number_of_values(1)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("tva")
+                    .help("Only these tvas, comma-separated")
+                    .long("tva")
+                    .multiple(true)
+                    .use_delimiter(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("lexicon")
+                    .help("A lexicon file (.csv or .toml) searched before the built-in one")
+                    .long("lexicon")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("config")
+                    .help("Read default settings from this file instead of ~/.config/gkverb/config.toml")
+                    .long("config")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("build-db")
+            .about("Writes every form of every verb in the lexicon to an SQLite database (needs a build with --features sqlite)")
+            .arg(
+                Arg::with_name("out")
+                    .help("The database to write")
+                    .long("out")
+                    .short("o")
+                    .required(true)
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("lexicon")
+                    .help("A lexicon file (.csv or .toml) whose verbs to write, in place of the built-in lexicon's")
+                    .long("lexicon")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("endings")
+                    .help("A .toml file of endings to use in place of the built-in ones, tva by tva")
+                    .long("endings")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("force")
+                    .help("Overwrite the database if it already exists")
+                    .long("force"),
+            )
+            .arg(
+                Arg::with_name("quiet")
+                    .help("Report no progress")
+                    .short("q")
+                    .long("quiet"),
+            )
+            .args(&profile_args())
+            .arg(
+                Arg::with_name("config")
+                    .help("Read default settings from this file instead of ~/.config/gkverb/config.toml")
+                    .long("config")
+                    .takes_value(true),
+            ),
+    )
+    .subcommand(
+        SubCommand::with_name("repl")
+            .about("Reads commands such as \"pai λυ\" or \"parse ἔλυσαν\" with the lexicon kept loaded")
+            .args(&session_args()),
+    )
+    .subcommand(
+        SubCommand::with_name("serve")
+            .about("Answers /conjugate and /parse requests over HTTP with JSON (needs a build with --features serve)")
+            .arg(
+                Arg::with_name("port")
+                    .help("The port to listen on")
+                    .long("port")
+                    .default_value("8080")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("host")
+                    .help("The address to listen on; 0.0.0.0 lets other machines in")
+                    .long("host")
+                    .default_value("127.0.0.1")
+                    .takes_value(true),
+            )
+            .args(&session_args()),
+    )
+    .subcommand(
+        SubCommand::with_name("completions")
+            .about("Prints a shell completion script, including the tva codes")
+            .arg(
+                Arg::with_name("shell")
+                    .help("The shell to complete for")
+                    .possible_values(&Shell::variants())
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("bin-name")
+                    .help("The command to complete, if not this program's own name")
+                    .long("bin-name")
+                    .takes_value(true),
+            ),
+    )
+}
+
+// The options that pick verbs and tvas and say how forms are written,
+// shared by the main command and its subcommands. Only the main command
+// needs --all or --tva, the subcommands taking every tva by default, and
+// only it can do without a verb, for --tui.
+fn verb_args<'a, 'b>(main: bool, completing: bool) -> Vec<Arg<'a, 'b>> {
+    let mut all = Arg::with_name("all")
+        .help("Print all combinations of tense, voice and mood for the given stem")
+        .short("a")
+        .long("all")
+        .multiple(false)
+        .takes_value(false);
+    let mut tva = Arg::with_name("tva")
+        .help("Tense, voice and mood, e.g. pai,ppi or present-active-indicative")
+        .short("t")
+        .long("tva")
+        .multiple(true)
+        .use_delimiter(true)
+        .required(false)
+        .takes_value(true);
+    if completing {
+        tva = tva
+            .possible_values(&ALL_TVAS)
+            .possible_values(&MIDDLE_TVAS)
+            .possible_value("all");
+    }
+    let mut verb: &[&str] = &["verb", "pp", "lemma", "infile", "lemmas"];
+    if main {
+        all = all.required_unless_one(&["tva", "infile", "tui"]);
+        verb = &["verb", "pp", "lemma", "infile", "lemmas", "tui"];
+    }
+    vec![
+        Arg::with_name("stem")
+            .help("Tense and stem, e.g. pres:παυ; repeat for several paradigms, or - to read from stdin")
+            .short("s")
+            .long("stem")
+            .multiple(true)
+            .number_of_values(1)
+            .required_unless_one(verb)
+            .takes_value(true),
+        Arg::with_name("derive")
+            .help("Derive these stems from each --stem's present as a regular verb's, e.g. fut,aor")
+            .long("derive")
+            .multiple(true)
+            .use_delimiter(true)
+            .possible_values(&derive::DERIVABLE)
+            .requires("stem")
+            .takes_value(true),
+        Arg::with_name("verb")
+            .help("One verb's stems, comma-separated, e.g. pres:παυ,fut:παυσ,aor:ἐπαυσ")
+            .long("verb")
+            .conflicts_with("stem")
+            .takes_value(true),
+        Arg::with_name("pp")
+            .help("All six principal parts, comma-separated; use - for a missing part")
+            .long("pp")
+            .conflicts_with_all(&["stem", "verb"])
+            .takes_value(true),
+        Arg::with_name("lemma")
+            .help("Dictionary form, e.g. φέρω; looked up in the lexicon or derived as regular")
+            .long("lemma")
+            .conflicts_with_all(&["stem", "verb", "pp"])
+            .takes_value(true),
+        Arg::with_name("infile")
+            .help("A csv file with a stem, pp or lemma column, one verb per row; written as csv, to stdout without an --outfile")
+            .long("infile")
+            .conflicts_with_all(&["stem", "verb", "pp", "lemma"])
+            .takes_value(true),
+        Arg::with_name("lemmas")
+            .help("A list of lemmas, one per line (or the first column of a .csv), - for stdin; those not in the lexicon are skipped")
+            .long("lemmas")
+            .conflicts_with_all(&["stem", "verb", "pp", "lemma", "infile"])
+            .takes_value(true),
+        Arg::with_name("lexicon")
+            .help("A lexicon file (.csv or .toml) searched before the built-in one")
+            .long("lexicon")
+            .takes_value(true),
+        Arg::with_name("endings")
+            .help("A .toml file of endings to use in place of the built-in ones, tva by tva")
+            .long("endings")
+            .takes_value(true),
+        Arg::with_name("input-encoding")
+            .help("How --stem, --pp and --lemma are typed")
+            .long("input-encoding")
+            .possible_values(&["unicode", "betacode"])
+            .default_value("unicode")
+            .takes_value(true),
+        Arg::with_name("output-encoding")
+            .help("How the generated forms are written")
+            .long("output-encoding")
+            .possible_values(&["unicode", "betacode"])
+            .default_value("unicode")
+            .takes_value(true),
+        Arg::with_name("normalize")
+            .help("Unicode normalization form of the generated forms")
+            .long("normalize")
+            .possible_values(&["nfc", "nfd"])
+            .default_value("nfc")
+            .takes_value(true),
+        Arg::with_name("transliterate")
+            .help("Add romanized columns after the Greek ones")
+            .long("transliterate")
+            .possible_values(&translit::SCHEMES)
+            .takes_value(true),
+        tva,
+        all,
+        Arg::with_name("persons")
+            .help("Only these persons and numbers, e.g. 1sg,2sg,3pl")
+            .long("persons")
+            .multiple(true)
+            .use_delimiter(true)
+            .possible_values(&CELLS)
+            .takes_value(true),
+        Arg::with_name("nu")
+            .help("Movable nu on -ουσι(ν), -ε(ν): omit, show, both (one cell) or rows")
+            .long("nu")
+            .possible_values(&NUS)
+            .default_value("omit")
+            .takes_value(true),
+        Arg::with_name("style")
+            .help("2nd sg. middle/passive ending: eta (-ῃ), ei (-ει) or both (rows)")
+            .long("style")
+            .possible_values(&STYLES)
+            .default_value("eta")
+            .takes_value(true),
+        Arg::with_name("profile")
+            .help("Follow a textbook's conventions; --nu and --style still override")
+            .long("profile")
+            .possible_values(&PROFILES)
+            .takes_value(true),
+        Arg::with_name("layout")
+            .help("Persons down the side and tvas across (by-person), the reverse (by-tense), or a table per tense with its voices across (by-voice)")
+            .long("layout")
+            .possible_values(&output::LAYOUTS)
+            .takes_value(true),
+        Arg::with_name("segmented")
+            .help("Write forms with hyphens between augment, stem and ending, e.g. ἐ-παυ-ον")
+            .long("segmented"),
+        Arg::with_name("strip-accents")
+            .help("Write forms and lemmas without accents, keeping breathings, e.g. for a beginners' textbook")
+            .long("strip-accents"),
+        Arg::with_name("labels")
+            .help("Label each form in full, e.g. 3rd person plural, aorist passive indicative")
+            .long("labels"),
+        Arg::with_name("config")
+            .help("Read default settings from this file instead of ~/.config/gkverb/config.toml")
+            .long("config")
+            .takes_value(true),
+    ]
+}
+
+// The options of the subcommands that keep running, repl and serve.
+fn session_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    let mut args = profile_args();
+    args.extend(vec![
+        Arg::with_name("lexicon")
+            .help("A lexicon file (.csv or .toml) searched before the built-in one")
+            .long("lexicon")
+            .takes_value(true),
+        Arg::with_name("config")
+            .help("Read default settings from this file instead of ~/.config/gkverb/config.toml")
+            .long("config")
+            .takes_value(true),
+    ]);
+    args
+}
+
+// How variant endings are written, for subcommands without verb_args.
+fn profile_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("nu")
+            .help("Movable nu on -ουσι(ν), -ε(ν): omit, show, both (one cell) or rows")
+            .long("nu")
+            .possible_values(&NUS)
+            .default_value("omit")
+            .takes_value(true),
+        Arg::with_name("style")
+            .help("2nd sg. middle/passive ending: eta (-ῃ), ei (-ει) or both (rows)")
+            .long("style")
+            .possible_values(&STYLES)
+            .default_value("eta")
+            .takes_value(true),
+        Arg::with_name("profile")
+            .help("Follow a textbook's conventions; --nu and --style still override")
+            .long("profile")
+            .possible_values(&PROFILES)
+            .takes_value(true),
+    ]
+}
+
+// A profile sets nu and style, which --nu and --style then override; the
+// config file's settings are applied first, so the command line wins.
+fn profile(matches: &ArgMatches, config: &config::Config) -> Result<Profile, GkVerbError> {
+    let mut profile = Profile::from_arg(config.profile.as_deref().unwrap_or("plain"))?;
+    if let Some(nu) = &config.nu {
+        profile.nu = Nu::from_arg(nu)?;
+    }
+    if let Some(style) = &config.style {
+        profile.style = Style::from_arg(style)?;
+    }
+    if matches.occurrences_of("profile") > 0 {
+        profile = Profile::from_arg(matches.value_of("profile").unwrap_or("plain"))?;
+    }
+    if matches.occurrences_of("nu") > 0 {
+        profile.nu = Nu::from_arg(matches.value_of("nu").unwrap_or("omit"))?;
+    }
+    if matches.occurrences_of("style") > 0 {
+        profile.style = Style::from_arg(matches.value_of("style").unwrap_or("eta"))?;
+    }
+    Ok(profile)
+}
+
+// The verbs given on the command line, each conjugated in its tvas and
+// written as asked.
+fn conjugate(matches: &ArgMatches, config: &config::Config) -> Result<Vec<Done>, Box<dyn Error>> {
+    let mut done = Vec::new();
+    for chunk in batches(matches, config)? {
+        done.extend(chunk?);
+    }
+    Ok(done)
+}
+
+// The verbs given on the command line, read and conjugated CHUNK at a
+// time, so that a long --infile can be written out as it goes rather than
+// held whole.
+fn batches<'a>(
+    matches: &'a ArgMatches,
+    config: &'a config::Config,
+) -> Result<impl Iterator<Item = Chunk> + 'a, Box<dyn Error>> {
+    let mut verbs = verbs(matches, config)?;
+    let profile = profile(matches, config)?;
+    let segmented = matches.is_present("segmented");
+    let labels = matches.is_present("labels");
+    let unaccented = matches.is_present("strip-accents");
+    let endings: Option<Arc<dyn EndingSet>> = match setting(matches, config, "endings") {
+        Some(path) => Some(Arc::new(EndingTable::load(path)?)),
+        None => None,
+    };
+    let finish = move |(mut vb, tvas): Todo| -> Result<Done, GkVerbError> {
+        vb.nu = profile.nu;
+        vb.style = profile.style;
+        if let Some(endings) = &endings {
+            vb.endings = endings.clone();
+        }
+
+        let tvas: Vec<&str> = if !tvas.is_empty() {
+            tvas.iter().map(String::as_str).collect()
+        } else {
+            matches
+                .values_of("tva")
+                .map_or(Vec::new(), Iterator::collect)
+        };
+        let reqs = if tvas.contains(&"all") {
+            vb.default_reqs()
+        } else {
+            parse_tvas(&vb, &tvas.join(","))?
+        };
+        conj_reqs(&mut vb, &reqs)?;
+        if let Some(cells) = matches.values_of("persons") {
+            let keys: Vec<_> = cells.filter_map(cell_key).collect();
+            vb.retain_cells(&keys);
+        }
+        if segmented {
+            vb.map_segments(|aug, stem, ending| {
+                let parts: Vec<&str> = [aug, stem, ending]
+                    .iter()
+                    .copied()
+                    .filter(|p| !p.is_empty())
+                    .collect();
+                parts.join(SEGMENT_MARK)
+            });
+        }
+        // --strip-accents takes the accents off the lemma too, and off any
+        // form that kept those of a stem typed with them.
+        if unaccented {
+            vb.map_forms(strip_accents);
+            vb.lemma = strip_accents(&vb.lemma);
+        }
+        if let Some(scheme) = setting(matches, config, "transliterate") {
+            let scheme = translit::Scheme::from_arg(scheme)?;
+            vb.romanize(|form| translit::transliterate(form, scheme));
+        }
+        if labels {
+            vb.label_forms();
+        }
+        if setting(matches, config, "normalize") == Some("nfd") {
+            vb.map_forms(|form| form.nfd().collect());
+        }
+        if setting(matches, config, "output-encoding") == Some("betacode") {
+            vb.map_forms(betacode::from_unicode);
+        }
+        Ok((vb, reqs))
+    };
+    Ok(std::iter::from_fn(move || {
+        let mut chunk = Vec::new();
+        for todo in verbs.by_ref().take(CHUNK) {
+            match todo {
+                Ok(todo) => chunk.push(todo),
+                Err(e) => return Some(Err(e)),
             }
-            stm if stm.starts_with("ἅ") => {
-                stem = stem.split("ἅ").collect::<Vec<&str>>()[1];
-                "ἡ"
+        }
+        if chunk.is_empty() {
+            return None;
+        }
+        // The verbs of a chunk are conjugated across the cores; the rows
+        // keep their order.
+        let done = chunk
+            .into_par_iter()
+            .map(&finish)
+            .collect::<Result<Vec<Done>, GkVerbError>>();
+        if let Ok(done) = &done {
+            log(done, matches.occurrences_of("verbose"));
+        }
+        Some(done.map_err(Into::into))
+    }))
+}
+
+// With -v, each verb and the tvas it was conjugated in, on stderr; with
+// -vv, the stem each tva was built on as well.
+fn log(done: &[Done], verbosity: u64) {
+    if verbosity == 0 {
+        return;
+    }
+    for (vb, reqs) in done {
+        let name = if vb.lemma.is_empty() {
+            let specs: Vec<String> = vb
+                .stems
+                .iter()
+                .map(|stem| format!("{}:{}", stem.system(), stem))
+                .collect();
+            specs.join(",")
+        } else {
+            vb.lemma.clone()
+        };
+        let codes: Vec<&str> = reqs.iter().map(|tva| tva.code()).collect();
+        eprintln!("conjugated {}: {}", name, codes.join(", "));
+        if verbosity > 1 {
+            for tva in reqs {
+                eprintln!("  {} on {}", tva, vb.stem_for(*tva));
             }
-            stm if stm.starts_with("ἇ") => {
-                stem = stem.split("ἇ").collect::<Vec<&str>>()[1];
-                "ἡ"
+        }
+    }
+}
+
+// The verbs given on the command line, each with any tvas given with it.
+// Those of an --infile are built as its rows are read, a row that names no
+// usable verb being reported and skipped.
+fn verbs<'a>(
+    matches: &'a ArgMatches,
+    config: &'a config::Config,
+) -> Result<Verbs<'a>, Box<dyn Error>> {
+    let encoding = setting(matches, config, "input-encoding").unwrap_or("unicode");
+    let mut verbs = Vec::new();
+    if let Some(specs) = matches.value_of("verb") {
+        verbs.push((
+            Verb::from_specs(&decode_input(specs, encoding))?,
+            Vec::new(),
+        ));
+    } else if let Some(pp) = matches.value_of("pp") {
+        let pp = decode_input(pp, encoding);
+        let parts: Vec<&str> = pp.split(',').map(str::trim).collect();
+        verbs.push((Verb::from_parts(&parts)?, Vec::new()));
+    } else if let Some(lemma) = matches.value_of("lemma") {
+        let entries = load_lexicon(setting(matches, config, "lexicon"))?;
+        verbs.push((
+            warned(Verb::from_lemma(&decode_input(lemma, encoding), &entries)?),
+            Vec::new(),
+        ));
+    } else if let Some(path) = matches.value_of("infile") {
+        let entries = load_lexicon(setting(matches, config, "lexicon"))?;
+        // A verb named again is built once; later rows get a copy.
+        let mut built = batch::Memo::new();
+        let rows = batch::rows(path)?.enumerate().map(move |(i, row)| {
+            let row = row.map_err(|e| format!("{}: {}", path, e))?;
+            // A bad tva or verb stops the run at its row, before any verb
+            // is conjugated in it; --check reports every bad row. Row 1 is
+            // the header.
+            let tvas = row.tvas();
+            if let Some(e) = tvas
+                .iter()
+                .filter(|tva| !tva.eq_ignore_ascii_case("all"))
+                .find_map(|tva| tva.parse::<Tva>().err())
+            {
+                return Err(format!("{}: row {}: {}", path, i + 2, e).into());
             }
-            stm if stm.starts_with("αἰ") => {
-                stem = stem.split("αἰ").collect::<Vec<&str>>()[1];
-                "ᾐ"
+            let vb = built
+                .get(&row, || {
+                    verb_from_row(&row, &entries, encoding).map_err(|e| e.to_string())
+                })
+                .as_ref()
+                .map_err(|e| format!("{}: row {}: {}", path, i + 2, e))?;
+            Ok((vb.clone(), tvas))
+        });
+        return Ok(Box::new(rows));
+    } else if let Some(path) = matches.value_of("lemmas") {
+        let entries = load_lexicon(setting(matches, config, "lexicon"))?;
+        let lemmas = batch::forms(path, None)?;
+        let mut missing = Vec::new();
+        for lemma in &lemmas {
+            let lemma = decode_input(lemma, encoding);
+            // Only verbs the lexicon knows are conjugated: a frequency list
+            // names nouns and particles too, which are not to be derived. A
+            // contracted lemma such as τιμῶ is taken as whichever reading
+            // of it the lexicon has.
+            match lexicon::find_lemma(&entries, &lemma).map(Verb::from_entry) {
+                Some(Ok(vb)) => verbs.push((warned(vb), Vec::new())),
+                Some(Err(e)) => eprintln!("{}: skipping {}: {}", path, lemma, e),
+                None => missing.push(lemma),
             }
-            stm if stm.starts_with("αἴ") => {
-                stem = stem.split("αἴ").collect::<Vec<&str>>()[1];
-                "ᾐ"
+        }
+        if !missing.is_empty() {
+            eprintln!(
+                "{}: {} of {} lemmas not in the lexicon, skipped: {}",
+                path,
+                missing.len(),
+                lemmas.len(),
+                missing.join(", ")
+            );
+        }
+    } else if let Some(stems) = matches.values_of("stem") {
+        let derive: Vec<&str> = matches
+            .values_of("derive")
+            .map_or(Vec::new(), Iterator::collect);
+        let verb = |spec: &str| -> Result<Verb, GkVerbError> {
+            let mut vb = Verb::new(&decode_input(spec, encoding))?;
+            if !derive.is_empty() {
+                vb.derive(&derive)?;
             }
-            stm if stm.starts_with("αἶ") => {
-                stem = stem.split("αἶ").collect::<Vec<&str>>()[1];
-                "ᾐ"
+            Ok(vb)
+        };
+        for stem in stems {
+            if stem == "-" {
+                for (spec, tvas) in read_specs(io::stdin().lock())? {
+                    verbs.push((verb(&spec)?, tvas));
+                }
+            } else {
+                verbs.push((verb(stem)?, Vec::new()));
             }
-            stm if stm.starts_with("αἱ") => {
-                stem = stem.split("αἱ").collect::<Vec<&str>>()[1];
-                "ᾑ"
+        }
+    }
+    Ok(Box::new(verbs.into_iter().map(Ok)))
+}
+
+// An option's value: as given on the command line, else from the config
+// file, else the option's default.
+fn setting<'a>(matches: &'a ArgMatches, config: &'a config::Config, name: &str) -> Option<&'a str> {
+    if matches.occurrences_of(name) > 0 {
+        return matches.value_of(name);
+    }
+    config.value_of(name).or_else(|| matches.value_of(name))
+}
+
+// The user's lexicon, if any, followed by the built-in one.
+fn load_lexicon(path: Option<&str>) -> Result<Vec<lexicon::Entry>, Box<dyn Error>> {
+    let mut entries = match path {
+        Some(path) => lexicon::load(path)?,
+        None => Vec::new(),
+    };
+    entries.extend_from_slice(lexicon::builtin());
+    Ok(entries)
+}
+
+// Reads one stem spec per line, optionally followed by its own tva codes
+// or "all", e.g. "pres:παυ pai,ppi". Blank lines and lines starting with # are skipped.
+fn read_specs(reader: impl BufRead) -> Result<Vec<Spec>, Box<dyn Error>> {
+    let mut specs = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let spec = fields.next().unwrap_or_default().to_string();
+        let tvas = fields
+            .flat_map(|f| f.split(','))
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect();
+        specs.push((spec, tvas));
+    }
+    Ok(specs)
+}
+
+// Converts command-line Greek from the --input-encoding, leaving any
+// "pres:"-style prefix on a stem alone; comma-separated parts or stems
+// are converted one by one.
+fn decode_input(s: &str, encoding: &str) -> String {
+    if encoding != "betacode" {
+        return s.to_string();
+    }
+    let decoded: Vec<String> = s
+        .split(',')
+        .map(|s| match s.split_once(':') {
+            Some((system, stem)) => format!("{}:{}", system, betacode::to_unicode(stem)),
+            None => betacode::to_unicode(s),
+        })
+        .collect();
+    decoded.join(",")
+}
+
+// Reads an --infile as a run would, reporting every row whose verb cannot
+// be built or whose tvas it cannot be conjugated in, and writes nothing.
+fn check_batch(matches: &ArgMatches, config: &config::Config) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout().lock();
+    let path = matches.value_of("infile").unwrap_or_default();
+    let encoding = setting(matches, config, "input-encoding").unwrap_or("unicode");
+    let entries = load_lexicon(setting(matches, config, "lexicon"))?;
+    let given: Vec<&str> = matches
+        .values_of("tva")
+        .map_or(Vec::new(), Iterator::collect);
+    let (mut rows, mut problems) = (0, 0);
+    let mut built = batch::Memo::new();
+    for (i, row) in batch::rows(path)?.enumerate() {
+        rows += 1;
+        // Row 1 is the header.
+        let mut report = |e: &dyn fmt::Display| {
+            eprintln!("{}: row {}: {}", path, i + 2, e);
+            problems += 1;
+        };
+        let row = match row {
+            Ok(row) => row,
+            Err(e) => {
+                report(&e);
+                continue;
             }
-            stm if stm.starts_with("αἵ") => {
-                stem = stem.split("αἵ").collect::<Vec<&str>>()[1];
-                "ᾑ"
+        };
+        let tvas = match row.tvas() {
+            tvas if tvas.is_empty() => given.iter().map(|t| t.to_string()).collect(),
+            tvas => tvas,
+        };
+        let all = tvas.iter().any(|t| t.eq_ignore_ascii_case("all"));
+        let mut reqs = Vec::new();
+        for tva in tvas.iter().filter(|t| !t.eq_ignore_ascii_case("all")) {
+            match tva.parse::<Tva>() {
+                Ok(tva) => reqs.push(tva),
+                Err(e) => report(&e),
             }
-            stm if stm.starts_with("αἷ") => {
-                stem = stem.split("αἷ").collect::<Vec<&str>>()[1];
-                "ᾑ"
+        }
+        let vb = match built.get(&row, || {
+            verb_from_row(&row, &entries, encoding).map_err(|e| e.to_string())
+        }) {
+            Ok(vb) => vb,
+            Err(e) => {
+                report(e);
+                continue;
             }
-            _ => "ἐ",
         };
-        (aug, stem)
-    }
-}
-
-fn conj_reqs(vb: &mut Verb, reqs: &[&str]) {
-    for req in reqs {
-        match req {
-            &"pai" => vb.conj_pai(),
-            &"ppi" => vb.conj_ppi(),
-            &"iai" => vb.conj_iai(),
-            &"ipi" => vb.conj_ipi(),
-            &"fai" => vb.conj_fai(),
-            &"fmi" => vb.conj_fmi(),
-            &"fpi" => vb.conj_fpi(),
-            &"aai" => vb.conj_aai(),
-            &"ami" => vb.conj_ami(),
-            &"api" => vb.conj_api(),
-            _ => {}
-        }
-    }
-}
-
-fn print_reqs(vb: &Verb, reqs: &[&str]) {
-    for req in reqs {
-        match req {
-            &"pai" => vb.pai.print(),
-            &"ppi" => vb.ppi.print(),
-            &"iai" => vb.iai.print(),
-            &"ipi" => vb.ipi.print(),
-            &"fai" => vb.fai.print(),
-            &"fmi" => vb.fmi.print(),
-            &"fpi" => vb.fpi.print(),
-            &"aai" => vb.aai.print(),
-            &"ami" => vb.ami.print(),
-            &"api" => vb.api.print(),
-            _ => {
-                eprintln!("print_reqs part not recognised.");
+        if all || tvas.is_empty() {
+            reqs = vb.default_reqs();
+        }
+        for tva in reqs {
+            if let Err(e) = vb.check(tva) {
+                report(&e);
             }
         }
     }
+    match problems {
+        0 => {
+            writeln!(stdout, "{}: {} rows, no problems found", path, rows)?;
+            Ok(())
+        }
+        1 => Err(format!("{}: 1 problem found in {} rows", path, rows).into()),
+        n => Err(format!("{}: {} problems found in {} rows", path, n, rows).into()),
+    }
 }
 
-fn to_csv(vb: &Verb, reqs: &[&str]) -> Result<(), Box<dyn Error>> {
-    let mut wtr = Writer::from_path("./test-output.csv")?;
-    for req in reqs {
-        let conjugated = match req {
-            &"pai" => &vb.pai,
-            &"ppi" => &vb.ppi,
-            &"iai" => &vb.iai,
-            &"ipi" => &vb.ipi,
-            &"fai" => &vb.fai,
-            &"fmi" => &vb.fmi,
-            &"fpi" => &vb.fpi,
-            &"aai" => &vb.aai,
-            &"ami" => &vb.ami,
-            &"api" => &vb.api,
-            _ => &vb.pai,
-        };
-        if let Conjugated::Some(conj) = conjugated {
-            wtr.write_record(conj)?;
-        }
+// A verb named by one row of an --infile batch.
+fn verb_from_row(
+    row: &batch::Row,
+    entries: &[lexicon::Entry],
+    encoding: &str,
+) -> Result<Verb, Box<dyn Error>> {
+    let vb = if !row.stem.is_empty() {
+        Verb::new(&decode_input(&row.stem, encoding))
+    } else if !row.pp.is_empty() {
+        let pp = decode_input(&row.pp, encoding);
+        let parts: Vec<&str> = pp.split(',').map(str::trim).collect();
+        Verb::from_parts(&parts)
+    } else if !row.lemma.is_empty() {
+        Verb::from_lemma(&decode_input(&row.lemma, encoding), entries).map(warned)
+    } else {
+        return Err("no stem, pp or lemma given".into());
+    };
+    Ok(vb?)
+}
+
+// The verb, once what its lexicon entry passed over has been shown.
+fn warned(vb: Verb) -> Verb {
+    for warning in &vb.warnings {
+        eprintln!("{}: {}.", vb.lemma, warning);
     }
-    wtr.flush()?;
-    Ok(())
+    vb
 }
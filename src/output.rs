@@ -0,0 +1,1609 @@
+use crate::{
+    cell_key, Form, GkVerbError, Mood, Number, Paradigm, Person, Tense, Tva, Verb, Voice, CELLS,
+    KEYS,
+};
+use serde_json::{json, Value};
+use unicode_normalization::char::is_combining_mark;
+
+// Structured and formatted renderings of conjugated verbs, for --format.
+// Each takes every (verb, tvas) pair of the run.
+
+pub const FORMATS: [&str; 17] = [
+    "table", "plain", "tsv", "json", "jsonl", "markdown", "latex", "html", "anki", "tei", "conllu",
+    "quizlet", "memrise", "gift", "xlsx", "kahoot", "sqlite",
+];
+
+// The forms of a cell, variants after the first, without repeats.
+fn cell_forms(paradigm: &Paradigm, key: (Person, Number)) -> Vec<&Form> {
+    paradigm
+        .forms()
+        .filter(|form| (form.person, form.number) == key)
+        .collect()
+}
+
+// The cells, with their names, that any of the paradigms has forms in: all
+// six unless some were left out, e.g. with --persons, or if there are no
+// paradigms at all.
+fn cells(paradigms: &[&Paradigm]) -> Vec<(&'static str, (Person, Number))> {
+    CELLS
+        .iter()
+        .copied()
+        .zip(KEYS)
+        .filter(|(_, (person, number))| {
+            paradigms.is_empty()
+                || paradigms
+                    .iter()
+                    .any(|paradigm| !paradigm.variants(*person, *number).is_empty())
+        })
+        .collect()
+}
+
+// How paradigms are arranged in tables and flat files. By default each
+// tva is its own table, persons down the side; by person puts all of a
+// verb's tvas side by side as columns, by tense gives each tva a row with
+// the persons as columns, and by voice gives each tense and mood a table
+// with its voices side by side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+    Paradigm,
+    ByPerson,
+    ByTense,
+    ByVoice,
+}
+
+// The values --layout takes; without one, each tva is its own table.
+pub const LAYOUTS: [&str; 3] = ["by-person", "by-tense", "by-voice"];
+
+impl Layout {
+    pub fn from_arg(s: Option<&str>) -> Result<Self, GkVerbError> {
+        match s {
+            None => Ok(Layout::Paradigm),
+            Some("by-person") => Ok(Layout::ByPerson),
+            Some("by-tense") => Ok(Layout::ByTense),
+            Some("by-voice") => Ok(Layout::ByVoice),
+            Some(s) => Err(GkVerbError::UnknownValue {
+                option: "layout",
+                value: s.to_string(),
+                expected: &LAYOUTS,
+            }),
+        }
+    }
+}
+
+// A laid-out table: a title, the column headings and, for each row, a
+// label and its cells. The tva is set when the table holds just one.
+pub struct Table {
+    pub tva: Option<Tva>,
+    pub title: String,
+    pub head: Vec<String>,
+    pub rows: Vec<(String, Vec<String>)>,
+}
+
+// The lemma, or for a bare stem the stem a tva is built on.
+pub fn name(vb: &Verb, tva: Tva) -> String {
+    if vb.lemma.is_empty() {
+        vb.stem_for(tva).into_owned()
+    } else {
+        vb.lemma.clone()
+    }
+}
+
+// E.g. "aorist passive indicative (api)"; a passive deponent's aorist is
+// the verb's plain aorist, so "aorist indicative, passive in form (ami)".
+fn heading(tva: Tva, voice: Voice) -> String {
+    if voice == tva.voice {
+        format!("{} {} {} ({})", tva.tense, tva.voice, tva.mood, tva)
+    } else {
+        format!("{} {}, {} in form ({})", tva.tense, tva.mood, voice, tva)
+    }
+}
+
+fn title(vb: &Verb, tva: Tva) -> String {
+    format!(
+        "{}: {}",
+        name(vb, tva),
+        heading(tva, vb.built_as(tva).voice)
+    )
+}
+
+// The paradigms alone, without their tvas.
+fn only<'a>(paradigms: &[(Tva, &'a Paradigm)]) -> Vec<&'a Paradigm> {
+    paradigms.iter().map(|(_, paradigm)| *paradigm).collect()
+}
+
+// The tvas of a verb that were conjugated, with their paradigms.
+fn paradigms<'a>(vb: &'a Verb, reqs: &[Tva]) -> Vec<(Tva, &'a Paradigm)> {
+    reqs.iter()
+        .filter_map(|req| Some((*req, vb.paradigm(*req)?)))
+        .collect()
+}
+
+// A cell's forms in one string, any romanization in brackets after each
+// and any label, the same for them all, after the lot.
+fn cell_text(paradigm: &Paradigm, key: (Person, Number)) -> String {
+    let forms = cell_forms(paradigm, key);
+    let texts: Vec<String> = forms
+        .iter()
+        .map(|form| match &form.romanized {
+            Some(roman) => format!("{} ({})", form.text, roman),
+            None => form.text.clone(),
+        })
+        .collect();
+    let text = join(texts.iter().map(String::as_str));
+    match forms.first().and_then(|form| form.label.as_ref()) {
+        Some(label) => format!("{} [{}]", text, label),
+        None => text,
+    }
+}
+
+fn tables(done: &[(Verb, Vec<Tva>)], layout: Layout) -> Vec<Table> {
+    done.iter()
+        .flat_map(|(vb, reqs)| verb_tables(vb, reqs, layout))
+        .collect()
+}
+
+// A paradigm as its own table, persons down the side, each variant of a
+// cell (as --nu rows and --style both give) on a row of its own.
+pub fn paradigm_table(paradigm: &Paradigm, title: String) -> Table {
+    let romanized = paradigm.is_romanized();
+    let labelled = paradigm.is_labelled();
+    let mut head = vec![String::new(), "form".to_string()];
+    if romanized {
+        head.push("transliteration".to_string());
+    }
+    if labelled {
+        head.push("label".to_string());
+    }
+    let mut rows = Vec::new();
+    for (cell, key) in cells(&[paradigm]) {
+        let mut seen: Vec<&str> = Vec::new();
+        for form in cell_forms(paradigm, key) {
+            if seen.contains(&form.text.as_str()) {
+                continue;
+            }
+            seen.push(&form.text);
+            let mut cells = vec![form.text.clone()];
+            if romanized {
+                cells.push(form.romanized.clone().unwrap_or_default());
+            }
+            if labelled {
+                cells.push(form.label.clone().unwrap_or_default());
+            }
+            rows.push((cell.to_string(), cells));
+        }
+    }
+    Table {
+        tva: Some(paradigm.tva()),
+        title,
+        head,
+        rows,
+    }
+}
+
+// The tables of one verb. Variants share a cell, separated by a slash.
+pub fn verb_tables(vb: &Verb, reqs: &[Tva], layout: Layout) -> Vec<Table> {
+    let mut tables = Vec::new();
+    let paradigms = paradigms(vb, reqs);
+    let first = match paradigms.first() {
+        Some((tva, _)) => *tva,
+        None => return tables,
+    };
+    match layout {
+        Layout::Paradigm => {
+            for (tva, paradigm) in paradigms {
+                tables.push(paradigm_table(paradigm, title(vb, tva)));
+            }
+        }
+        Layout::ByPerson => {
+            let mut head = vec![String::new()];
+            head.extend(paradigms.iter().map(|(tva, _)| tva.to_string()));
+            let body = cells(&only(&paradigms))
+                .into_iter()
+                .map(|(cell, key)| {
+                    let cells = paradigms
+                        .iter()
+                        .map(|(_, paradigm)| cell_text(paradigm, key))
+                        .collect();
+                    (cell.to_string(), cells)
+                })
+                .collect();
+            tables.push(Table {
+                tva: None,
+                title: name(vb, first),
+                head,
+                rows: body,
+            });
+        }
+        Layout::ByTense => {
+            let cells = cells(&only(&paradigms));
+            let mut head = vec![String::new()];
+            head.extend(cells.iter().map(|(cell, _)| cell.to_string()));
+            let body = paradigms
+                .iter()
+                .map(|(tva, paradigm)| {
+                    let cells = cells
+                        .iter()
+                        .map(|(_, key)| cell_text(paradigm, *key))
+                        .collect();
+                    (tva.to_string(), cells)
+                })
+                .collect();
+            tables.push(Table {
+                tva: None,
+                title: name(vb, first),
+                head,
+                rows: body,
+            });
+        }
+        Layout::ByVoice => {
+            let mut groups: Vec<Vec<(Tva, &Paradigm)>> = Vec::new();
+            for (tva, paradigm) in paradigms {
+                let same = |group: &&mut Vec<(Tva, &Paradigm)>| {
+                    let (other, _) = group[0];
+                    (other.tense, other.mood) == (tva.tense, tva.mood)
+                };
+                match groups.iter_mut().find(same) {
+                    Some(group) => group.push((tva, paradigm)),
+                    None => groups.push(vec![(tva, paradigm)]),
+                }
+            }
+            for group in groups {
+                let (tva, _) = group[0];
+                let mut head = vec![String::new()];
+                head.extend(group.iter().map(|(tva, _)| tva.voice.to_string()));
+                let body = cells(&only(&group))
+                    .into_iter()
+                    .map(|(cell, key)| {
+                        let cells = group
+                            .iter()
+                            .map(|(_, paradigm)| cell_text(paradigm, key))
+                            .collect();
+                        (cell.to_string(), cells)
+                    })
+                    .collect();
+                tables.push(Table {
+                    tva: None,
+                    title: format!("{}: {} {}", name(vb, tva), tva.tense, tva.mood),
+                    head,
+                    rows: body,
+                });
+            }
+        }
+    }
+    tables
+}
+
+// A synopsis: one person and number (a cell such as "3sg") in every tense
+// down the side and voice across. The middle/passive forms of the present
+// and perfect systems stand under both middle and passive.
+pub fn synopsis(vb: &Verb, reqs: &[Tva], cell: &str) -> Option<Table> {
+    let key = cell_key(cell)?;
+    let paradigms = paradigms(vb, reqs);
+    let first = paradigms.first()?.0;
+    let voices = [Voice::Active, Voice::Middle, Voice::Passive];
+    let mut rows: Vec<(String, Vec<String>)> = Vec::new();
+    for (tva, forms) in &paradigms {
+        let tense = tva.tense.name();
+        let row = match rows.iter().position(|(t, _)| t == tense) {
+            Some(row) => row,
+            None => {
+                rows.push((tense.to_string(), vec![String::new(); voices.len()]));
+                rows.len() - 1
+            }
+        };
+        for (v, voice) in voices.iter().enumerate() {
+            if tva.voice.covers(*voice) {
+                rows[row].1[v] = cell_text(forms, key);
+            }
+        }
+    }
+    let (person, number) = key;
+    let mut head = vec![String::new()];
+    head.extend(voices.iter().map(|v| v.to_string()));
+    Some(Table {
+        tva: None,
+        title: format!(
+            "{}: synopsis, {} person {}",
+            name(vb, first),
+            ordinal(person),
+            number.name()
+        ),
+        head,
+        rows,
+    })
+}
+
+// The principal parts in the usual order, each with the tvas whose 1st sg.
+// it is; where the system has no active, the middle.
+const PARTS: [(&str, &[&str]); 6] = [
+    ("present", &["pai", "ppi"]),
+    ("future", &["fai", "fmi"]),
+    ("aorist", &["aai", "ami"]),
+    ("perfect active", &["pfai"]),
+    ("perfect middle", &["pfpi"]),
+    ("aorist passive", &["api"]),
+];
+
+// A principal-parts chart: each part down the side with its form, "-"
+// for a part the verb has no stem for.
+pub fn principal_parts(vb: &Verb, reqs: &[Tva]) -> Option<Table> {
+    let paradigms = paradigms(vb, reqs);
+    let first = paradigms.first()?.0;
+    let rows = PARTS
+        .iter()
+        .map(|(part, tvas)| {
+            let form = tvas
+                .iter()
+                .find_map(|code| paradigms.iter().find(|(tva, _)| tva.code() == *code))
+                .map(|(_, paradigm)| cell_text(paradigm, (Person::First, Number::Sg)))
+                .filter(|form| !form.is_empty())
+                .unwrap_or_else(|| "-".to_string());
+            (part.to_string(), vec![form])
+        })
+        .collect();
+    Some(Table {
+        tva: None,
+        title: format!("{}: principal parts", name(vb, first)),
+        head: vec![String::new(), "form".to_string()],
+        rows,
+    })
+}
+
+// Every tva the tables conjugate: its code, its name and the stem spec
+// it needs, e.g. "api", "aorist passive indicative", "aorpass or
+// aorpass2".
+pub fn tva_table() -> Table {
+    let mut tvas: Vec<Tva> = Tva::all().chain(Tva::middles()).collect();
+    tvas.sort();
+    let rows = tvas
+        .into_iter()
+        .map(|tva| {
+            let system = tva.system();
+            let stems = match system {
+                "aorpass" if tva.tense == Tense::Future => {
+                    "aorpass, aorpass2 or futpass".to_string()
+                }
+                "aor" | "aorpass" => format!("{} or {}2", system, system),
+                _ => system.to_string(),
+            };
+            let name = format!("{} {} {}", tva.tense, tva.voice, tva.mood);
+            (tva.code().to_string(), vec![name, stems])
+        })
+        .collect();
+    Table {
+        tva: None,
+        title: "tvas".to_string(),
+        head: vec!["tva".to_string(), "name".to_string(), "stem".to_string()],
+        rows,
+    }
+}
+
+// The rows of a flat file (plain, tsv or csv). By default each paradigm
+// row is one line of six forms, variants on further lines. The other
+// layouts say what each line is: by tense and by voice, each paradigm row
+// is led by the verb and its tva; by person, each verb gives a line per
+// person and number, led by the verb and the cell, with a field per tva.
+// Each variant of a cell is on a line of its own.
+pub fn grid(done: &[(Verb, Vec<Tva>)], layout: Layout) -> Vec<Vec<String>> {
+    let mut grid = Vec::new();
+    for (vb, reqs) in done {
+        let paradigms = paradigms(vb, reqs);
+        match layout {
+            Layout::Paradigm => {
+                for (_, paradigm) in paradigms {
+                    grid.extend(paradigm.rows());
+                }
+            }
+            Layout::ByPerson => {
+                let first = match paradigms.first() {
+                    Some((tva, _)) => *tva,
+                    None => continue,
+                };
+                for (cell, key) in cells(&only(&paradigms)) {
+                    let variants: Vec<Vec<&Form>> = paradigms
+                        .iter()
+                        .map(|(_, paradigm)| cell_forms(paradigm, key))
+                        .collect();
+                    let n = variants.iter().map(Vec::len).max().unwrap_or(0);
+                    for i in 0..n {
+                        let mut row = vec![name(vb, first), cell.to_string()];
+                        row.extend(variants.iter().map(|forms| {
+                            forms
+                                .get(i)
+                                .or_else(|| forms.first())
+                                .map_or(String::new(), |form| form_text(form))
+                        }));
+                        grid.push(row);
+                    }
+                }
+            }
+            Layout::ByTense | Layout::ByVoice => {
+                for (tva, paradigm) in paradigms {
+                    for row in paradigm.rows() {
+                        let mut keyed = vec![name(vb, tva), tva.to_string()];
+                        keyed.extend(row);
+                        grid.push(keyed);
+                    }
+                }
+            }
+        }
+    }
+    grid
+}
+
+// A form with any romanization in brackets after it and any label after
+// that.
+fn form_text(form: &Form) -> String {
+    let mut text = form.text.clone();
+    if let Some(roman) = &form.romanized {
+        text.push_str(&format!(" ({})", roman));
+    }
+    if let Some(label) = &form.label {
+        text.push_str(&format!(" [{}]", label));
+    }
+    text
+}
+
+fn join<'a>(forms: impl Iterator<Item = &'a str>) -> String {
+    let mut seen: Vec<&str> = Vec::new();
+    for form in forms {
+        if !seen.contains(&form) {
+            seen.push(form);
+        }
+    }
+    seen.join(" / ")
+}
+
+// How many columns a form takes up in a terminal; combining marks and
+// colour escapes take none.
+fn width(s: &str) -> usize {
+    let mut width = 0;
+    let mut escape = false;
+    for c in s.chars() {
+        if escape {
+            escape = c != 'm';
+        } else if c == '\x1b' {
+            escape = true;
+        } else if !is_combining_mark(c) {
+            width += 1;
+        }
+    }
+    width
+}
+
+fn pad(s: &str, to: usize) -> String {
+    format!("{}{}", s, " ".repeat(to.saturating_sub(width(s))))
+}
+
+// Whether any paradigm carries a romanization.
+fn romanized(done: &[(Verb, Vec<Tva>)]) -> bool {
+    done.iter()
+        .flat_map(|(vb, _)| vb.forms())
+        .any(|form| form.romanized.is_some())
+}
+
+// Whether any paradigm carries labels.
+fn labelled(done: &[(Verb, Vec<Tva>)]) -> bool {
+    done.iter()
+        .flat_map(|(vb, _)| vb.forms())
+        .any(|form| form.label.is_some())
+}
+
+// Column names for grid: the persons, led by verb and tva except by
+// paradigm, or by person verb, person and the first verb's tvas.
+pub fn grid_header(done: &[(Verb, Vec<Tva>)], layout: Layout) -> Vec<String> {
+    if layout == Layout::ByPerson {
+        let mut header = vec!["verb".to_string(), "person".to_string()];
+        if let Some((vb, reqs)) = done.first() {
+            header.extend(paradigms(vb, reqs).iter().map(|(tva, _)| tva.to_string()));
+        }
+        return header;
+    }
+    let all: Vec<&Paradigm> = done
+        .iter()
+        .flat_map(|(vb, reqs)| only(&paradigms(vb, reqs)))
+        .collect();
+    let cells = cells(&all);
+    let mut header = match layout {
+        Layout::Paradigm => Vec::new(),
+        _ => vec!["verb".to_string(), "tva".to_string()],
+    };
+    header.extend(cells.iter().map(|(cell, _)| cell.to_string()));
+    if romanized(done) {
+        header.extend(
+            cells
+                .iter()
+                .map(|(cell, _)| format!("{} transliteration", cell)),
+        );
+    }
+    if labelled(done) {
+        header.extend(cells.iter().map(|(cell, _)| format!("{} label", cell)));
+    }
+    header
+}
+
+pub fn long_header(done: &[(Verb, Vec<Tva>)], gloss: bool) -> Vec<String> {
+    let mut header: Vec<String> = [
+        "lemma", "stem", "tense", "voice", "mood", "person", "number", "form",
+    ]
+    .iter()
+    .map(|h| h.to_string())
+    .collect();
+    if romanized(done) {
+        header.push("transliteration".to_string());
+    }
+    if labelled(done) {
+        header.push("label".to_string());
+    }
+    if gloss {
+        header.push("gloss".to_string());
+    }
+    header
+}
+
+// One row per form, labelled: lemma, stem, tense, voice, mood, person,
+// number, form, the romanization and label if there are any, and with
+// gloss set the verb's gloss, empty for a verb the lexicon has none for.
+pub fn long_grid(done: &[(Verb, Vec<Tva>)], gloss: bool) -> Vec<Vec<String>> {
+    let mut grid = Vec::new();
+    for (vb, reqs) in done {
+        for (tva, paradigm) in paradigms(vb, reqs) {
+            let stem = vb.stem_for(tva);
+            for form in paradigm.forms() {
+                let mut row = vec![
+                    vb.lemma.clone(),
+                    stem.to_string(),
+                    form.tense.to_string(),
+                    form.voice.to_string(),
+                    form.mood.to_string(),
+                    form.person.ordinal().to_string(),
+                    form.number.name().to_string(),
+                    form.text.clone(),
+                ];
+                row.extend(form.romanized.clone());
+                row.extend(form.label.clone());
+                if gloss {
+                    row.push(vb.gloss.clone());
+                }
+                grid.push(row);
+            }
+        }
+    }
+    grid
+}
+
+// Comma-separated rows with no header, for further processing.
+pub fn plain(done: &[(Verb, Vec<Tva>)], layout: Layout) -> String {
+    let mut out = String::new();
+    for row in grid(done, layout) {
+        out.push_str(&row.join(", "));
+        out.push('\n');
+    }
+    out
+}
+
+// The plain rows, tab-separated.
+pub fn tsv(done: &[(Verb, Vec<Tva>)], layout: Layout) -> String {
+    let mut out = String::new();
+    for row in grid(done, layout) {
+        out.push_str(&row.join("\t"));
+        out.push('\n');
+    }
+    out
+}
+
+// Aligned columns under a title line, for reading in a terminal.
+pub fn table(done: &[(Verb, Vec<Tva>)], layout: Layout) -> String {
+    aligned(&tables(done, layout))
+}
+
+// A paradigm as the table format prints it, titled by its tva alone.
+pub fn paradigm_string(paradigm: &Paradigm) -> String {
+    aligned(&[paradigm_table(
+        paradigm,
+        heading(paradigm.tva(), paradigm.voice()),
+    )])
+}
+
+pub fn aligned(tables: &[Table]) -> String {
+    let mut out = String::new();
+    for (n, table) in tables.iter().enumerate() {
+        if n > 0 {
+            out.push('\n');
+        }
+        let mut widths: Vec<usize> = table.head.iter().map(|h| width(h)).collect();
+        for (label, cells) in &table.rows {
+            widths[0] = widths[0].max(width(label));
+            for (i, cell) in cells.iter().enumerate() {
+                widths[i + 1] = widths[i + 1].max(width(cell));
+            }
+        }
+        let line = |cells: Vec<&str>| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &w)| pad(cell, w))
+                .collect();
+            format!("{}\n", padded.join("  ").trim_end())
+        };
+        out.push_str(&format!("{}\n", table.title));
+        out.push_str(&line(table.head.iter().map(String::as_str).collect()));
+        for (label, cells) in &table.rows {
+            let mut row = vec![label.as_str()];
+            row.extend(cells.iter().map(String::as_str));
+            out.push_str(&line(row));
+        }
+    }
+    out
+}
+
+// With links set, each lemma links to Logeion and each form to Perseus.
+pub fn markdown(done: &[(Verb, Vec<Tva>)], layout: Layout, links: bool) -> String {
+    let links = if links { Some(Links::new(done)) } else { None };
+    markdown_with(&tables(done, layout), links.as_ref())
+}
+
+pub fn markdown_tables(tables: &[Table]) -> String {
+    markdown_with(tables, None)
+}
+
+fn markdown_with(tables: &[Table], links: Option<&Links>) -> String {
+    let escape = |s: &str| s.replace('|', "\\|");
+    let link = |word: &str, url: &str| format!("[{}]({})", word, url);
+    let mut out = String::new();
+    for table in tables {
+        let title = match links {
+            Some(links) => links.title(&table.title, link, |s| s.to_string()),
+            None => table.title.clone(),
+        };
+        out.push_str(&format!("### {}\n\n", title));
+        out.push_str(&format!("| {} |\n", table.head.join(" | ")));
+        out.push_str(&format!("|{}\n", "---|".repeat(table.head.len())));
+        for (label, cells) in &table.rows {
+            let cells: Vec<String> = cells
+                .iter()
+                .map(|c| match links {
+                    Some(links) => links.cell(c, link, escape),
+                    None => escape(c),
+                })
+                .collect();
+            out.push_str(&format!("| {} | {} |\n", label, cells.join(" | ")));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// What --links points to: Logeion's entry for a lemma, and the Perseus
+// word study tool's analysis of a form. Only the verbs' lemmas are linked
+// in titles, not the stems of verbs without one; in cells, every Greek
+// word is a form.
+struct Links {
+    lemmas: Vec<String>,
+}
+
+impl Links {
+    fn new(done: &[(Verb, Vec<Tva>)]) -> Self {
+        Links {
+            lemmas: done
+                .iter()
+                .map(|(vb, _)| vb.lemma.clone())
+                .filter(|lemma| !lemma.is_empty())
+                .collect(),
+        }
+    }
+
+    fn title(
+        &self,
+        text: &str,
+        link: impl Fn(&str, &str) -> String,
+        escape: impl Fn(&str) -> String,
+    ) -> String {
+        linked(text, &link, &escape, |word| {
+            if self.lemmas.iter().any(|lemma| lemma == word) {
+                Some(format!("https://logeion.uchicago.edu/{}", word))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn cell(
+        &self,
+        text: &str,
+        link: impl Fn(&str, &str) -> String,
+        escape: impl Fn(&str) -> String,
+    ) -> String {
+        linked(text, &link, &escape, |word| {
+            Some(format!(
+                "https://www.perseus.tufts.edu/hopper/morph?l={}&la=greek",
+                word
+            ))
+        })
+    }
+}
+
+fn is_greek(c: char) -> bool {
+    matches!(c, '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}')
+}
+
+// The text with each Greek word that url gives an address for made a link
+// to it, and everything else escaped.
+fn linked(
+    text: &str,
+    link: impl Fn(&str, &str) -> String,
+    escape: impl Fn(&str) -> String,
+    url: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let greek = rest.starts_with(is_greek);
+        // A word runs on through any combining accents.
+        let mut end = rest
+            .char_indices()
+            .find(|&(_, c)| (is_greek(c) || (greek && is_combining_mark(c))) != greek)
+            .map_or(rest.len(), |(i, _)| i);
+        // As does a movable nu written "(ν)", which is looked up with it.
+        if greek && rest[end..].starts_with("(ν)") {
+            end += "(ν)".len();
+        }
+        let (run, tail) = rest.split_at(end);
+        match url(&run.replace("(ν)", "ν")).filter(|_| greek) {
+            Some(url) => out.push_str(&link(&escape(run), &escape(&url))),
+            None => out.push_str(&escape(run)),
+        }
+        rest = tail;
+    }
+    out
+}
+
+// Escapes TeX's special characters, which Beta Code output can contain;
+// polytonic Greek itself goes through untouched for XeLaTeX or LuaLaTeX.
+pub fn latex_escape(s: &str) -> String {
+    let mut out = String::new();
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '|' => out.push_str("\\textbar{}"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+// A booktabs tabular per paradigm, headed by its title.
+pub fn latex(done: &[(Verb, Vec<Tva>)], layout: Layout) -> String {
+    latex_tables(&tables(done, layout))
+}
+
+pub fn latex_tables(tables: &[Table]) -> String {
+    let mut out =
+        String::from("% Needs \\usepackage{booktabs} and a Greek font (XeLaTeX or LuaLaTeX).\n");
+    for table in tables {
+        out.push_str("\\begin{center}\n");
+        out.push_str(&format!(
+            "\\textbf{{{}}}\\\\[0.5ex]\n",
+            latex_escape(&table.title)
+        ));
+        out.push_str(&format!(
+            "\\begin{{tabular}}{{{}}}\n",
+            "l".repeat(table.head.len())
+        ));
+        out.push_str("\\toprule\n");
+        out.push_str(&format!("{} \\\\\n", table.head.join(" & ")));
+        out.push_str("\\midrule\n");
+        for (label, cells) in &table.rows {
+            let cells: Vec<String> = cells.iter().map(|c| latex_escape(c)).collect();
+            out.push_str(&format!("{} & {} \\\\\n", label, cells.join(" & ")));
+        }
+        out.push_str("\\bottomrule\n");
+        out.push_str("\\end{tabular}\n");
+        out.push_str("\\end{center}\n\n");
+    }
+    out
+}
+
+pub fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// A table per paradigm, classed by tense, voice and mood (e.g. "tense-aorist
+// voice-middle-passive") so a stylesheet can colour them, marked lang="grc".
+// With links set, each lemma links to Logeion and each form to Perseus.
+pub fn html(done: &[(Verb, Vec<Tva>)], layout: Layout, links: bool) -> String {
+    let links = if links { Some(Links::new(done)) } else { None };
+    html_with(&tables(done, layout), links.as_ref())
+}
+
+pub fn html_tables(tables: &[Table]) -> String {
+    html_with(tables, None)
+}
+
+fn html_with(tables: &[Table], links: Option<&Links>) -> String {
+    let link = |word: &str, url: &str| format!("<a href=\"{}\">{}</a>", url, word);
+    let mut out = String::new();
+    for table in tables {
+        match &table.tva {
+            Some(tva) => {
+                out.push_str(&format!(
+                    "<table class=\"paradigm tense-{} voice-{} mood-{}\" lang=\"grc\">\n",
+                    tva.tense,
+                    tva.voice.name().replace('/', "-"),
+                    tva.mood
+                ));
+            }
+            None => out.push_str("<table class=\"paradigm\" lang=\"grc\">\n"),
+        }
+        let title = match links {
+            Some(links) => links.title(&table.title, link, html_escape),
+            None => html_escape(&table.title),
+        };
+        out.push_str(&format!("  <caption>{}</caption>\n", title));
+        out.push_str("  <thead>\n    <tr>");
+        for head in &table.head {
+            out.push_str(&format!("<th>{}</th>", head));
+        }
+        out.push_str("</tr>\n  </thead>\n  <tbody>\n");
+        for (label, cells) in &table.rows {
+            out.push_str(&format!("    <tr><th scope=\"row\">{}</th>", label));
+            for cell in cells {
+                let cell = match links {
+                    Some(links) => links.cell(cell, link, html_escape),
+                    None => html_escape(cell),
+                };
+                out.push_str(&format!("<td>{}</td>", cell));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("  </tbody>\n</table>\n");
+    }
+    out
+}
+
+fn ordinal(person: Person) -> &'static str {
+    match person {
+        Person::First => "1st",
+        Person::Second => "2nd",
+        Person::Third => "3rd",
+    }
+}
+
+/// A form's parse spelled out, as --labels prints it beside the form,
+/// e.g. "3rd person plural, aorist passive indicative".
+pub fn label(form: &Form) -> String {
+    format!(
+        "{} person {}, {} {} {}",
+        ordinal(form.person),
+        form.number.name(),
+        form.tense,
+        form.voice,
+        form.mood
+    )
+}
+
+// A form's parse, such as "3rd sg. aorist passive indicative".
+pub fn parse(form: &Form) -> String {
+    let number = match form.number {
+        Number::Sg => "sg.",
+        Number::Pl => "pl.",
+    };
+    format!(
+        "{} {} {} {} {}",
+        ordinal(form.person),
+        number,
+        form.tense,
+        form.voice,
+        form.mood
+    )
+}
+
+// One form to be learnt, with what a flashcard says about it.
+pub struct Card {
+    pub form: String,
+    pub parse: String,
+    pub lemma: String,
+    pub gloss: String,
+    pub tva: Tva,
+}
+
+// A card per form of every paradigm, variants included.
+pub fn cards(done: &[(Verb, Vec<Tva>)]) -> Vec<Card> {
+    let mut cards = Vec::new();
+    for (vb, reqs) in done {
+        for (tva, paradigm) in paradigms(vb, reqs) {
+            for form in paradigm.forms() {
+                cards.push(Card {
+                    form: form.text.clone(),
+                    parse: parse(form),
+                    lemma: name(vb, tva),
+                    gloss: vb.gloss.clone(),
+                    tva,
+                });
+            }
+        }
+    }
+    cards
+}
+
+// Tab-separated notes for Anki's text import, one per form: the form, its
+// parse, the lemma (or stem) and gloss, and tags naming the tva and its
+// tense system. The header lines tell Anki how to read the file.
+pub fn anki(done: &[(Verb, Vec<Tva>)]) -> String {
+    let mut out = String::from("#separator:tab\n#html:false\n#tags column:5\n");
+    for card in cards(done) {
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{}\tgkverb {} {}\n",
+            card.form,
+            card.parse,
+            card.lemma,
+            card.gloss,
+            card.tva.system(),
+            card.tva
+        ));
+    }
+    out
+}
+
+// Term and definition pairs for Quizlet's import box, the term being the
+// form and the definition its parse and the gloss, separated by the given
+// delimiter, one card per line.
+pub fn quizlet(done: &[(Verb, Vec<Tva>)], delimiter: &str) -> String {
+    let mut out = String::new();
+    for card in cards(done) {
+        out.push_str(&format!(
+            "{}{}{}\n",
+            card.form,
+            delimiter,
+            definition(&card)
+        ));
+    }
+    out
+}
+
+// A card's parse, lemma and any gloss, e.g. "1st sg. present active
+// indicative of λύω (loose)".
+fn definition(card: &Card) -> String {
+    match card.gloss.as_str() {
+        "" => format!("{} of {}", card.parse, card.lemma),
+        gloss => format!("{} of {} ({})", card.parse, card.lemma, gloss),
+    }
+}
+
+// The tense systems in the order they are learnt, each a level of a
+// Memrise course.
+const LEVELS: [(&str, &str); 6] = [
+    ("pres", "present system"),
+    ("fut", "future system"),
+    ("aor", "aorist system"),
+    ("perf", "perfect active system"),
+    ("perfmid", "perfect middle system"),
+    ("aorpass", "aorist passive system"),
+];
+
+// Words for Memrise's bulk add, a level per tense system: each level is
+// headed by a line "# Level N: ...", under which come its words, the form
+// and its definition (the parse, lemma and gloss, as for Quizlet)
+// separated by a tab, ready to paste into that level's bulk-add box.
+// Levels with no forms are left out and the rest numbered in turn.
+pub fn memrise(done: &[(Verb, Vec<Tva>)]) -> String {
+    let cards = cards(done);
+    let mut out = String::new();
+    let mut level = 0;
+    for (system, title) in LEVELS.iter() {
+        let words: Vec<&Card> = cards
+            .iter()
+            .filter(|card| card.tva.system() == *system)
+            .collect();
+        if words.is_empty() {
+            continue;
+        }
+        level += 1;
+        if level > 1 {
+            out.push('\n');
+        }
+        out.push_str(&format!("# Level {}: {}\n", level, title));
+        for card in words {
+            out.push_str(&format!("{}\t{}\n", card.form, definition(card)));
+        }
+    }
+    out
+}
+
+// Escapes the characters GIFT gives a meaning to.
+fn gift_escape(s: &str) -> String {
+    let mut out = String::new();
+    for c in s.chars() {
+        if matches!(c, '~' | '=' | '#' | '{' | '}' | ':' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// Questions in Moodle's GIFT format, two per cell: a short answer asking
+// for the form from its parse, any variant being right, and a multiple
+// choice asking for the parse of the form. The wrong choices are the
+// parses of the next forms of the same verb that the form cannot have.
+pub fn gift(done: &[(Verb, Vec<Tva>)]) -> String {
+    let mut out = String::new();
+    for (vb, reqs) in done {
+        let paradigms = paradigms(vb, reqs);
+        let parses: Vec<(&str, String)> = paradigms
+            .iter()
+            .flat_map(|(_, paradigm)| paradigm.forms())
+            .map(|form| (form.text.as_str(), parse(form)))
+            .collect();
+        let mut choices: Vec<&str> = Vec::new();
+        for (_, text) in &parses {
+            if !choices.contains(&text.as_str()) {
+                choices.push(text);
+            }
+        }
+        for (tva, paradigm) in paradigms {
+            let lemma = name(vb, tva);
+            let of = match vb.gloss.as_str() {
+                "" => lemma.clone(),
+                gloss => format!("{} ({})", lemma, gloss),
+            };
+            out.push_str(&format!("// {}\n\n", title(vb, tva)));
+            for (cell, key) in cells(&[paradigm]) {
+                let forms = cell_forms(paradigm, key);
+                let first = match forms.first() {
+                    Some(first) => *first,
+                    None => continue,
+                };
+                let right = parse(first);
+                let answers: Vec<String> = forms
+                    .iter()
+                    .map(|form| format!("={}", gift_escape(&form.text)))
+                    .collect();
+                out.push_str(&format!(
+                    "::{} {}.{}::Give the {} of {}. {{{}}}\n\n",
+                    gift_escape(&lemma),
+                    tva,
+                    cell,
+                    right,
+                    gift_escape(&of),
+                    answers.join(" ")
+                ));
+                // The form's own parses, which none of the wrong choices
+                // may be: ἔλυον is 1st sg. and 3rd pl. alike.
+                let own: Vec<&str> = parses
+                    .iter()
+                    .filter(|(text, _)| *text == first.text)
+                    .map(|(_, parse)| parse.as_str())
+                    .collect();
+                let start = choices.iter().position(|c| *c == right).unwrap_or(0);
+                let wrong: Vec<&str> = choices
+                    .iter()
+                    .cycle()
+                    .skip(start + 1)
+                    .take(choices.len())
+                    .filter(|c| !own.contains(c))
+                    .take(3)
+                    .copied()
+                    .collect();
+                out.push_str(&format!(
+                    "::{} {}.{} parse::Parse {} (from {}). {{\n\t={}\n",
+                    gift_escape(&lemma),
+                    tva,
+                    cell,
+                    gift_escape(&first.text),
+                    gift_escape(&lemma),
+                    right
+                ));
+                for choice in wrong {
+                    out.push_str(&format!("\t~{}\n", choice));
+                }
+                out.push_str("}\n\n");
+            }
+        }
+    }
+    out
+}
+
+// A question asking for a form from its parse, with the answers to choose
+// from and which of them is right.
+pub struct Choice {
+    pub question: String,
+    pub answers: Vec<String>,
+    pub correct: usize,
+}
+
+// A question per cell, as for a game of Kahoot: the right form and, as
+// the wrong answers, up to count - 1 of the verb's other forms, those of
+// the cells that follow first. The right answer's place goes round in
+// turn, so that it is not always the first.
+pub fn choices(done: &[(Verb, Vec<Tva>)], count: usize) -> Vec<Choice> {
+    let mut out = Vec::new();
+    for (vb, reqs) in done {
+        let paradigms = paradigms(vb, reqs);
+        let mut texts: Vec<&str> = Vec::new();
+        for form in paradigms.iter().flat_map(|(_, paradigm)| paradigm.forms()) {
+            if !texts.contains(&form.text.as_str()) {
+                texts.push(&form.text);
+            }
+        }
+        for (tva, paradigm) in &paradigms {
+            let of = match vb.gloss.as_str() {
+                "" => name(vb, *tva),
+                gloss => format!("{} ({})", name(vb, *tva), gloss),
+            };
+            for (_, key) in cells(&[paradigm]) {
+                let forms = cell_forms(paradigm, key);
+                let first = match forms.first() {
+                    Some(first) => *first,
+                    None => continue,
+                };
+                let start = texts.iter().position(|t| *t == first.text).unwrap_or(0);
+                let mut answers: Vec<String> = texts
+                    .iter()
+                    .cycle()
+                    .skip(start + 1)
+                    .take(texts.len())
+                    .filter(|t| !forms.iter().any(|form| form.text == **t))
+                    .take(count.saturating_sub(1))
+                    .map(|t| t.to_string())
+                    .collect();
+                if answers.is_empty() {
+                    continue;
+                }
+                let correct = out.len() % (answers.len() + 1);
+                answers.insert(correct, first.text.clone());
+                out.push(Choice {
+                    question: format!("{}: {}", of, parse(first)),
+                    answers,
+                    correct,
+                });
+            }
+        }
+    }
+    out
+}
+
+// The xml:id that a TEI @ana pointer uses for a label, e.g.
+// "middle/passive" -> "middle-passive".
+fn interp_id(label: &str) -> String {
+    label.replace('/', "-")
+}
+
+const TEI_INTERPS: [&str; 16] = [
+    "present",
+    "imperfect",
+    "future",
+    "aorist",
+    "perfect",
+    "pluperfect",
+    "active",
+    "middle",
+    "passive",
+    "middle/passive",
+    "indicative",
+    "singular",
+    "plural",
+    "first",
+    "second",
+    "third",
+];
+
+// A TEI document with an <entry> per verb. Each paradigm is a <form> whose
+// @ana points to its tense, voice and mood, holding a <form> per inflected
+// form whose @ana points to its person and number; the <interpGrp> in
+// <back> defines what the pointers refer to.
+pub fn tei(done: &[(Verb, Vec<Tva>)]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <TEI xmlns=\"http://www.tei-c.org/ns/1.0\">\n  \
+         <teiHeader>\n    <fileDesc>\n      \
+         <titleStmt><title>Generated Greek verb paradigms</title></titleStmt>\n      \
+         <publicationStmt><p>Generated by gkverb.</p></publicationStmt>\n      \
+         <sourceDesc><p>Born digital.</p></sourceDesc>\n    \
+         </fileDesc>\n  </teiHeader>\n  <text>\n    <body>\n",
+    );
+    for (vb, reqs) in done {
+        out.push_str("      <entry xml:lang=\"grc\">\n");
+        if !vb.lemma.is_empty() {
+            out.push_str(&format!(
+                "        <form type=\"lemma\"><orth>{}</orth></form>\n",
+                html_escape(&vb.lemma)
+            ));
+        }
+        for (tva, paradigm) in paradigms(vb, reqs) {
+            out.push_str(&format!(
+                "        <form type=\"paradigm\" ana=\"#{} #{} #{}\">\n",
+                tva.tense,
+                interp_id(tva.voice.name()),
+                tva.mood
+            ));
+            for form in paradigm.forms() {
+                let person = match form.person {
+                    Person::First => "first",
+                    Person::Second => "second",
+                    Person::Third => "third",
+                };
+                out.push_str(&format!(
+                    "          <form type=\"inflected\" ana=\"#{} #{}\"><orth>{}</orth></form>\n",
+                    person,
+                    form.number.name(),
+                    html_escape(&form.text)
+                ));
+            }
+            out.push_str("        </form>\n");
+        }
+        out.push_str("      </entry>\n");
+    }
+    out.push_str("    </body>\n    <back>\n      <interpGrp type=\"morphology\">\n");
+    for label in TEI_INTERPS.iter() {
+        out.push_str(&format!(
+            "        <interp xml:id=\"{}\">{}</interp>\n",
+            interp_id(label),
+            label
+        ));
+    }
+    out.push_str("      </interpGrp>\n    </back>\n  </text>\n</TEI>\n");
+    out
+}
+
+pub fn json(done: &[(Verb, Vec<Tva>)]) -> String {
+    let mut out = Vec::new();
+    for (vb, reqs) in done {
+        for (tva, paradigm) in paradigms(vb, reqs) {
+            let mut forms = Vec::new();
+            for form in paradigm.forms() {
+                let mut entry = json!({
+                    "person": form.person.ordinal(),
+                    "number": form.number.name(),
+                    "form": form.text,
+                });
+                if let Some(roman) = &form.romanized {
+                    entry["transliteration"] = Value::from(roman.as_str());
+                }
+                if let Some(label) = &form.label {
+                    entry["label"] = Value::from(label.as_str());
+                }
+                forms.push(entry);
+            }
+            let mut entry = json!({
+                "lemma": if vb.lemma.is_empty() { Value::Null } else { Value::from(vb.lemma.as_str()) },
+                "stem": vb.stem_for(tva),
+                "tva": tva.code(),
+                "tense": tva.tense.name(),
+                "voice": paradigm.voice().name(),
+                "mood": tva.mood.name(),
+                "forms": forms,
+            });
+            if !vb.gloss.is_empty() {
+                entry["gloss"] = Value::from(vb.gloss.as_str());
+            }
+            out.push(entry);
+        }
+    }
+    serde_json::to_string_pretty(&Value::Array(out)).unwrap_or_default()
+}
+
+// A form's Universal Dependencies features, in their alphabetical order,
+// with aspect and tense as the Ancient Greek treebanks give them: e.g.
+// "Aspect=Perf|Mood=Ind|Number=Plur|Person=3|Tense=Past|VerbForm=Fin|Voice=Pass"
+// for an aorist passive 3rd pl.
+pub fn feats(form: &Form) -> String {
+    let (aspect, tense) = match form.tense {
+        Tense::Present => (Some("Imp"), "Pres"),
+        Tense::Imperfect => (Some("Imp"), "Past"),
+        Tense::Future => (None, "Fut"),
+        Tense::Aorist => (Some("Perf"), "Past"),
+        Tense::Perfect => (Some("Perf"), "Pres"),
+        Tense::Pluperfect => (Some("Perf"), "Pqp"),
+    };
+    let mood = match form.mood {
+        Mood::Indicative => "Ind",
+    };
+    let number = match form.number {
+        Number::Sg => "Sing",
+        Number::Pl => "Plur",
+    };
+    let voice = match form.voice {
+        Voice::Active => "Act",
+        Voice::Middle => "Mid",
+        Voice::Passive => "Pass",
+        Voice::MiddlePassive => "Mid,Pass",
+    };
+    let mut feats = Vec::new();
+    if let Some(aspect) = aspect {
+        feats.push(format!("Aspect={}", aspect));
+    }
+    feats.push(format!("Mood={}", mood));
+    feats.push(format!("Number={}", number));
+    feats.push(format!("Person={}", form.person.ordinal()));
+    feats.push(format!("Tense={}", tense));
+    feats.push("VerbForm=Fin".to_string());
+    feats.push(format!("Voice={}", voice));
+    feats.join("|")
+}
+
+// The spellings a form stands for: one written with a movable nu, as
+// --nu both gives it, is both λυουσι and λυουσιν.
+fn spellings(text: &str) -> Vec<String> {
+    if text.contains("(ν)") {
+        vec![text.replace("(ν)", ""), text.replace("(ν)", "ν")]
+    } else {
+        vec![text.to_string()]
+    }
+}
+
+// A CoNLL-U fragment for seeding a treebank: each form a sentence of one
+// token, a VERB with its lemma and features, its id naming the verb, tva
+// and cell. A movable nu gives a sentence for each spelling. A bare stem
+// has no lemma, written "_".
+pub fn conllu(done: &[(Verb, Vec<Tva>)]) -> String {
+    let mut out = String::new();
+    for (vb, reqs) in done {
+        let lemma = if vb.lemma.is_empty() {
+            "_"
+        } else {
+            vb.lemma.as_str()
+        };
+        for (tva, paradigm) in paradigms(vb, reqs) {
+            for (cell, key) in cells(&[paradigm]) {
+                let forms = cell_forms(paradigm, key);
+                let mut seen: Vec<String> = Vec::new();
+                for form in forms {
+                    for text in spellings(&form.text) {
+                        if seen.contains(&text) {
+                            continue;
+                        }
+                        let mut id = format!("{}-{}-{}", name(vb, tva), tva, cell);
+                        if !seen.is_empty() {
+                            id.push_str(&format!("-{}", seen.len() + 1));
+                        }
+                        out.push_str(&format!(
+                            "# sent_id = {}\n# text = {}\n1\t{}\t{}\tVERB\t_\t{}\t0\troot\t_\t_\n\n",
+                            id,
+                            text,
+                            text,
+                            lemma,
+                            feats(form)
+                        ));
+                        seen.push(text);
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+// JSON Lines: an object per form, on a line of its own, with its verb's
+// lemma, stem and gloss and its paradigm's tva beside the keys json gives
+// each form, so that every line stands alone.
+pub fn jsonl(done: &[(Verb, Vec<Tva>)]) -> String {
+    let mut out = String::new();
+    for (vb, reqs) in done {
+        for (tva, paradigm) in paradigms(vb, reqs) {
+            for form in paradigm.forms() {
+                let mut entry = json!({
+                    "lemma": if vb.lemma.is_empty() { Value::Null } else { Value::from(vb.lemma.as_str()) },
+                    "stem": vb.stem_for(tva),
+                    "tva": tva.code(),
+                    "tense": form.tense.name(),
+                    "voice": form.voice.name(),
+                    "mood": form.mood.name(),
+                    "person": form.person.ordinal(),
+                    "number": form.number.name(),
+                    "form": form.text,
+                });
+                if !vb.gloss.is_empty() {
+                    entry["gloss"] = Value::from(vb.gloss.as_str());
+                }
+                if let Some(roman) = &form.romanized {
+                    entry["transliteration"] = Value::from(roman.as_str());
+                }
+                if let Some(label) = &form.label {
+                    entry["label"] = Value::from(label.as_str());
+                }
+                out.push_str(&entry.to_string());
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{conj_reqs, Nu};
+
+    fn done(spec: &str, tva: &str, nu: Nu) -> Vec<(Verb, Vec<Tva>)> {
+        let mut vb = Verb::new(spec).unwrap();
+        vb.nu = nu;
+        let reqs = vec![tva.parse().unwrap()];
+        conj_reqs(&mut vb, &reqs).unwrap();
+        vec![(vb, reqs)]
+    }
+
+    #[test]
+    fn heads_a_deponent_aorist_as_the_plain_aorist() {
+        let mut vb = Verb::from_lemma("βούλομαι", crate::lexicon::builtin()).unwrap();
+        let ami: Tva = "ami".parse().unwrap();
+        conj_reqs(&mut vb, &[ami]).unwrap();
+        assert_eq!(
+            title(&vb, ami),
+            "βούλομαι: aorist indicative, passive in form (ami)"
+        );
+        let paradigm = vb.paradigm(ami).unwrap();
+        assert!(paradigm
+            .to_string()
+            .starts_with("aorist indicative, passive in form (ami)"));
+        assert_eq!(
+            parse(
+                paradigm
+                    .variants(Person::Third, Number::Sg)
+                    .first()
+                    .unwrap()
+            ),
+            "3rd sg. aorist passive indicative"
+        );
+    }
+
+    #[test]
+    fn puts_each_nu_variant_on_its_own_row() {
+        let done = done("pres:λυ", "pai", Nu::Rows);
+        let tables = tables(&done, Layout::Paradigm);
+        let third: Vec<&str> = tables[0]
+            .rows
+            .iter()
+            .filter(|(cell, _)| cell == "3pl")
+            .map(|(_, cells)| cells[0].as_str())
+            .collect();
+        assert_eq!(third, ["λυουσι", "λυουσιν"]);
+        assert!(!markdown_tables(&tables).contains(" / "));
+        assert!(html_tables(&tables).contains("<td>λυουσιν</td>"));
+        assert!(latex_tables(&tables).contains("3pl & λυουσιν"));
+    }
+
+    #[test]
+    fn writes_each_spelling_of_a_movable_nu_in_conllu() {
+        let out = conllu(&done("pres:λυ", "pai", Nu::Both));
+        assert!(!out.contains("(ν)"));
+        assert!(out.contains("# sent_id = λυ-pai-3pl\n# text = λυουσι\n"));
+        assert!(out.contains("# sent_id = λυ-pai-3pl-2\n# text = λυουσιν\n"));
+    }
+
+    #[test]
+    fn writes_a_flat_grid_by_paradigm_and_by_person() {
+        let done = done("pres:λυ", "pai", Nu::Omit);
+        assert_eq!(
+            grid_header(&done, Layout::Paradigm),
+            ["1sg", "2sg", "3sg", "1pl", "2pl", "3pl"]
+        );
+        assert_eq!(
+            grid(&done, Layout::Paradigm),
+            [["λυω", "λυεις", "λυει", "λυομεν", "λυετε", "λυουσι"]]
+        );
+        assert_eq!(
+            grid_header(&done, Layout::ByPerson),
+            ["verb", "person", "pai"]
+        );
+        assert_eq!(grid(&done, Layout::ByPerson)[5], ["λυ", "3pl", "λυουσι"]);
+        assert_eq!(
+            grid(&done, Layout::ByTense),
+            [[
+                "λυ",
+                "pai",
+                "λυω",
+                "λυεις",
+                "λυει",
+                "λυομεν",
+                "λυετε",
+                "λυουσι"
+            ]]
+        );
+        assert_eq!(
+            plain(&done, Layout::Paradigm),
+            "λυω, λυεις, λυει, λυομεν, λυετε, λυουσι\n"
+        );
+    }
+
+    #[test]
+    fn writes_a_long_row_per_form() {
+        let done = done("pres:λυ", "pai", Nu::Omit);
+        assert_eq!(long_header(&done, true).last().unwrap(), "gloss");
+        let rows = long_grid(&done, false);
+        assert_eq!(rows.len(), 6);
+        assert_eq!(
+            rows[2],
+            [
+                "",
+                "λυ",
+                "present",
+                "active",
+                "indicative",
+                "3",
+                "singular",
+                "λυει"
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_a_json_line_per_form() {
+        let out = jsonl(&done("pres:λυ", "pai", Nu::Omit));
+        let lines: Vec<Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0]["lemma"], Value::Null);
+        assert_eq!(lines[0]["tva"], "pai");
+        assert_eq!(lines[5]["person"], 3);
+        assert_eq!(lines[5]["form"], "λυουσι");
+        let whole: Value = serde_json::from_str(&json(&done("pres:λυ", "pai", Nu::Omit))).unwrap();
+        assert_eq!(whole[0]["forms"][1]["form"], "λυεις");
+    }
+
+    #[test]
+    fn writes_a_gift_question_pair_per_cell() {
+        let out = gift(&done("pres:λυ", "pai", Nu::Omit));
+        assert!(
+            out.contains("::λυ pai.1sg::Give the 1st sg. present active indicative of λυ. {=λυω}")
+        );
+        assert!(out.contains("::λυ pai.1sg parse::Parse λυω (from λυ). {"));
+        assert!(out.contains("\t=1st sg. present active indicative\n"));
+        assert_eq!(out.matches("parse::").count(), 6);
+        assert_eq!(gift_escape("a=b:{c}"), "a\\=b\\:\\{c\\}");
+    }
+
+    #[test]
+    fn offers_a_cells_form_among_the_verbs_others() {
+        let done = done("pres:παυ", "pai", Nu::Omit);
+        let asked = choices(&done, 4);
+        assert_eq!(asked.len(), 6);
+        let first = &asked[0];
+        assert_eq!(first.question, "παυ: 1st sg. present active indicative");
+        assert_eq!(first.answers, ["παυω", "παυεις", "παυει", "παυομεν"]);
+        assert_eq!(first.correct, 0);
+        // The right answer's place goes round.
+        assert_eq!(asked[1].correct, 1);
+        assert_eq!(asked[1].answers[1], "παυεις");
+        assert!(asked.iter().all(|c| c.answers.len() == 4));
+        assert_eq!(choices(&done, 1).len(), 0);
+    }
+
+    #[test]
+    fn writes_a_tei_entry_per_verb() {
+        let out = tei(&done("pres:λυ", "pai", Nu::Omit));
+        assert!(out.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<TEI"));
+        assert!(out.contains("<form type=\"paradigm\" ana=\"#present #active #indicative\">"));
+        assert!(out.contains(
+            "<form type=\"inflected\" ana=\"#third #plural\"><orth>λυουσι</orth></form>"
+        ));
+        assert!(out.contains("<interp xml:id=\"present\">present</interp>"));
+        assert!(out.trim_end().ends_with("</TEI>"));
+    }
+
+    #[test]
+    fn escapes_latex_and_html() {
+        assert_eq!(latex_escape("a_b & 50% #1"), "a\\_b \\& 50\\% \\#1");
+        assert_eq!(latex_escape("*)/a\\"), "*)/a\\textbackslash{}");
+        assert_eq!(
+            latex_escape("~^|"),
+            "\\textasciitilde{}\\textasciicircum{}\\textbar{}"
+        );
+        assert_eq!(latex_escape("λυω"), "λυω");
+        assert_eq!(
+            html_escape("<b class=\"x\">&</b>"),
+            "&lt;b class=&quot;x&quot;&gt;&amp;&lt;/b&gt;"
+        );
+    }
+}
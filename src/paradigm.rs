@@ -0,0 +1,327 @@
+use crate::{output, Mood, Tense, Tva, Voice, CELLS};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+// The forms of one tva by person and number. A cell's variant spellings
+// (with and without movable nu, say) are kept in order, the usual one
+// first; printed flat, each variant after the first makes another row,
+// other cells repeating their first form. A romanization or a label, once
+// added, is kept on each form.
+//
+// With the serde feature a form is stored much as --format json prints
+// it, and a paradigm as the list of its forms.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "u32", try_from = "u32"))]
+pub enum Person {
+    First,
+    Second,
+    Third,
+}
+
+impl Person {
+    /// 1, 2 or 3.
+    pub fn ordinal(self) -> u32 {
+        match self {
+            Person::First => 1,
+            Person::Second => 2,
+            Person::Third => 3,
+        }
+    }
+}
+
+impl From<Person> for u32 {
+    fn from(person: Person) -> u32 {
+        person.ordinal()
+    }
+}
+
+impl TryFrom<u32> for Person {
+    type Error = String;
+
+    fn try_from(n: u32) -> Result<Self, String> {
+        match n {
+            1 => Ok(Person::First),
+            2 => Ok(Person::Second),
+            3 => Ok(Person::Third),
+            _ => Err(format!("person must be 1, 2 or 3, not {}", n)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Number {
+    #[cfg_attr(feature = "serde", serde(rename = "singular"))]
+    Sg,
+    #[cfg_attr(feature = "serde", serde(rename = "plural"))]
+    Pl,
+}
+
+impl Number {
+    /// "singular" or "plural".
+    pub fn name(self) -> &'static str {
+        match self {
+            Number::Sg => "singular",
+            Number::Pl => "plural",
+        }
+    }
+}
+
+/// The person and number of each of [`CELLS`], in order.
+pub const KEYS: [(Person, Number); 6] = [
+    (Person::First, Number::Sg),
+    (Person::Second, Number::Sg),
+    (Person::Third, Number::Sg),
+    (Person::First, Number::Pl),
+    (Person::Second, Number::Pl),
+    (Person::Third, Number::Pl),
+];
+
+/// The person and number of a cell written as in [`CELLS`], e.g. "3pl".
+pub fn cell_key(cell: &str) -> Option<(Person, Number)> {
+    CELLS.iter().position(|c| *c == cell).map(|i| KEYS[i])
+}
+
+/// One inflected form and what it is.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Form {
+    #[cfg_attr(feature = "serde", serde(rename = "form"))]
+    pub text: String,
+    pub person: Person,
+    pub number: Number,
+    pub tense: Tense,
+    pub voice: Voice,
+    pub mood: Mood,
+    /// The form romanized, if a romanization was added.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "transliteration",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub romanized: Option<String>,
+    /// What the form is, spelled out, if labels were added.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub label: Option<String>,
+}
+
+impl Form {
+    /// The form's person and number as written in overrides and tables,
+    /// e.g. "3pl".
+    pub fn cell(&self) -> &'static str {
+        let i = KEYS
+            .iter()
+            .position(|key| *key == (self.person, self.number))
+            .unwrap_or(0);
+        CELLS[i]
+    }
+}
+
+// Each cell's variants.
+type Cells = BTreeMap<(Person, Number), Vec<Form>>;
+
+// A paradigm's cells as the list of their forms, which carry their own
+// person and number.
+#[cfg(feature = "serde")]
+mod cells {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(cells: &Cells, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(KEYS.iter().filter_map(|key| cells.get(key)).flatten())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Cells, D::Error> {
+        let mut cells = Cells::new();
+        for form in Vec::<Form>::deserialize(d)? {
+            cells
+                .entry((form.person, form.number))
+                .or_default()
+                .push(form);
+        }
+        Ok(cells)
+    }
+}
+
+/// The forms of one tva, keyed by person and number.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Paradigm {
+    tva: Tva,
+    #[cfg_attr(feature = "serde", serde(with = "cells"))]
+    forms: Cells,
+}
+
+impl Paradigm {
+    /// The tva's forms, from each cell's variants in the order of [`KEYS`].
+    pub fn new(tva: Tva, cells: Vec<Vec<String>>) -> Self {
+        let mut paradigm = Paradigm {
+            tva,
+            forms: BTreeMap::new(),
+        };
+        for ((person, number), texts) in KEYS.iter().copied().zip(cells) {
+            let forms = texts
+                .into_iter()
+                .map(|text| paradigm.form(person, number, text))
+                .collect();
+            paradigm.forms.insert((person, number), forms);
+        }
+        paradigm
+    }
+
+    fn form(&self, person: Person, number: Number, text: String) -> Form {
+        Form {
+            text,
+            person,
+            number,
+            tense: self.tva.tense,
+            voice: self.voice(),
+            mood: self.tva.mood,
+            romanized: None,
+            label: None,
+        }
+    }
+
+    /// The tense, voice and mood of the paradigm.
+    pub fn tva(&self) -> Tva {
+        self.tva
+    }
+
+    /// The voice the forms are in: the tva's, save for a passive deponent's
+    /// aorist, which is passive in form.
+    pub fn voice(&self) -> Voice {
+        self.forms
+            .values()
+            .flatten()
+            .next()
+            .map_or(self.tva.voice, |form| form.voice)
+    }
+
+    /// Says the forms are in another voice than the tva's.
+    pub fn set_voice(&mut self, voice: Voice) {
+        for form in self.forms.values_mut().flatten() {
+            form.voice = voice;
+        }
+    }
+
+    /// The usual form of a cell.
+    pub fn get(&self, person: Person, number: Number) -> Option<&str> {
+        self.variants(person, number)
+            .first()
+            .map(|form| form.text.as_str())
+    }
+
+    /// Every spelling of a cell, the usual one first.
+    pub fn variants(&self, person: Person, number: Number) -> &[Form] {
+        self.forms.get(&(person, number)).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every form, cell by cell in the order of [`KEYS`]; a variant that
+    /// repeats one before it in its cell is left out.
+    pub fn forms(&self) -> impl Iterator<Item = &Form> {
+        KEYS.iter().flat_map(move |&(person, number)| {
+            let variants = self.variants(person, number);
+            variants
+                .iter()
+                .enumerate()
+                .filter(move |(j, form)| !variants[..*j].contains(form))
+                .map(|(_, form)| form)
+        })
+    }
+
+    pub fn is_romanized(&self) -> bool {
+        self.forms().any(|form| form.romanized.is_some())
+    }
+
+    pub fn is_labelled(&self) -> bool {
+        self.forms().any(|form| form.label.is_some())
+    }
+
+    /// Puts one form in a cell in place of all its variants.
+    pub fn set(&mut self, person: Person, number: Number, text: String) {
+        self.set_variants(person, number, vec![text]);
+    }
+
+    /// Puts the given spellings in a cell in place of its variants, the
+    /// usual one first.
+    pub fn set_variants(&mut self, person: Person, number: Number, texts: Vec<String>) {
+        let forms = texts
+            .into_iter()
+            .map(|text| self.form(person, number, text))
+            .collect();
+        self.forms.insert((person, number), forms);
+    }
+
+    /// Drops every cell but those of the given persons and numbers.
+    pub fn retain(&mut self, keys: &[(Person, Number)]) {
+        self.forms.retain(|key, _| keys.contains(key));
+    }
+
+    /// Rewrites every form, and any romanization, e.g. into another encoding.
+    pub fn map(&mut self, f: impl Fn(&str) -> String) {
+        for form in self.forms.values_mut().flatten() {
+            form.text = f(&form.text);
+            if let Some(romanized) = &mut form.romanized {
+                *romanized = f(romanized);
+            }
+        }
+    }
+
+    /// Adds a romanization of every form.
+    pub fn romanize(&mut self, f: impl Fn(&str) -> String) {
+        for form in self.forms.values_mut().flatten() {
+            form.romanized = Some(f(&form.text));
+        }
+    }
+
+    /// Adds a label to every form saying what it is, e.g. "3rd person
+    /// plural, aorist passive indicative".
+    pub fn label(&mut self) {
+        for form in self.forms.values_mut().flatten() {
+            form.label = Some(output::label(form));
+        }
+    }
+
+    /// The rows as printed flat: six forms, then any romanization of them,
+    /// then any labels.
+    pub fn rows(&self) -> Vec<Vec<String>> {
+        let n = self.forms.values().map(Vec::len).max().unwrap_or(0);
+        let romanized = self.is_romanized();
+        let labelled = self.is_labelled();
+        let pick = |i: usize| {
+            KEYS.iter().filter_map(move |key| {
+                let variants = self.forms.get(key)?;
+                variants.get(i).or_else(|| variants.first())
+            })
+        };
+        (0..n)
+            .map(|i| {
+                let mut row: Vec<String> = pick(i).map(|form| form.text.clone()).collect();
+                if romanized {
+                    row.extend(pick(i).map(|form| form.romanized.clone().unwrap_or_default()));
+                }
+                if labelled {
+                    row.extend(pick(i).map(|form| form.label.clone().unwrap_or_default()));
+                }
+                row
+            })
+            .collect()
+    }
+}
+
+/// The paradigm as the table format prints it, persons down the side.
+impl fmt::Display for Paradigm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&output::paradigm_string(self))
+    }
+}
@@ -0,0 +1,285 @@
+use crate::lexicon::Entry;
+use crate::{conj_reqs, derive, normalize_for_match, output, Form, Nu, Style, Tva, Verb, KEYS};
+use serde_json::{json, Value};
+use unicode_normalization::char::is_combining_mark;
+
+// The parse subcommand, conjugation in reverse: a form is looked for in
+// the paradigms of every verb in the lexicon and, failing that, of the
+// regular verbs it could come from, found by taking off any augment or
+// reduplication and trying each beginning of what is left as the stem of
+// an -ω verb. Accents are ignored, as the endings are unaccented.
+
+pub struct Analysis {
+    pub lemma: String,
+    pub tva: Tva,
+    // The form found, as the verb's paradigm spells it.
+    pub form: Form,
+    // Whether the verb is in the lexicon rather than guessed as regular.
+    pub known: bool,
+}
+
+impl Analysis {
+    pub fn parse(&self) -> String {
+        output::parse(&self.form)
+    }
+
+    // E.g. "ἐπαύοντο: 3rd pl. imperfect middle/passive indicative of παύω",
+    // a guessed verb being marked as such.
+    pub fn describe(&self, form: &str) -> String {
+        let guessed = if self.known {
+            ""
+        } else {
+            " (regular, not in the lexicon)"
+        };
+        format!("{}: {} of {}{}", form, self.parse(), self.lemma, guessed)
+    }
+
+    // As the serve and wasm APIs give it.
+    pub fn json(&self) -> Value {
+        json!({
+            "lemma": self.lemma,
+            "tva": self.tva.code(),
+            "parse": self.parse(),
+            "known": self.known,
+        })
+    }
+}
+
+// A form as it is looked up: without accents, breathings kept.
+pub fn comparable(s: &str) -> String {
+    normalize_for_match(s, false)
+}
+
+// The Greek words of a text, in order, each with the number of the line
+// it is on, counting from 1: runs of Greek letters and the accents and
+// breathings on them, everything else (punctuation, numbers, Latin
+// letters) parting them.
+pub fn words(text: &str) -> Vec<(usize, &str)> {
+    let greek = |c: char| matches!(c, '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}');
+    let mut out = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let mut start = None;
+        for (at, c) in line.char_indices() {
+            let inside = greek(c) || (start.is_some() && is_combining_mark(c));
+            match (start, inside) {
+                (None, true) => start = Some(at),
+                (Some(from), false) => {
+                    out.push((i + 1, &line[from..at]));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(from) = start {
+            out.push((i + 1, &line[from..]));
+        }
+    }
+    out
+}
+
+// A verb in every tva it has, with every variant ending.
+fn conjugated(mut vb: Verb) -> Verb {
+    vb.nu = Nu::Rows;
+    vb.style = Style::Both;
+    let reqs = vb.default_reqs();
+    // The verb has a stem for each of its default reqs, so none is refused.
+    conj_reqs(&mut vb, &reqs).ok();
+    vb
+}
+
+// The cells of a verb's paradigms that hold the form.
+fn find(vb: &Verb, word: &str, known: bool, out: &mut Vec<Analysis>) {
+    for tva in vb.default_reqs() {
+        let paradigm = match vb.paradigm(tva) {
+            Some(paradigm) => paradigm,
+            None => continue,
+        };
+        for (person, number) in KEYS {
+            let found = paradigm
+                .variants(person, number)
+                .iter()
+                .find(|form| comparable(&form.text) == word);
+            let seen = out.iter().any(|a| {
+                a.lemma == vb.lemma
+                    && a.tva == tva
+                    && (a.form.person, a.form.number) == (person, number)
+            });
+            if let (Some(form), false) = (found, seen) {
+                out.push(Analysis {
+                    lemma: vb.lemma.clone(),
+                    tva,
+                    form: form.clone(),
+                    known,
+                });
+            }
+        }
+    }
+}
+
+// πεπαυκ- -> παυκ-, τεθυκ- -> θυκ-: a consonant and ε before the same
+// consonant, or before its aspirate.
+fn unreduplicate(s: &str) -> Option<String> {
+    let chars: Vec<char> = s.chars().collect();
+    match chars.as_slice() {
+        [c, 'ε', d, ..] if c == d || matches!((c, d), ('π', 'φ') | ('τ', 'θ') | ('κ', 'χ')) => {
+            Some(chars[2..].iter().collect())
+        }
+        _ => None,
+    }
+}
+
+// The lemmas of the regular verbs a form could belong to.
+fn candidates(word: &str) -> Vec<String> {
+    let unaugmented = Verb::unaugmentings(word);
+    let mut bases = vec![word.to_string()];
+    bases.extend(unaugmented.iter().cloned());
+    bases.extend(unreduplicate(word));
+    bases.extend(unaugmented.iter().filter_map(|s| unreduplicate(s)));
+    let mut lemmas: Vec<String> = Vec::new();
+    for base in bases {
+        let chars: Vec<char> = base.chars().collect();
+        for end in 1..=chars.len() {
+            let lemma: String = chars[..end].iter().chain(['ω'].iter()).collect();
+            if !lemmas.contains(&lemma) {
+                lemmas.push(lemma);
+            }
+        }
+    }
+    lemmas
+}
+
+// The lexicon's verbs, conjugated once for all the forms to be parsed.
+pub struct Parser {
+    known: Vec<Verb>,
+}
+
+impl Parser {
+    pub fn new(entries: &[Entry]) -> Self {
+        // A user's entry comes before the built-in one of the same lemma.
+        let mut lemmas: Vec<&str> = Vec::new();
+        let mut known = Vec::new();
+        for entry in entries {
+            if lemmas.contains(&entry.lemma.as_str()) {
+                continue;
+            }
+            lemmas.push(&entry.lemma);
+            if let Ok(vb) = Verb::from_entry(entry) {
+                known.push(conjugated(vb));
+            }
+        }
+        Parser { known }
+    }
+
+    // The analyses of the form among the lexicon's verbs alone.
+    pub fn lookup(&self, word: &str) -> Vec<Analysis> {
+        let word = comparable(word);
+        let mut out = Vec::new();
+        for vb in &self.known {
+            find(vb, &word, true, &mut out);
+        }
+        out
+    }
+
+    pub fn analyses(&self, word: &str) -> Vec<Analysis> {
+        let out = self.lookup(word);
+        if !out.is_empty() {
+            return out;
+        }
+        let word = comparable(word);
+        let mut out = Vec::new();
+        for lemma in candidates(&word) {
+            if let Ok(stems) = derive::stems_from_lemma(&lemma) {
+                let mut vb = Verb::with_stems(stems);
+                vb.lemma = lemma;
+                find(&conjugated(vb), &word, false, &mut out);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexicon;
+
+    fn described(parser: &Parser, word: &str) -> Vec<String> {
+        parser
+            .analyses(word)
+            .iter()
+            .map(|a| a.describe(word))
+            .collect()
+    }
+
+    #[test]
+    fn takes_off_an_augment_or_reduplication() {
+        assert_eq!(unreduplicate("πεπαυκ").as_deref(), Some("παυκ"));
+        assert_eq!(unreduplicate("τεθυκ").as_deref(), Some("θυκ"));
+        assert_eq!(unreduplicate("παυκ"), None);
+        let lemmas = candidates("ἐχορευσαν");
+        assert!(lemmas.contains(&"χορευω".to_string()));
+        assert!(lemmas.contains(&"ἐχορευω".to_string()));
+        assert!(candidates("κεχορευκαμεν").contains(&"χορευω".to_string()));
+    }
+
+    #[test]
+    fn parses_the_forms_of_a_verb_in_the_lexicon() {
+        let parser = Parser::new(lexicon::builtin());
+        assert_eq!(
+            described(&parser, "ἐπαύοντο"),
+            ["ἐπαύοντο: 3rd pl. imperfect middle/passive indicative of παύω"]
+        );
+        let found = parser.analyses("πεπαύκαμεν");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].tva.code(), "pfai");
+        assert!(found[0].known);
+        assert_eq!(found[0].json()["parse"], found[0].parse());
+    }
+
+    #[test]
+    fn guesses_a_regular_verb_not_in_the_lexicon() {
+        let parser = Parser::new(lexicon::builtin());
+        for (word, parse) in [
+            ("χορεύομεν", "1st pl. present active indicative"),
+            ("ἐχόρευσαν", "3rd pl. aorist active indicative"),
+            ("κεχορεύκαμεν", "1st pl. perfect active indicative"),
+        ] {
+            assert_eq!(
+                described(&parser, word),
+                [format!(
+                    "{}: {} of χορευω (regular, not in the lexicon)",
+                    word, parse
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn finds_only_the_lexicons_verbs() {
+        let parser = Parser::new(lexicon::builtin());
+        let found = parser.lookup("ἐκελεύσαμεν");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].lemma, "κελεύω");
+        assert!(parser.lookup("χορεύομεν").is_empty());
+        assert!(!parser.analyses("χορεύομεν").is_empty());
+    }
+
+    #[test]
+    fn splits_a_text_into_its_greek_words_by_line() {
+        let text = "ὁ δὲ ἔλυσε τοὺς ἵππους.\nκαὶ ἐπαύσαμεν, 3 x.\n";
+        assert_eq!(
+            words(text),
+            [
+                (1, "ὁ"),
+                (1, "δὲ"),
+                (1, "ἔλυσε"),
+                (1, "τοὺς"),
+                (1, "ἵππους"),
+                (2, "καὶ"),
+                (2, "ἐπαύσαμεν"),
+            ]
+        );
+        // A decomposed accent stays on its word.
+        assert_eq!(words("ε\u{301}λυσε;"), [(1, "ε\u{301}λυσε")]);
+    }
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Quiz results kept between runs, in ~/.local/share/gkverb/progress.csv
+// (or the file given with --progress), one row per item asked, e.g.
+// "2nd pl. aorist passive indicative of παύω". Each item is scheduled as
+// in SM-2: a right answer puts off its next review by a growing interval,
+// a wrong one brings it back the next day and makes it come round sooner
+// from then on. quiz --due asks only the items whose review has come.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub item: String,
+    pub ease: f64,
+    pub interval: u32,
+    pub reps: u32,
+    pub due: u64,
+}
+
+pub struct Progress {
+    path: PathBuf,
+    items: BTreeMap<String, Item>,
+}
+
+// Days since 1970-01-01.
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86400)
+        .unwrap_or(0)
+}
+
+fn default_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(base.join("gkverb").join("progress.csv"))
+}
+
+// Reads the progress file, which need not exist yet.
+pub fn load(path: Option<&str>) -> Result<Progress, Box<dyn Error>> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => default_path().ok_or("cannot find a home directory for the progress file")?,
+    };
+    let mut items = BTreeMap::new();
+    if path.exists() {
+        let shown = path.display().to_string();
+        let mut rdr = csv::Reader::from_path(&path).map_err(|e| format!("{}: {}", shown, e))?;
+        for item in rdr.deserialize() {
+            let item: Item = item.map_err(|e| format!("{}: {}", shown, e))?;
+            items.insert(item.item.clone(), item);
+        }
+    }
+    Ok(Progress { path, items })
+}
+
+impl Progress {
+    // Whether an item has been asked before and is due for review.
+    pub fn is_due(&self, item: &str, today: u64) -> bool {
+        self.items.get(item).is_some_and(|i| i.due <= today)
+    }
+
+    pub fn record(&mut self, item: &str, right: bool, today: u64) {
+        let entry = self.items.entry(item.to_string()).or_insert(Item {
+            item: item.to_string(),
+            ease: 2.5,
+            interval: 0,
+            reps: 0,
+            due: today,
+        });
+        // SM-2 grades answers from 0 to 5; a right answer counts as 4 and a
+        // wrong one as 1.
+        let quality: f64 = if right { 4.0 } else { 1.0 };
+        if right {
+            entry.interval = match entry.reps {
+                0 => 1,
+                1 => 6,
+                _ => (entry.interval as f64 * entry.ease).round() as u32,
+            };
+            entry.reps += 1;
+        } else {
+            entry.interval = 1;
+            entry.reps = 0;
+        }
+        let miss = 5.0 - quality;
+        entry.ease = (entry.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(1.3);
+        entry.due = today + entry.interval as u64;
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let shown = self.path.display().to_string();
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", shown, e))?;
+        }
+        let mut wtr =
+            csv::Writer::from_path(&self.path).map_err(|e| format!("{}: {}", shown, e))?;
+        for item in self.items.values() {
+            wtr.serialize(item)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEM: &str = "2nd pl. aorist passive indicative of παύω";
+
+    fn fresh(name: &str) -> Progress {
+        let path = env::temp_dir().join(format!(
+            "gkverb-progress-{}-{}.csv",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_file(&path);
+        load(path.to_str()).unwrap()
+    }
+
+    #[test]
+    fn puts_off_a_right_answer_and_brings_back_a_wrong_one() {
+        let mut progress = fresh("schedule");
+        assert!(!progress.is_due(ITEM, 0));
+        progress.record(ITEM, true, 0);
+        assert_eq!(progress.items[ITEM].due, 1);
+        progress.record(ITEM, true, 1);
+        assert_eq!(progress.items[ITEM].due, 7);
+        assert!(!progress.is_due(ITEM, 6));
+        assert!(progress.is_due(ITEM, 7));
+        // Right answers leave the ease at 2.5, so the interval after 6 is 15.
+        progress.record(ITEM, true, 7);
+        assert_eq!(progress.items[ITEM].interval, 15);
+        progress.record(ITEM, false, 22);
+        let item = &progress.items[ITEM];
+        assert_eq!((item.interval, item.reps, item.due), (1, 0, 23));
+        assert!(item.ease < 2.5);
+    }
+
+    #[test]
+    fn keeps_the_ease_above_its_floor() {
+        let mut progress = fresh("floor");
+        for day in 0..20 {
+            progress.record(ITEM, false, day);
+        }
+        assert_eq!(progress.items[ITEM].ease, 1.3);
+    }
+
+    #[test]
+    fn reads_back_what_it_saved() {
+        let mut progress = fresh("saved");
+        progress.record(ITEM, true, 100);
+        progress.save().unwrap();
+        let again = load(progress.path.to_str()).unwrap();
+        assert!(again.is_due(ITEM, 101));
+        assert_eq!(again.items[ITEM].reps, 1);
+        fs::remove_file(&progress.path).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+use crate::parse::Parser;
+use crate::{conjugate_json, lexicon, ALL_TVAS};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+// The engine as a Python module, built with maturin (see pyproject.toml):
+//
+//     import gkverb
+//     gkverb.conjugate("pres:παυ", "pai")   # [{"tva": "pai", "forms": [...], ...}]
+//     gkverb.conjugate("λύω")               # every tva the verb has
+//     gkverb.parse("ἔλυσαν")                # [{"lemma": "λύω", "tva": "aai", ...}]
+//     gkverb.list_tvas()                    # ["pai", "ppi", ...]
+//
+// Paradigms come as --format json gives them, as lists and dicts. A verb
+// is a stem spec, six comma-separated principal parts or a lemma, looked
+// up in the built-in lexicon; bad input raises ValueError.
+
+// Built on the first parse, as it conjugates the whole lexicon.
+fn parser() -> &'static Parser {
+    static PARSER: OnceLock<Parser> = OnceLock::new();
+    PARSER.get_or_init(|| Parser::new(lexicon::builtin()))
+}
+
+fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+#[pyfunction]
+#[pyo3(signature = (verb, tvas = "all"))]
+fn conjugate<'py>(py: Python<'py>, verb: &str, tvas: &str) -> PyResult<Bound<'py, PyAny>> {
+    let json = conjugate_json(verb, tvas, lexicon::builtin())
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    from_json(py, &json)
+}
+
+#[pyfunction]
+fn parse<'py>(py: Python<'py>, form: &str) -> PyResult<Bound<'py, PyAny>> {
+    let analyses: Vec<Value> = parser().analyses(form).iter().map(|a| a.json()).collect();
+    from_json(py, &Value::from(analyses).to_string())
+}
+
+#[pyfunction]
+fn list_tvas() -> Vec<&'static str> {
+    ALL_TVAS.to_vec()
+}
+
+#[pymodule]
+fn gkverb(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(conjugate, m)?)?;
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_function(wrap_pyfunction!(list_tvas, m)?)?;
+    Ok(())
+}
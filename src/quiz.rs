@@ -0,0 +1,161 @@
+use greek_writer::output;
+use greek_writer::{normalize_for_match, Tva, Verb};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::io::{self, BufRead, Write};
+
+// The quiz subcommand: asks for forms by their parse ("2nd pl. aorist
+// passive indicative of παύω?"), checks each typed answer and gives the
+// score at the end. Any variant of a cell is a right answer. Accents never
+// count, since the forms are generated without them: an answer typed with
+// them is as right as one typed without. Breathings count unless checking
+// is loose (--breathings lenient); iota subscript and diaeresis always
+// count.
+
+// The item names what is asked, e.g. "2nd pl. aorist passive indicative
+// of παύω", and is the key its progress is kept under.
+pub struct Question {
+    pub item: String,
+    pub answers: Vec<String>,
+}
+
+// A question per cell of every paradigm, in random order.
+pub fn questions(done: &[(Verb, Vec<Tva>)], seed: u64) -> Vec<Question> {
+    let mut questions: Vec<Question> = Vec::new();
+    for card in output::cards(done) {
+        let item = format!("{} of {}", card.parse, card.lemma);
+        match questions.iter_mut().find(|q| q.item == item) {
+            Some(question) => question.answers.push(card.form),
+            None => questions.push(Question {
+                item,
+                answers: vec![card.form],
+            }),
+        }
+    }
+    questions.shuffle(&mut StdRng::seed_from_u64(seed));
+    questions
+}
+
+// How strictly answers are checked: breathings count, or they do not.
+#[derive(Clone, Copy)]
+pub enum Checking {
+    Lenient,
+    Loose,
+}
+
+impl Checking {
+    pub fn from_arg(breathings: Option<&str>) -> Self {
+        match breathings {
+            Some("lenient") => Checking::Loose,
+            _ => Checking::Lenient,
+        }
+    }
+}
+
+// A form as compared: composed, without accents, with a final sigma
+// however typed.
+fn comparable(s: &str, checking: Checking) -> String {
+    normalize_for_match(s, matches!(checking, Checking::Loose))
+}
+
+pub fn check(answer: &str, answers: &[String], checking: Checking) -> bool {
+    let answer = comparable(answer, checking);
+    answers.iter().any(|a| comparable(a, checking) == answer)
+}
+
+// Asks each question in turn, reading answers a line at a time until the
+// questions or the input run out. Returns, for each question asked,
+// whether it was answered rightly. Typed answers go through decode first,
+// e.g. from Beta Code.
+pub fn run(
+    questions: &[Question],
+    mut input: impl BufRead,
+    mut out: impl Write,
+    checking: Checking,
+    decode: impl Fn(&str) -> String,
+) -> io::Result<Vec<bool>> {
+    let mut results = Vec::new();
+    for question in questions {
+        write!(out, "{}? ", question.item)?;
+        out.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(out)?;
+            break;
+        }
+        let right = check(&decode(line.trim()), &question.answers, checking);
+        if right {
+            writeln!(out, "Right.")?;
+        } else {
+            writeln!(out, "No: {}", question.answers.join(" / "))?;
+        }
+        results.push(right);
+    }
+    let right = results.iter().filter(|&&r| r).count();
+    writeln!(out, "Score: {}/{}", right, results.len())?;
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greek_writer::conj_reqs;
+
+    fn answers(forms: &[&str]) -> Vec<String> {
+        forms.iter().map(|form| form.to_string()).collect()
+    }
+
+    #[test]
+    fn lets_accents_go_but_not_breathings() {
+        let right = answers(&["παυω"]);
+        assert!(check("παύω", &right, Checking::Lenient));
+        assert!(check(" παυω ", &right, Checking::Lenient));
+        let right = answers(&["ἐπαυσα"]);
+        assert!(check("ἔπαυσα", &right, Checking::Lenient));
+        assert!(!check("ἑπαυσα", &right, Checking::Lenient));
+        assert!(!check("επαυσα", &right, Checking::Lenient));
+        assert!(check("επαυσα", &right, Checking::Loose));
+        assert!(check("παυεισ", &answers(&["παυεις"]), Checking::Lenient));
+    }
+
+    #[test]
+    fn asks_every_cell_and_scores_the_answers() {
+        let mut vb = Verb::new("pres:παυ").unwrap();
+        let reqs = vec!["pai".parse().unwrap()];
+        conj_reqs(&mut vb, &reqs).unwrap();
+        let done = vec![(vb, reqs)];
+        let asked = questions(&done, 1);
+        assert_eq!(asked.len(), 6);
+        assert_eq!(
+            asked.iter().map(|q| &q.item).collect::<Vec<_>>(),
+            questions(&done, 1)
+                .iter()
+                .map(|q| &q.item)
+                .collect::<Vec<_>>()
+        );
+        let typed: Vec<String> = asked
+            .iter()
+            .map(|q| {
+                if q.item.starts_with("1st sg.") {
+                    "λυω".to_string()
+                } else {
+                    q.answers[0].clone()
+                }
+            })
+            .collect();
+        let mut out = Vec::new();
+        let results = run(
+            &asked,
+            typed.join("\n").as_bytes(),
+            &mut out,
+            Checking::Lenient,
+            str::to_string,
+        )
+        .unwrap();
+        assert_eq!(results.iter().filter(|&&r| r).count(), 5);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("No: παυω\n"));
+        assert!(out.ends_with("Score: 5/6\n"));
+    }
+}
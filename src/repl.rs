@@ -0,0 +1,179 @@
+use greek_writer::lexicon::Entry;
+use greek_writer::output::{self, Layout};
+use greek_writer::parse::Parser;
+use greek_writer::{
+    conj_reqs, parse_tvas, strip_accents, translit, Nu, Style, Tva, Verb, NUS, STYLES,
+};
+use std::io::{self, BufRead, Write};
+
+// The repl subcommand: one command per line, with the lexicon, and the
+// parser built from it, loaded once for the whole session.
+//
+//     pai λυ               a tva (or several, comma-separated) and a verb
+//     all παύω             every tva the verb has
+//     parse ἔλυσαν         the analyses of a form
+//     set nu rows          change an option for the rest of the session
+//
+// A verb is a lemma, six principal parts, a stem spec such as aor:ἐλυσ,
+// or a bare stem, taken to be of the first tva's tense system (the present
+// for all).
+
+const HELP: &str = "\
+pai λυ              conjugate a tva (or pai,iai,...) of a stem, lemma or principal parts
+all παύω            conjugate every tva of a verb
+parse ἔλυσαν        list the analyses of a form
+set nu VALUE        omit, show, both or rows
+set style VALUE     eta, ei or both
+set format VALUE    table, plain, tsv, markdown or json
+set transliterate   ala-lc, scientific or off
+help                show this
+quit                leave the repl
+";
+
+const SETTINGS: [(&str, &[&str]); 4] = [
+    ("nu", &NUS),
+    ("style", &STYLES),
+    ("format", &["table", "plain", "tsv", "markdown", "json"]),
+    ("transliterate", &["ala-lc", "scientific", "off"]),
+];
+
+pub struct Session {
+    entries: Vec<Entry>,
+    parser: Option<Parser>,
+    nu: Nu,
+    style: Style,
+    format: String,
+    transliterate: Option<translit::Scheme>,
+}
+
+impl Session {
+    pub fn new(entries: Vec<Entry>, nu: Nu, style: Style) -> Self {
+        Session {
+            entries,
+            parser: None,
+            nu,
+            style,
+            format: "table".to_string(),
+            transliterate: None,
+        }
+    }
+
+    fn verb(&self, word: &str, system: &str) -> Result<Verb, String> {
+        let bare = !word.contains(':') && !word.contains(',') && {
+            let word = strip_accents(word);
+            !word.ends_with('ω') && !word.ends_with("ομαι")
+        };
+        let vb = if bare {
+            Verb::new(&format!("{}:{}", system, word))
+        } else {
+            Verb::from_input(word, &self.entries)
+        };
+        vb.map_err(|e| e.to_string())
+    }
+
+    fn conjugate(&self, tvas: &str, word: &str) -> Result<String, String> {
+        let system = match tvas.split(',').next().unwrap_or_default() {
+            "all" => "pres",
+            first => first.parse::<Tva>().map_err(|e| e.to_string())?.system(),
+        };
+        let mut vb = self.verb(word, system)?;
+        vb.nu = self.nu;
+        vb.style = self.style;
+        let reqs = parse_tvas(&vb, tvas).map_err(|e| e.to_string())?;
+        conj_reqs(&mut vb, &reqs).map_err(|e| e.to_string())?;
+        if let Some(scheme) = self.transliterate {
+            vb.romanize(|form| translit::transliterate(form, scheme));
+        }
+        let done = vec![(vb, reqs)];
+        Ok(match self.format.as_str() {
+            "plain" => output::plain(&done, Layout::Paradigm),
+            "tsv" => output::tsv(&done, Layout::Paradigm),
+            "markdown" => output::markdown(&done, Layout::Paradigm, false),
+            "json" => format!("{}\n", output::json(&done)),
+            _ => output::table(&done, Layout::Paradigm),
+        })
+    }
+
+    fn parse(&mut self, forms: &[&str]) -> String {
+        let entries = &self.entries;
+        let parser = self.parser.get_or_insert_with(|| Parser::new(entries));
+        let mut out = String::new();
+        for form in forms {
+            let analyses = parser.analyses(form);
+            if analyses.is_empty() {
+                out.push_str(&format!("{}: no analysis found\n", form));
+            }
+            for analysis in analyses {
+                out.push_str(&format!("{}\n", analysis.describe(form)));
+            }
+        }
+        out
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<String, String> {
+        let choices = SETTINGS
+            .iter()
+            .find(|(setting, _)| *setting == name)
+            .map(|(_, choices)| *choices)
+            .ok_or_else(|| format!("no setting {}", name))?;
+        if !choices.contains(&value) {
+            return Err(format!("{} must be one of {}", name, choices.join(", ")));
+        }
+        match name {
+            "nu" => self.nu = Nu::from_arg(value).map_err(|e| e.to_string())?,
+            "style" => self.style = Style::from_arg(value).map_err(|e| e.to_string())?,
+            "format" => self.format = value.to_string(),
+            _ if value == "off" => self.transliterate = None,
+            _ => {
+                self.transliterate =
+                    Some(translit::Scheme::from_arg(value).map_err(|e| e.to_string())?)
+            }
+        }
+        Ok(String::new())
+    }
+
+    // The output of one line, or None to quit.
+    fn command(&mut self, line: &str) -> Option<Result<String, String>> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        Some(match words.as_slice() {
+            [] => Ok(String::new()),
+            ["quit"] | ["exit"] => return None,
+            ["help"] => Ok(HELP.to_string()),
+            ["parse", forms @ ..] if !forms.is_empty() => Ok(self.parse(forms)),
+            ["set", name, value] => self.set(name, value),
+            [tvas, word] => self.conjugate(tvas, word),
+            _ => Err("unknown command; type help for the commands".to_string()),
+        })
+    }
+}
+
+// Reads commands until quit or the end of the input, prompting if asked.
+pub fn run(
+    session: &mut Session,
+    input: impl BufRead,
+    mut out: impl Write,
+    prompt: bool,
+) -> io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        if prompt {
+            write!(out, "gkverb> ")?;
+            out.flush()?;
+        }
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => {
+                if prompt {
+                    writeln!(out)?;
+                }
+                break;
+            }
+        };
+        match session.command(&line) {
+            None => break,
+            Some(Ok(text)) => write!(out, "{}", text)?,
+            Some(Err(e)) => writeln!(out, "error: {}", e)?,
+        }
+    }
+    Ok(())
+}
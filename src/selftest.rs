@@ -0,0 +1,87 @@
+use greek_writer::lexicon::Entry;
+use greek_writer::{conj_reqs, Nu, Style, Tva, Verb, CELLS, KEYS};
+use serde::Deserialize;
+use std::error::Error;
+use std::fs;
+
+// The selftest subcommand: the tables are checked against reference
+// paradigms, those of data/paradigms.toml or a file of the same layout,
+// and each cell that differs is printed as a diff, the expected form
+// after - and the generated one after +; a paradigm that cannot be
+// generated is reported with the reason. Forms are generated with the
+// movable nu written "(ν)" and the 2nd sg. in -ῃ, as the references give
+// them.
+
+const BUILTIN: &str = include_str!("../data/paradigms.toml");
+
+// A paradigm as the grammars give it.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Reference {
+    verb: String,
+    tva: String,
+    forms: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct References {
+    paradigm: Vec<Reference>,
+}
+
+pub fn builtin() -> Vec<Reference> {
+    read(BUILTIN).expect("built-in reference paradigms are valid")
+}
+
+pub fn load(path: &str) -> Result<Vec<Reference>, Box<dyn Error>> {
+    let data = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(read(&data).map_err(|e| format!("{}: {}", path, e))?)
+}
+
+fn read(toml: &str) -> Result<Vec<Reference>, Box<dyn Error>> {
+    let file: References = toml::from_str(toml)?;
+    for reference in &file.paradigm {
+        if reference.forms.len() != 6 {
+            return Err(format!(
+                "{} {} has {} forms; expected 6",
+                reference.verb,
+                reference.tva,
+                reference.forms.len()
+            )
+            .into());
+        }
+    }
+    Ok(file.paradigm)
+}
+
+// The diff of a reference paradigm and the generated one, empty if they
+// agree; a verb or tva that cannot be generated is a difference too.
+pub fn diff(reference: &Reference, entries: &[Entry]) -> String {
+    let heading = format!("{} {}", reference.verb, reference.tva);
+    let generated = reference
+        .tva
+        .parse::<Tva>()
+        .map_err(|e| e.to_string())
+        .and_then(|tva| {
+            let mut vb = Verb::from_input(&reference.verb, entries).map_err(|e| e.to_string())?;
+            vb.nu = Nu::Both;
+            vb.style = Style::Eta;
+            conj_reqs(&mut vb, &[tva]).map_err(|e| e.to_string())?;
+            Ok(vb.paradigm(tva).cloned())
+        });
+    let paradigm = match generated {
+        Ok(Some(paradigm)) => paradigm,
+        Ok(None) => return format!("{}: nothing generated\n", heading),
+        Err(e) => return format!("{}: {}\n", heading, e),
+    };
+    let mut out = String::new();
+    for ((cell, (person, number)), expected) in CELLS.iter().zip(KEYS).zip(&reference.forms) {
+        let got = paradigm.get(person, number).unwrap_or_default();
+        if got != expected {
+            out.push_str(&format!(
+                "{} {}\n- {}\n+ {}\n",
+                heading, cell, expected, got
+            ));
+        }
+    }
+    out
+}
@@ -0,0 +1,222 @@
+use greek_writer::lexicon::Entry;
+use greek_writer::output;
+use greek_writer::parse::Parser;
+use greek_writer::{conj_reqs, parse_tvas, Nu, Style, Verb, ALL_TVAS};
+use serde_json::{json, Value};
+use std::error::Error;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// The serve subcommand: a small HTTP API answering in JSON, with the
+// lexicon loaded once for every request.
+//
+//     GET /conjugate?stem=pres:παυ&tva=pai     a stem spec, pp= or lemma=,
+//                                              and tva= (default all)
+//     GET /parse?form=ἔλυσαν&form=ἐπαύοντο     the analyses of each form
+//     GET /tvas                                the tva codes
+//
+// /conjugate answers as --format json does; nu= and style= take the values
+// of --nu and --style. Errors come back as {"error": "..."} with a 4xx
+// status. Any origin may call the API, so a web page can use it.
+
+struct State {
+    entries: Vec<Entry>,
+    parser: Option<Parser>,
+    nu: Nu,
+    style: Style,
+}
+
+type Answer = Result<Value, (u16, String)>;
+
+fn bad(message: impl Into<String>) -> (u16, String) {
+    (400, message.into())
+}
+
+// The values of a query parameter, with any comma-separated lists split.
+fn values<'a>(query: &'a [(String, String)], name: &str) -> Vec<&'a str> {
+    query
+        .iter()
+        .filter(|(key, _)| key == name)
+        .flat_map(|(_, value)| value.split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+fn value<'a>(query: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    query
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.trim())
+}
+
+impl State {
+    fn conjugate(&self, query: &[(String, String)]) -> Answer {
+        let mut vb = if let Some(stem) = value(query, "stem") {
+            Verb::new(stem)
+        } else if let Some(pp) = value(query, "pp") {
+            let parts: Vec<&str> = pp.split(',').map(str::trim).collect();
+            Verb::from_parts(&parts)
+        } else if let Some(lemma) = value(query, "lemma") {
+            Verb::from_lemma(lemma, &self.entries)
+        } else {
+            return Err(bad("give a stem, pp or lemma"));
+        }
+        .map_err(|e| bad(e.to_string()))?;
+        vb.nu = match value(query, "nu") {
+            Some(nu) => Nu::from_arg(nu).map_err(|e| bad(e.to_string()))?,
+            None => self.nu,
+        };
+        vb.style = match value(query, "style") {
+            Some(style) => Style::from_arg(style).map_err(|e| bad(e.to_string()))?,
+            None => self.style,
+        };
+        let tvas = values(query, "tva");
+        let reqs = if tvas.contains(&"all") {
+            vb.default_reqs()
+        } else {
+            parse_tvas(&vb, &tvas.join(",")).map_err(|e| bad(e.to_string()))?
+        };
+        conj_reqs(&mut vb, &reqs).map_err(|e| bad(e.to_string()))?;
+        let done = vec![(vb, reqs)];
+        serde_json::from_str(&output::json(&done)).map_err(|e| (500, e.to_string()))
+    }
+
+    fn parse(&mut self, query: &[(String, String)]) -> Answer {
+        let forms = values(query, "form");
+        if forms.is_empty() {
+            return Err(bad("give a form to parse"));
+        }
+        let entries = &self.entries;
+        let parser = self.parser.get_or_insert_with(|| Parser::new(entries));
+        let mut out = Vec::new();
+        for form in forms {
+            let analyses: Vec<Value> = parser.analyses(form).iter().map(|a| a.json()).collect();
+            out.push(json!({ "form": form, "analyses": analyses }));
+        }
+        Ok(Value::from(out))
+    }
+
+    fn answer(&mut self, request: &Request) -> Answer {
+        if *request.method() != Method::Get {
+            return Err((405, "only GET is supported".to_string()));
+        }
+        let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+        let query: Vec<(String, String)> = form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect();
+        match path {
+            "/conjugate" => self.conjugate(&query),
+            "/parse" => self.parse(&query),
+            "/tvas" => Ok(json!(ALL_TVAS)),
+            _ => Err((404, format!("no endpoint {}", path))),
+        }
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("a valid header")
+}
+
+// Answers requests until the process is stopped.
+pub fn serve(
+    host: &str,
+    port: u16,
+    entries: Vec<Entry>,
+    nu: Nu,
+    style: Style,
+) -> Result<(), Box<dyn Error>> {
+    let server = Server::http((host, port))
+        .map_err(|e| format!("cannot listen on {}:{}: {}", host, port, e))?;
+    eprintln!("Listening on http://{}:{}", host, port);
+    let mut state = State {
+        entries,
+        parser: None,
+        nu,
+        style,
+    };
+    for request in server.incoming_requests() {
+        let (status, body) = match state.answer(&request) {
+            Ok(body) => (200, body),
+            Err((status, message)) => (status, json!({ "error": message })),
+        };
+        let response = Response::from_string(format!("{}\n", body))
+            .with_status_code(status)
+            .with_header(header("Content-Type", "application/json; charset=utf-8"))
+            .with_header(header("Access-Control-Allow-Origin", "*"));
+        if let Err(e) = request.respond(response) {
+            eprintln!("cannot answer a request: {}", e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greek_writer::lexicon;
+
+    fn state() -> State {
+        State {
+            entries: lexicon::builtin().to_vec(),
+            parser: None,
+            nu: Nu::Omit,
+            style: Style::Eta,
+        }
+    }
+
+    fn query(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn splits_repeated_and_listed_values() {
+        let query = query(&[("tva", "pai, iai"), ("tva", "aai"), ("tva", "")]);
+        assert_eq!(values(&query, "tva"), ["pai", "iai", "aai"]);
+        assert_eq!(value(&query, "tva"), Some("pai, iai"));
+        assert_eq!(value(&query, "stem"), None);
+    }
+
+    #[test]
+    fn conjugates_as_format_json_does() {
+        let server = state();
+        let answer = server
+            .conjugate(&query(&[
+                ("stem", "pres:παυ"),
+                ("tva", "pai"),
+                ("nu", "show"),
+            ]))
+            .unwrap();
+        assert_eq!(answer[0]["tva"], "pai");
+        assert_eq!(answer[0]["forms"][5]["form"], "παυουσιν");
+        let answer = server
+            .conjugate(&query(&[("lemma", "παύω"), ("tva", "all")]))
+            .unwrap();
+        assert_eq!(answer.as_array().unwrap().len(), 14);
+    }
+
+    #[test]
+    fn answers_a_bad_request_with_its_error() {
+        let server = state();
+        let error = |pairs: &[(&str, &str)]| server.conjugate(&query(pairs)).unwrap_err();
+        assert_eq!(error(&[]), (400, "give a stem, pp or lemma".to_string()));
+        assert_eq!(error(&[("stem", "pres:παυ"), ("nu", "maybe")]).0, 400);
+        assert_eq!(error(&[("stem", "pres:παυ"), ("tva", "aai")]).0, 400);
+        assert_eq!(
+            state().parse(&[]).unwrap_err(),
+            (400, "give a form to parse".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_each_form_asked_about() {
+        let answer = state()
+            .parse(&query(&[("form", "ἔλυσαν,ἐπαύοντο")]))
+            .unwrap();
+        assert_eq!(answer[0]["form"], "ἔλυσαν");
+        assert_eq!(answer[0]["analyses"][0]["lemma"], "λύω");
+        assert_eq!(answer[1]["analyses"][0]["tva"], "ipi");
+    }
+}
@@ -0,0 +1,199 @@
+use greek_writer::parse::{self, Analysis};
+use greek_writer::{cell_key, output, Form, Tva, Verb};
+use rusqlite::{params, Connection, OpenFlags};
+use std::error::Error;
+
+// An SQLite database of forms for --format sqlite: one table, forms, with
+// a row per form (each variant its own) giving its lemma, tense, voice,
+// mood, person (1, 2 or 3), number (singular or plural), the form and the
+// dialect of the endings it was made with, attic unless --endings named
+// another. A database that already has the table is added to. The find
+// subcommand looks forms up in such a database, as build-db writes it.
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS forms (
+    lemma TEXT NOT NULL,
+    tense TEXT NOT NULL,
+    voice TEXT NOT NULL,
+    mood TEXT NOT NULL,
+    person INTEGER NOT NULL,
+    number TEXT NOT NULL,
+    form TEXT NOT NULL,
+    dialect TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS forms_form ON forms (form);
+CREATE INDEX IF NOT EXISTS forms_lemma ON forms (lemma);
+";
+
+pub fn write(done: &[(Verb, Vec<Tva>)], dialect: &str, path: &str) -> rusqlite::Result<()> {
+    let mut conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+    // One transaction for the lot, so that SQLite syncs to disk once.
+    let tx = conn.transaction()?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO forms (lemma, tense, voice, mood, person, number, form, dialect)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for (vb, reqs) in done {
+            for &req in reqs {
+                let paradigm = match vb.paradigm(req) {
+                    Some(paradigm) => paradigm,
+                    None => continue,
+                };
+                let lemma = output::name(vb, req);
+                for form in paradigm.forms() {
+                    insert.execute(params![
+                        lemma,
+                        form.tense.name(),
+                        form.voice.name(),
+                        form.mood.name(),
+                        form.person.ordinal(),
+                        form.number.name(),
+                        form.text,
+                        dialect,
+                    ])?;
+                }
+            }
+        }
+    }
+    tx.commit()
+}
+
+// The analyses of a form in a database; a form made with several dialects'
+// endings is given once.
+pub fn find(path: &str, word: &str) -> Result<Vec<Analysis>, Box<dyn Error>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("{}: {}", path, e))?;
+    let mut select = conn.prepare(
+        "SELECT DISTINCT lemma, tense, voice, mood, person, number, form
+         FROM forms WHERE form = ?1",
+    )?;
+    let rows = select.query_map([parse::comparable(word)], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            format!(
+                "{}-{}-{}",
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?
+            ),
+            format!(
+                "{}{}",
+                row.get::<_, u32>(4)?,
+                if row.get::<_, String>(5)? == "plural" {
+                    "pl"
+                } else {
+                    "sg"
+                }
+            ),
+            row.get::<_, String>(6)?,
+        ))
+    })?;
+    let mut found = Vec::new();
+    for row in rows {
+        let (lemma, name, cell, text) = row?;
+        let tva: Tva = name.parse()?;
+        let (person, number) =
+            cell_key(&cell).ok_or_else(|| format!("{}: bad cell {}", path, cell))?;
+        found.push(Analysis {
+            lemma,
+            tva,
+            form: Form {
+                text,
+                person,
+                number,
+                tense: tva.tense,
+                voice: tva.voice,
+                mood: tva.mood,
+                romanized: None,
+                label: None,
+            },
+            known: true,
+        });
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greek_writer::conj_reqs;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn done() -> Vec<(Verb, Vec<Tva>)> {
+        let mut vb = Verb::new("pres:παυ").unwrap();
+        vb.lemma = "παύω".to_string();
+        let reqs = vec!["pai".parse().unwrap(), "ipi".parse().unwrap()];
+        conj_reqs(&mut vb, &reqs).unwrap();
+        vec![(vb, reqs)]
+    }
+
+    fn database(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("gkverb-{}-{}.db", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn writes_a_row_per_form_and_adds_to_the_table() {
+        let path = database("write");
+        let path = path.to_str().unwrap();
+        write(&done(), "attic", path).unwrap();
+        let conn = Connection::open(path).unwrap();
+        let row: (String, String, String, u32, String, String) = conn
+            .query_row(
+                "SELECT tense, voice, mood, person, number, dialect FROM forms
+                 WHERE lemma = 'παύω' AND form = 'ἐπαυοντο'",
+                [],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            (
+                "imperfect".to_string(),
+                "middle/passive".to_string(),
+                "indicative".to_string(),
+                3,
+                "plural".to_string(),
+                "attic".to_string()
+            )
+        );
+        write(&done(), "koine", path).unwrap();
+        let count: u32 = conn
+            .query_row("SELECT COUNT(*) FROM forms", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 24);
+        drop(conn);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn finds_a_form_once_whatever_its_dialects() {
+        let path = database("find");
+        let path = path.to_str().unwrap();
+        write(&done(), "attic", path).unwrap();
+        write(&done(), "koine", path).unwrap();
+        let found = find(path, "ἐπαύοντο").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].describe("ἐπαύοντο"),
+            "ἐπαύοντο: 3rd pl. imperfect middle/passive indicative of παύω"
+        );
+        assert!(find(path, "ἔλυσα").unwrap().is_empty());
+        fs::remove_file(path).unwrap();
+        assert!(find(path, "ἐπαύοντο").is_err());
+    }
+}
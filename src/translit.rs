@@ -0,0 +1,187 @@
+use crate::GkVerbError;
+use unicode_normalization::UnicodeNormalization;
+
+// Romanization of generated forms, for readers who cannot yet read the
+// script. ALA-LC is the library standard: no accents, υ as y except in
+// diphthongs, χ as ch. The scientific scheme keeps the accents and writes
+// υ as u, ξ as ks and χ as kh. Both mark long vowels with a macron, write
+// rough breathing as h and iota subscript as a following i.
+
+// The names --transliterate takes.
+pub const SCHEMES: [&str; 2] = ["ala-lc", "scientific"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheme {
+    AlaLc,
+    Scientific,
+}
+
+impl Scheme {
+    pub fn from_arg(s: &str) -> Result<Self, GkVerbError> {
+        match s {
+            "ala-lc" => Ok(Scheme::AlaLc),
+            "scientific" => Ok(Scheme::Scientific),
+            _ => Err(GkVerbError::UnknownValue {
+                option: "transliteration scheme",
+                value: s.to_string(),
+                expected: &SCHEMES,
+            }),
+        }
+    }
+}
+
+// A letter with the combining marks that follow it.
+struct Letter {
+    base: char,
+    upper: bool,
+    marks: Vec<char>,
+}
+
+impl Letter {
+    fn has(&self, mark: char) -> bool {
+        self.marks.contains(&mark)
+    }
+}
+
+const ROUGH: char = '\u{0314}';
+const DIAERESIS: char = '\u{0308}';
+const SUBSCRIPT: char = '\u{0345}';
+
+fn letters(s: &str) -> Vec<Letter> {
+    let mut out: Vec<Letter> = Vec::new();
+    for c in s.nfd() {
+        if ('\u{0300}'..='\u{036f}').contains(&c) {
+            if let Some(last) = out.last_mut() {
+                last.marks.push(c);
+            }
+            continue;
+        }
+        let base = c.to_lowercase().next().unwrap_or(c);
+        out.push(Letter {
+            base,
+            upper: base != c,
+            marks: Vec::new(),
+        });
+    }
+    out
+}
+
+fn roman(c: char, scheme: Scheme) -> &'static str {
+    let scientific = scheme == Scheme::Scientific;
+    match c {
+        'α' => "a",
+        'β' => "b",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' => "e",
+        'ζ' => "z",
+        'η' => "ē",
+        'θ' => "th",
+        'ι' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' if scientific => "ks",
+        'ξ' => "x",
+        'ο' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' | 'ϲ' => "s",
+        'τ' => "t",
+        'υ' if scientific => "u",
+        'υ' => "y",
+        'φ' => "ph",
+        'χ' if scientific => "kh",
+        'χ' => "ch",
+        'ψ' => "ps",
+        'ω' => "ō",
+        'ϝ' => "w",
+        _ => "",
+    }
+}
+
+pub fn transliterate(s: &str, scheme: Scheme) -> String {
+    let word = letters(s);
+    let mut out = String::new();
+    for (i, l) in word.iter().enumerate() {
+        let prev = if i > 0 { word.get(i - 1) } else { None };
+        let next = word.get(i + 1);
+        let mut piece = String::new();
+        // Rough breathing sits on the second vowel of a diphthong but is
+        // pronounced before the first.
+        let rough_next =
+            next.is_some_and(|n| n.has(ROUGH) && matches!(n.base, 'ι' | 'υ') && !n.has(DIAERESIS));
+        let second = matches!(l.base, 'ι' | 'υ')
+            && !l.has(DIAERESIS)
+            && prev.is_some_and(|p| matches!(p.base, 'α' | 'ε' | 'η' | 'ο' | 'υ'));
+        if (l.has(ROUGH) && !second || rough_next) && l.base != 'ρ' {
+            piece.push('h');
+        }
+        let text = match l.base {
+            'γ' if next.is_some_and(|n| matches!(n.base, 'γ' | 'κ' | 'ξ' | 'χ')) => "n",
+            'ρ' if l.has(ROUGH) || prev.is_some_and(|p| p.base == 'ρ') => "rh",
+            'υ' if prev.is_some_and(|p| matches!(p.base, 'α' | 'ε' | 'η' | 'ο'))
+                && !l.has(DIAERESIS) =>
+            {
+                "u"
+            }
+            c => match roman(c, scheme) {
+                "" => {
+                    out.push(c);
+                    continue;
+                }
+                r => r,
+            },
+        };
+        piece.push_str(text);
+        for &m in &l.marks {
+            match m {
+                DIAERESIS => piece.push(m),
+                '\u{0301}' | '\u{0300}' if scheme == Scheme::Scientific => piece.push(m),
+                '\u{0342}' if scheme == Scheme::Scientific => piece.push('\u{0302}'),
+                _ => {}
+            }
+        }
+        if l.has(SUBSCRIPT) {
+            piece.push('i');
+        }
+        if l.upper {
+            let mut chars = piece.chars();
+            if let Some(first) = chars.next() {
+                piece = first.to_uppercase().chain(chars).collect();
+            }
+        }
+        out.push_str(&piece);
+    }
+    out.nfc().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_an_unknown_scheme() {
+        assert_eq!(Scheme::from_arg("ala-lc").unwrap(), Scheme::AlaLc);
+        assert_eq!(Scheme::from_arg("scientific").unwrap(), Scheme::Scientific);
+        assert_eq!(
+            Scheme::from_arg("iso").unwrap_err().to_string(),
+            "unknown transliteration scheme iso; expected one of ala-lc, scientific"
+        );
+    }
+
+    #[test]
+    fn romanizes_by_either_scheme() {
+        assert_eq!(transliterate("λύουσι", Scheme::AlaLc), "lyousi");
+        assert_eq!(transliterate("λύουσι", Scheme::Scientific), "lúousi");
+        assert_eq!(transliterate("ἔχω", Scheme::AlaLc), "echō");
+        assert_eq!(transliterate("ἔχω", Scheme::Scientific), "ékhō");
+        assert_eq!(transliterate("οἱ", Scheme::AlaLc), "hoi");
+        assert_eq!(transliterate("ῥήτωρ", Scheme::AlaLc), "rhētōr");
+        assert_eq!(transliterate("ἄγγελος", Scheme::AlaLc), "angelos");
+        assert_eq!(transliterate("τῷ", Scheme::AlaLc), "tōi");
+        assert_eq!(transliterate("Ἑλλάς", Scheme::AlaLc), "Hellas");
+        assert_eq!(transliterate("ξύν", Scheme::Scientific), "ksún");
+    }
+}
@@ -0,0 +1,332 @@
+use base64::Engine;
+use greek_writer::lexicon::Entry;
+use greek_writer::output::{self, Layout};
+use greek_writer::{conj_reqs, translit, Nu, Style, Tva, Verb, TENSES};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Rect};
+use ratatui::style::{Modifier, Style as Look};
+use ratatui::widgets::{Block, Borders, List, ListState, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::error::Error;
+use std::io::{self, Write};
+
+// The --tui paradigm browser: type a lemma, stem spec or principal parts,
+// pick a tense, voice and mood from the panes, and the paradigm shows at
+// once. The ending options are toggled live, and c copies the table, as
+// printed by the table format, through the terminal's clipboard (OSC 52).
+
+const VOICES: [&str; 3] = ["active", "middle", "passive"];
+const MOODS: [&str; 1] = ["indicative"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Focus {
+    Input,
+    Tense,
+    Voice,
+    Mood,
+}
+
+struct App {
+    entries: Vec<Entry>,
+    input: String,
+    focus: Focus,
+    tense: usize,
+    voice: usize,
+    mood: usize,
+    nu: Nu,
+    style: Style,
+    transliterate: bool,
+    verb: Option<Verb>,
+    status: String,
+}
+
+impl App {
+    fn refresh(&mut self) {
+        if self.input.trim().is_empty() {
+            self.verb = None;
+            return;
+        }
+        match Verb::from_input(&self.input, &self.entries) {
+            Ok(mut vb) => {
+                vb.nu = self.nu;
+                vb.style = self.style;
+                let reqs = vb.default_reqs();
+                // The verb has a stem for each of its default reqs.
+                conj_reqs(&mut vb, &reqs).ok();
+                if self.transliterate {
+                    vb.romanize(|form| translit::transliterate(form, translit::Scheme::AlaLc));
+                }
+                self.verb = Some(vb);
+                self.status.clear();
+            }
+            Err(e) => {
+                self.verb = None;
+                self.status = e.to_string();
+            }
+        }
+    }
+
+    // The tva picked in the panes, if the verb has it.
+    fn tva(&self) -> Option<Tva> {
+        let vb = self.verb.as_ref()?;
+        vb.default_reqs().into_iter().find(|tva| {
+            tva.tense.name() == TENSES[self.tense]
+                && tva.voice.name().split('/').any(|v| v == VOICES[self.voice])
+                && tva.mood.name() == MOODS[self.mood]
+        })
+    }
+
+    fn table(&self) -> Option<output::Table> {
+        let vb = self.verb.as_ref()?;
+        let tva = self.tva()?;
+        output::verb_tables(vb, &[tva], Layout::Paradigm).pop()
+    }
+
+    fn options(&self) -> String {
+        let nu = match self.nu {
+            Nu::Omit => "omit",
+            Nu::Show => "show",
+            Nu::Both => "both",
+            Nu::Rows => "rows",
+        };
+        let style = match self.style {
+            Style::Eta => "eta",
+            Style::Ei => "ei",
+            Style::Both => "both",
+        };
+        let translit = if self.transliterate { "on" } else { "off" };
+        format!(
+            " nu: {}  style: {}  transliteration: {} ",
+            nu, style, translit
+        )
+    }
+
+    fn copy(&mut self) -> io::Result<()> {
+        let table = match self.table() {
+            Some(table) => table,
+            None => {
+                self.status = "Nothing to copy.".to_string();
+                return Ok(());
+            }
+        };
+        let text = output::aligned(&[table]);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+        let mut out = io::stdout();
+        write!(out, "\x1b]52;c;{}\x07", encoded)?;
+        out.flush()?;
+        self.status = "Copied the table to the clipboard.".to_string();
+        Ok(())
+    }
+
+    // Handles a key press, returning false to quit.
+    fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> io::Result<bool> {
+        if code == KeyCode::Esc
+            || code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)
+        {
+            return Ok(false);
+        }
+        match (self.focus, code) {
+            (_, KeyCode::Tab) => {
+                self.focus = match self.focus {
+                    Focus::Input => Focus::Tense,
+                    Focus::Tense => Focus::Voice,
+                    Focus::Voice => Focus::Mood,
+                    Focus::Mood => Focus::Input,
+                }
+            }
+            (_, KeyCode::BackTab) => {
+                self.focus = match self.focus {
+                    Focus::Input => Focus::Mood,
+                    Focus::Tense => Focus::Input,
+                    Focus::Voice => Focus::Tense,
+                    Focus::Mood => Focus::Voice,
+                }
+            }
+            (Focus::Input, KeyCode::Enter) => {
+                self.refresh();
+                self.focus = Focus::Tense;
+            }
+            (Focus::Input, KeyCode::Backspace) => {
+                self.input.pop();
+            }
+            (Focus::Input, KeyCode::Char(c)) => self.input.push(c),
+            (_, KeyCode::Char('q')) => return Ok(false),
+            (focus, KeyCode::Up) | (focus, KeyCode::Char('k')) => {
+                if let Some((i, _)) = self.choice(focus) {
+                    *i = i.saturating_sub(1);
+                }
+            }
+            (focus, KeyCode::Down) | (focus, KeyCode::Char('j')) => {
+                if let Some((i, len)) = self.choice(focus) {
+                    *i = (*i + 1).min(len - 1);
+                }
+            }
+            (_, KeyCode::Char('n')) => {
+                self.nu = match self.nu {
+                    Nu::Omit => Nu::Show,
+                    Nu::Show => Nu::Both,
+                    Nu::Both => Nu::Rows,
+                    Nu::Rows => Nu::Omit,
+                };
+                self.refresh();
+            }
+            (_, KeyCode::Char('s')) => {
+                self.style = match self.style {
+                    Style::Eta => Style::Ei,
+                    Style::Ei => Style::Both,
+                    Style::Both => Style::Eta,
+                };
+                self.refresh();
+            }
+            (_, KeyCode::Char('t')) => {
+                self.transliterate = !self.transliterate;
+                self.refresh();
+            }
+            (_, KeyCode::Char('c')) => self.copy()?,
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    // The selection a pane moves, and how many items it has.
+    fn choice(&mut self, focus: Focus) -> Option<(&mut usize, usize)> {
+        match focus {
+            Focus::Tense => Some((&mut self.tense, TENSES.len())),
+            Focus::Voice => Some((&mut self.voice, VOICES.len())),
+            Focus::Mood => Some((&mut self.mood, MOODS.len())),
+            Focus::Input => None,
+        }
+    }
+
+    fn block(&self, title: &str, focus: Focus) -> Block<'static> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string());
+        if self.focus == focus {
+            block.border_style(Look::default().add_modifier(Modifier::BOLD))
+        } else {
+            block
+        }
+    }
+
+    fn pane(&self, frame: &mut Frame, area: Rect, title: &str, items: &[&str], at: usize) {
+        let focus = match title {
+            "Tense" => Focus::Tense,
+            "Voice" => Focus::Voice,
+            _ => Focus::Mood,
+        };
+        let list = List::new(items.iter().map(|i| i.to_string()))
+            .block(self.block(title, focus))
+            .highlight_style(Look::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(at));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let rows = ratatui::layout::Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(8),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+        let input = Paragraph::new(self.input.as_str()).block(self.block(
+            "Lemma, stem (pres:παυ) or principal parts; Enter to conjugate",
+            Focus::Input,
+        ));
+        frame.render_widget(input, rows[0]);
+
+        let cols = ratatui::layout::Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(14),
+                Constraint::Length(11),
+                Constraint::Length(14),
+                Constraint::Min(20),
+            ])
+            .split(rows[1]);
+        self.pane(frame, cols[0], "Tense", &TENSES, self.tense);
+        self.pane(frame, cols[1], "Voice", &VOICES, self.voice);
+        self.pane(frame, cols[2], "Mood", &MOODS, self.mood);
+
+        let block = Block::default().borders(Borders::ALL).title(self.options());
+        match self.table() {
+            Some(table) => {
+                let widths: Vec<Constraint> = table
+                    .head
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| match i {
+                        0 => Constraint::Length(5),
+                        _ => Constraint::Min(12),
+                    })
+                    .collect();
+                let body = table.rows.iter().map(|(label, cells)| {
+                    Row::new(std::iter::once(label.clone()).chain(cells.iter().cloned()))
+                });
+                let widget = Table::new(body, widths)
+                    .header(
+                        Row::new(table.head.clone())
+                            .style(Look::default().add_modifier(Modifier::BOLD)),
+                    )
+                    .block(block.title_bottom(table.title));
+                frame.render_widget(widget, cols[3]);
+            }
+            None => {
+                let text = if self.verb.is_some() {
+                    "The verb has no forms for this tense, voice and mood."
+                } else {
+                    ""
+                };
+                frame.render_widget(Paragraph::new(text).block(block), cols[3]);
+            }
+        }
+
+        frame.render_widget(Paragraph::new(self.status.as_str()), rows[2]);
+        frame.render_widget(
+            Paragraph::new(
+                "Tab: next pane  ↑↓: choose  n: nu  s: 2nd sg. style  t: transliteration  c: copy  q/Esc: quit",
+            ),
+            rows[3],
+        );
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.key(key.code, key.modifiers)? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+// Starts the browser, with the verb given on the command line, if any.
+pub fn browse(input: &str, entries: Vec<Entry>) -> Result<(), Box<dyn Error>> {
+    let mut app = App {
+        entries,
+        input: input.to_string(),
+        focus: if input.is_empty() {
+            Focus::Input
+        } else {
+            Focus::Tense
+        },
+        tense: 0,
+        voice: 0,
+        mood: 0,
+        nu: Nu::Omit,
+        style: Style::Eta,
+        transliterate: false,
+        verb: None,
+        status: String::new(),
+    };
+    app.refresh();
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app);
+    ratatui::restore();
+    Ok(result?)
+}
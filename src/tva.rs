@@ -0,0 +1,416 @@
+use crate::GkVerbError;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+// A tva code names a tense, voice and mood, e.g. pai (present active
+// indicative) or pfpi (perfect middle/passive indicative): the tense in
+// one or two letters, then the voice and the mood in one each. Where the
+// middle and passive share their forms the voice is written p, and those
+// forms can be had labelled as middle alone with m: pmi is the present
+// middle, its forms those of ppi.
+//
+// A tva can also be named in full, tense, voice and mood joined by
+// hyphens or dots and each written out or abbreviated, e.g.
+// present-active-indicative or pres.act.ind. A name can give the middle or
+// the passive where the two share their forms, so present-passive is ppi
+// and present-middle pmi, and can leave out the mood, the indicative being the only one, so
+// impf-act is iai. Other tools' abbreviations are read through ALIASES,
+// and case is ignored throughout.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Tense {
+    Present,
+    Imperfect,
+    Future,
+    Aorist,
+    Perfect,
+    Pluperfect,
+}
+
+impl Tense {
+    pub const ALL: [Tense; 6] = [
+        Tense::Present,
+        Tense::Imperfect,
+        Tense::Future,
+        Tense::Aorist,
+        Tense::Perfect,
+        Tense::Pluperfect,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tense::Present => "present",
+            Tense::Imperfect => "imperfect",
+            Tense::Future => "future",
+            Tense::Aorist => "aorist",
+            Tense::Perfect => "perfect",
+            Tense::Pluperfect => "pluperfect",
+        }
+    }
+
+    // As a long-form name spells it.
+    fn words(self) -> &'static [&'static str] {
+        match self {
+            Tense::Present => &["present", "pres"],
+            Tense::Imperfect => &["imperfect", "impf", "imperf"],
+            Tense::Future => &["future", "fut"],
+            Tense::Aorist => &["aorist", "aor"],
+            Tense::Perfect => &["perfect", "perf", "pf"],
+            Tense::Pluperfect => &["pluperfect", "plupf", "plup"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Voice {
+    Active,
+    Middle,
+    Passive,
+    /// The one set of forms the middle and passive share outside the
+    /// future and aorist.
+    #[cfg_attr(feature = "serde", serde(rename = "middle/passive"))]
+    MiddlePassive,
+}
+
+impl Voice {
+    pub fn name(self) -> &'static str {
+        match self {
+            Voice::Active => "active",
+            Voice::Middle => "middle",
+            Voice::Passive => "passive",
+            Voice::MiddlePassive => "middle/passive",
+        }
+    }
+
+    fn words(self) -> &'static [&'static str] {
+        match self {
+            Voice::Active => &["active", "act"],
+            Voice::Middle => &["middle", "mid"],
+            Voice::Passive => &["passive", "pass"],
+            Voice::MiddlePassive => &["middle/passive", "mid/pass", "mp"],
+        }
+    }
+
+    /// Whether forms of this voice serve for the other, as middle/passive
+    /// forms serve for both the middle and the passive.
+    pub fn covers(self, other: Voice) -> bool {
+        self == other
+            || (self == Voice::MiddlePassive && matches!(other, Voice::Middle | Voice::Passive))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Mood {
+    Indicative,
+}
+
+impl Mood {
+    pub fn name(self) -> &'static str {
+        match self {
+            Mood::Indicative => "indicative",
+        }
+    }
+
+    fn words(self) -> &'static [&'static str] {
+        match self {
+            Mood::Indicative => &["indicative", "ind"],
+        }
+    }
+}
+
+macro_rules! display_name {
+    ($($t:ty),*) => {
+        $(impl fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        })*
+    };
+}
+
+display_name!(Tense, Voice, Mood);
+
+/// A tense, voice and mood that the tables can conjugate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "String", try_from = "String"))]
+pub struct Tva {
+    pub tense: Tense,
+    pub voice: Voice,
+    pub mood: Mood,
+}
+
+// Every tva, in the order of ALL_TVAS.
+const TVAS: [(Tense, Voice); 14] = [
+    (Tense::Present, Voice::Active),
+    (Tense::Present, Voice::MiddlePassive),
+    (Tense::Imperfect, Voice::Active),
+    (Tense::Imperfect, Voice::MiddlePassive),
+    (Tense::Future, Voice::Active),
+    (Tense::Future, Voice::Middle),
+    (Tense::Future, Voice::Passive),
+    (Tense::Aorist, Voice::Active),
+    (Tense::Aorist, Voice::Middle),
+    (Tense::Aorist, Voice::Passive),
+    (Tense::Perfect, Voice::Active),
+    (Tense::Perfect, Voice::MiddlePassive),
+    (Tense::Pluperfect, Voice::Active),
+    (Tense::Pluperfect, Voice::MiddlePassive),
+];
+
+// The tenses whose middle/passive forms can be labelled middle, in the
+// order of MIDDLE_TVAS.
+const MIDDLES: [Tense; 4] = [
+    Tense::Present,
+    Tense::Imperfect,
+    Tense::Perfect,
+    Tense::Pluperfect,
+];
+
+impl Tva {
+    /// Every tva, in the order they are printed.
+    pub fn all() -> impl Iterator<Item = Tva> {
+        TVAS.iter().map(|&(tense, voice)| Tva {
+            tense,
+            voice,
+            mood: Mood::Indicative,
+        })
+    }
+
+    /// The middles of [`MIDDLE_TVAS`](crate::MIDDLE_TVAS), whose forms are
+    /// those the passive shares.
+    pub fn middles() -> impl Iterator<Item = Tva> {
+        MIDDLES.iter().map(|&tense| Tva {
+            tense,
+            voice: Voice::Middle,
+            mood: Mood::Indicative,
+        })
+    }
+
+    /// The tva's code, e.g. "pai".
+    pub fn code(self) -> &'static str {
+        if self.voice == Voice::Middle {
+            if let Some(i) = MIDDLES.iter().position(|&tense| tense == self.tense) {
+                return crate::MIDDLE_TVAS[i];
+            }
+        }
+        let i = TVAS
+            .iter()
+            .position(|&(tense, voice)| (tense, voice) == (self.tense, self.voice))
+            .unwrap_or(0);
+        crate::ALL_TVAS[i]
+    }
+
+    /// The tva whose forms this one has: for pmi, say, ppi.
+    pub fn shared(self) -> Tva {
+        Tva::all()
+            .find(|tva| tva.tense == self.tense && tva.voice.covers(self.voice))
+            .unwrap_or(self)
+    }
+
+    /// The tense system whose principal part the tva is built on, spelled
+    /// as in a stem spec.
+    pub fn system(self) -> &'static str {
+        match (self.tense, self.voice) {
+            (Tense::Present | Tense::Imperfect, _) => "pres",
+            (Tense::Future, Voice::Passive) | (Tense::Aorist, Voice::Passive) => "aorpass",
+            (Tense::Future, _) => "fut",
+            (Tense::Aorist, _) => "aor",
+            (Tense::Perfect | Tense::Pluperfect, Voice::Active) => "perf",
+            (Tense::Perfect | Tense::Pluperfect, _) => "perfmid",
+        }
+    }
+
+    /// Whether the tva is active, and so one a deponent lacks.
+    pub fn is_active(self) -> bool {
+        self.voice == Voice::Active
+    }
+}
+
+impl From<Tva> for String {
+    fn from(tva: Tva) -> String {
+        tva.code().to_string()
+    }
+}
+
+impl TryFrom<String> for Tva {
+    type Error = GkVerbError;
+
+    fn try_from(s: String) -> Result<Self, GkVerbError> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Tva {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+// Words of a long-form name as other tools abbreviate them, and the word
+// each stands for.
+const ALIASES: [(&str, &str); 12] = [
+    ("pr", "present"),
+    ("prs", "present"),
+    ("ipf", "imperfect"),
+    ("ft", "future"),
+    ("aor1", "aorist"),
+    ("aor2", "aorist"),
+    ("plpf", "pluperfect"),
+    ("plu", "pluperfect"),
+    ("pas", "passive"),
+    ("m/p", "middle/passive"),
+    ("mediopassive", "middle/passive"),
+    ("indic", "indicative"),
+];
+
+// The tense, voice and mood a long-form name spells, in lower case.
+fn named(name: &str) -> Option<(Tense, Voice, Mood)> {
+    let words: Vec<&str> = name
+        .split(['-', '.'])
+        .map(|word| {
+            ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == word)
+                .map_or(word, |(_, word)| word)
+        })
+        .collect();
+    let (tense, voice, mood) = match words.as_slice() {
+        [tense, voice, mood] => (tense, voice, mood),
+        [tense, voice] => (tense, voice, &"indicative"),
+        _ => return None,
+    };
+    let tense = Tense::ALL.iter().find(|t| t.words().contains(tense))?;
+    let voice = [
+        Voice::Active,
+        Voice::Middle,
+        Voice::Passive,
+        Voice::MiddlePassive,
+    ]
+    .iter()
+    .find(|v| v.words().contains(voice))?;
+    let mood = [Mood::Indicative]
+        .iter()
+        .find(|m| m.words().contains(mood))?;
+    Some((*tense, *voice, *mood))
+}
+
+impl FromStr for Tva {
+    type Err = GkVerbError;
+
+    /// A tva code such as "aai", or a long-form name such as
+    /// "aorist-active-indicative" or "aor-act", in any case. A name whose
+    /// tense has no such voice, such as "future-middle/passive", is
+    /// rejected.
+    fn from_str(s: &str) -> Result<Self, GkVerbError> {
+        let lower = s.to_lowercase();
+        let mut every = Tva::all().chain(Tva::middles());
+        if let Some(tva) = every.find(|tva| tva.code() == lower) {
+            return Ok(tva);
+        }
+        let (tense, voice, mood) = match named(&lower) {
+            Some(named) => named,
+            None => return Err(GkVerbError::UnknownTva(s.to_string())),
+        };
+        let tva = Tva { tense, voice, mood };
+        Tva::all()
+            .chain(Tva::middles())
+            .find(|t| *t == tva)
+            .or_else(|| Tva::all().find(|t| t.tense == tense && t.voice.covers(voice)))
+            .ok_or(GkVerbError::ImpossibleTva {
+                code: s.to_string(),
+                tense,
+                voice,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tva(s: &str) -> Tva {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn reads_and_writes_every_code() {
+        for tva in Tva::all() {
+            assert_eq!(tva.code().parse::<Tva>().unwrap(), tva);
+        }
+        let aai = tva("aai");
+        assert_eq!(
+            (aai.tense, aai.voice, aai.mood),
+            (Tense::Aorist, Voice::Active, Mood::Indicative)
+        );
+        assert_eq!(tva("pfpi").voice, Voice::MiddlePassive);
+        assert_eq!(tva("fpi").system(), "aorpass");
+        assert_eq!(tva("plpi").system(), "perfmid");
+        assert_eq!(tva("iai").system(), "pres");
+        assert_eq!(
+            "xyz".parse::<Tva>().unwrap_err(),
+            GkVerbError::UnknownTva("xyz".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_long_names() {
+        assert_eq!(tva("present-active-indicative"), tva("pai"));
+        assert_eq!(tva("pres.act.ind"), tva("pai"));
+        assert_eq!(tva("impf-act"), tva("iai"));
+        assert_eq!(tva("aorist-passive"), tva("api"));
+        assert_eq!(tva("perfect-middle/passive"), tva("pfpi"));
+        // The passive shares its forms with the middle here.
+        assert_eq!(tva("present-passive"), tva("ppi"));
+        assert!(matches!(
+            "future-middle/passive".parse::<Tva>(),
+            Err(GkVerbError::ImpossibleTva { .. })
+        ));
+        assert!(matches!(
+            "present-active-optative".parse::<Tva>(),
+            Err(GkVerbError::UnknownTva(_))
+        ));
+    }
+
+    #[test]
+    fn reads_other_tools_abbreviations_in_any_case() {
+        assert_eq!(tva("AAI"), tva("aai"));
+        assert_eq!(tva("Present-Active-Indicative"), tva("pai"));
+        assert_eq!(tva("prs-act"), tva("pai"));
+        assert_eq!(tva("ipf.m/p.indic"), tva("ipi"));
+        assert_eq!(tva("aor2-act"), tva("aai"));
+        assert_eq!(tva("plpf-mediopassive"), tva("plpi"));
+        assert_eq!(tva("ft-pas"), tva("fpi"));
+        // The error names the tva as typed.
+        assert_eq!(
+            "AOR-XYZ".parse::<Tva>().unwrap_err(),
+            GkVerbError::UnknownTva("AOR-XYZ".to_string())
+        );
+    }
+
+    #[test]
+    fn labels_shared_forms_as_middle() {
+        let pmi = tva("pmi");
+        assert_eq!(pmi.voice, Voice::Middle);
+        assert_eq!(pmi.code(), "pmi");
+        assert_eq!(pmi.shared(), tva("ppi"));
+        assert_eq!(tva("present-middle"), pmi);
+        assert_eq!(tva("pfmi").system(), "perfmid");
+        assert_eq!(tva("ami").shared(), tva("ami"));
+        assert_eq!(
+            Tva::middles().map(Tva::code).collect::<Vec<_>>(),
+            ["pmi", "imi", "pfmi", "plmi"]
+        );
+        assert!(Voice::MiddlePassive.covers(Voice::Passive));
+        assert!(!Voice::Middle.covers(Voice::MiddlePassive));
+    }
+}
@@ -0,0 +1,100 @@
+use greek_writer::output::Card;
+use greek_writer::{betacode, strip_accents};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+
+// The verify subcommand: each generated form is looked up in Morpheus,
+// the Perseus analyzer, and those it cannot analyse are listed, to audit
+// the tables against it. Morpheus is asked either through a service whose
+// URL has {form} where the form goes, such as the Perseids morphology
+// service, or through a dump of the forms it recognizes, one per line
+// (anything after the first word is ignored) in Beta Code or Unicode.
+// Forms are compared without accents, since the generated ones have none.
+
+pub enum Analyzer {
+    Dump(HashSet<String>),
+    Service(String),
+}
+
+impl Analyzer {
+    pub fn dump(path: &str) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let known = text
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .map(comparable)
+            .collect();
+        Ok(Analyzer::Dump(known))
+    }
+
+    pub fn recognizes(&self, form: &str) -> Result<bool, Box<dyn Error>> {
+        match self {
+            Analyzer::Dump(known) => Ok(known.contains(&comparable(form))),
+            Analyzer::Service(url) => ask(url, form),
+        }
+    }
+}
+
+// A form as compared: in Unicode, if it was written in Beta Code, and
+// without accents.
+fn comparable(form: &str) -> String {
+    if form.chars().any(|c| c.is_ascii_alphabetic()) {
+        strip_accents(&betacode::to_unicode(form))
+    } else {
+        strip_accents(form)
+    }
+}
+
+// Whether the service finds an analysis of the form: Morpheus names the
+// headword (hdwd) of each one, in its XML and JSON alike.
+#[cfg(feature = "verify")]
+fn ask(url: &str, form: &str) -> Result<bool, Box<dyn Error>> {
+    let url = url.replace("{form}", &percent_encode(form));
+    match ureq::get(&url).call() {
+        Ok(mut response) => Ok(response.body_mut().read_to_string()?.contains("hdwd")),
+        Err(ureq::Error::StatusCode(404)) => Ok(false),
+        Err(e) => Err(format!("{}: {}", url, e).into()),
+    }
+}
+
+#[cfg(not(feature = "verify"))]
+fn ask(_url: &str, _form: &str) -> Result<bool, Box<dyn Error>> {
+    Err("this gkverb was built without HTTP requests; rebuild with --features verify, or give a --dump".into())
+}
+
+#[cfg(feature = "verify")]
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// The cards whose forms the analyzer does not recognize; a form shared by
+// several cards is asked about once.
+pub fn unrecognized<'a>(
+    analyzer: &Analyzer,
+    cards: &'a [Card],
+) -> Result<Vec<&'a Card>, Box<dyn Error>> {
+    let mut known = HashMap::new();
+    let mut missing = Vec::new();
+    for card in cards {
+        let recognized = match known.get(&card.form) {
+            Some(&recognized) => recognized,
+            None => {
+                let recognized = analyzer.recognizes(&card.form)?;
+                known.insert(card.form.clone(), recognized);
+                recognized
+            }
+        };
+        if !recognized {
+            missing.push(card);
+        }
+    }
+    Ok(missing)
+}
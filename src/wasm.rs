@@ -0,0 +1,44 @@
+use crate::lexicon::{self, Entry};
+use crate::parse::Parser;
+use crate::{conjugate_json, ALL_TVAS};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+// The engine for a browser, with no server: build with
+//
+//     wasm-pack build --target web -- --features wasm
+//
+// and call from JavaScript
+//
+//     conjugate("pres:παυ", "pai")     the paradigms as --format json gives
+//                                      them; tvas may be "pai,iai" or "all"
+//     parse("ἔλυσαν")                  [{lemma, tva, parse, known}, ...]
+//     list_tvas()                      ["pai", "ppi", ...]
+//
+// A verb is a stem spec, six comma-separated principal parts or a lemma,
+// looked up in the built-in lexicon. Bad input throws an Error.
+
+thread_local! {
+    static ENTRIES: &'static [Entry] = lexicon::builtin();
+    // Built on the first parse, as it conjugates the whole lexicon.
+    static PARSER: Parser = ENTRIES.with(|entries| Parser::new(entries));
+}
+
+#[wasm_bindgen]
+pub fn conjugate(verb: &str, tvas: &str) -> Result<String, JsError> {
+    ENTRIES
+        .with(|entries| conjugate_json(verb, tvas, entries))
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn parse(form: &str) -> String {
+    let analyses: Vec<Value> =
+        PARSER.with(|parser| parser.analyses(form).iter().map(|a| a.json()).collect());
+    Value::from(analyses).to_string()
+}
+
+#[wasm_bindgen]
+pub fn list_tvas() -> Vec<String> {
+    ALL_TVAS.iter().map(|tva| tva.to_string()).collect()
+}
@@ -0,0 +1,291 @@
+use greek_writer::output::{html_escape, latex_escape, Table};
+use greek_writer::CELLS;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::BTreeSet;
+
+// Gap-fill worksheets for the worksheet subcommand: the paradigm tables
+// with some cells left empty for students to fill in. Cells are blanked
+// at random, a percentage of them, or by pattern, e.g. every 3rd person.
+// A paradigm's row is blanked whole, so that a transliteration column
+// does not give the form away. The answer key has the same tables with
+// the blanked cells filled in and highlighted.
+
+pub const FORMATS: [&str; 3] = ["markdown", "latex", "html"];
+
+pub enum Blanks<'a> {
+    Percent(usize),
+    Pattern(Vec<&'a str>),
+}
+
+// A blanked cell: table, row and column (counting from the first form).
+pub type Cell = (usize, usize, usize);
+
+// Whether a --pattern term picks out a cell such as "3sg": the term can be
+// a cell itself, a person ("3" or "3rd") or a number ("pl" or "plural").
+// None if the term is none of these.
+pub fn picks(term: &str, cell: &str) -> Option<bool> {
+    let (person, number) = cell.split_at(1);
+    match term {
+        "1" | "1st" | "2" | "2nd" | "3" | "3rd" => Some(term.starts_with(person)),
+        "sg" | "singular" | "pl" | "plural" => Some(term[..1] == number[..1]),
+        _ if CELLS.contains(&term) => Some(term == cell),
+        _ => None,
+    }
+}
+
+// The person and number a cell is for, from its column heading when the
+// persons run across, else from its row label.
+fn cell_of(table: &Table, row: usize, col: usize) -> &str {
+    match table.head.get(col + 1) {
+        Some(head) if CELLS.contains(&head.as_str()) => head,
+        _ => &table.rows[row].0,
+    }
+}
+
+// The cells to blank, each group blanked together.
+fn units(tables: &[Table]) -> Vec<Vec<Cell>> {
+    let mut units = Vec::new();
+    for (t, table) in tables.iter().enumerate() {
+        for (r, (_, cells)) in table.rows.iter().enumerate() {
+            let filled: Vec<Cell> = (0..cells.len())
+                .filter(|&c| !cells[c].is_empty())
+                .map(|c| (t, r, c))
+                .collect();
+            if table.tva.is_some() {
+                if !filled.is_empty() {
+                    units.push(filled);
+                }
+            } else {
+                units.extend(filled.into_iter().map(|cell| vec![cell]));
+            }
+        }
+    }
+    units
+}
+
+pub fn blank(tables: &[Table], blanks: &Blanks, seed: u64) -> BTreeSet<Cell> {
+    let mut units = units(tables);
+    match blanks {
+        Blanks::Percent(percent) => {
+            let mut rng = StdRng::seed_from_u64(seed);
+            units.shuffle(&mut rng);
+            let n = (units.len() * percent + 50) / 100;
+            units.into_iter().take(n).flatten().collect()
+        }
+        Blanks::Pattern(terms) => units
+            .into_iter()
+            .flatten()
+            .filter(|&(t, r, c)| {
+                let cell = cell_of(&tables[t], r, c);
+                terms.iter().any(|term| picks(term, cell) == Some(true))
+            })
+            .collect(),
+    }
+}
+
+// Each table's rows, the blanked cells written by blank and the rest by
+// escape.
+fn rows<'a>(
+    tables: &'a [Table],
+    blanked: &'a BTreeSet<Cell>,
+    blank: impl Fn(&str) -> String + 'a,
+    escape: impl Fn(&str) -> String + 'a,
+) -> impl Iterator<Item = (&'a Table, Vec<(&'a str, Vec<String>)>)> {
+    tables.iter().enumerate().map(move |(t, table)| {
+        let rows = table
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(r, (label, cells))| {
+                let cells = cells
+                    .iter()
+                    .enumerate()
+                    .map(|(c, cell)| {
+                        if blanked.contains(&(t, r, c)) {
+                            blank(cell)
+                        } else {
+                            escape(cell)
+                        }
+                    })
+                    .collect();
+                (label.as_str(), cells)
+            })
+            .collect();
+        (table, rows)
+    })
+}
+
+fn heading(key: bool) -> &'static str {
+    if key {
+        "Answer key."
+    } else {
+        "Fill in the missing forms."
+    }
+}
+
+pub fn markdown(tables: &[Table], blanked: &BTreeSet<Cell>, key: bool) -> String {
+    let escape = |s: &str| s.replace('|', "\\|");
+    let blank = |s: &str| {
+        if key {
+            format!("**{}**", escape(s))
+        } else {
+            "\\_".repeat(8)
+        }
+    };
+    let mut out = format!("{}\n\n", heading(key));
+    for (table, rows) in rows(tables, blanked, blank, escape) {
+        out.push_str(&format!("### {}\n\n", table.title));
+        out.push_str(&format!("| {} |\n", table.head.join(" | ")));
+        out.push_str(&format!("|{}\n", "---|".repeat(table.head.len())));
+        for (label, cells) in rows {
+            out.push_str(&format!("| {} | {} |\n", label, cells.join(" | ")));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// Blanks are ruled lines to write on, with room enough in the rows; the
+// key has the answers in bold.
+pub fn latex(tables: &[Table], blanked: &BTreeSet<Cell>, key: bool) -> String {
+    let blank = |s: &str| {
+        if key {
+            format!("\\textbf{{{}}}", latex_escape(s))
+        } else {
+            "\\rule{8em}{0.4pt}".to_string()
+        }
+    };
+    let mut out =
+        String::from("% Needs \\usepackage{booktabs} and a Greek font (XeLaTeX or LuaLaTeX).\n");
+    out.push_str(&format!("{}\n\n", heading(key)));
+    for (table, rows) in rows(tables, blanked, blank, latex_escape) {
+        out.push_str("\\begin{center}\n");
+        out.push_str(&format!(
+            "\\textbf{{{}}}\\\\[0.5ex]\n",
+            latex_escape(&table.title)
+        ));
+        out.push_str("{\\renewcommand{\\arraystretch}{1.6}\n");
+        out.push_str(&format!(
+            "\\begin{{tabular}}{{{}}}\n",
+            "l".repeat(table.head.len())
+        ));
+        out.push_str("\\toprule\n");
+        out.push_str(&format!("{} \\\\\n", table.head.join(" & ")));
+        out.push_str("\\midrule\n");
+        for (label, cells) in rows {
+            out.push_str(&format!("{} & {} \\\\\n", label, cells.join(" & ")));
+        }
+        out.push_str("\\bottomrule\n");
+        out.push_str("\\end{tabular}}\n");
+        out.push_str("\\end{center}\n\n");
+    }
+    out
+}
+
+// Blanked cells are classed "blank" so a stylesheet can give them room;
+// in the key they are classed "answer" and marked.
+pub fn html(tables: &[Table], blanked: &BTreeSet<Cell>, key: bool) -> String {
+    let cell = |s: &str| format!("<td>{}</td>", html_escape(s));
+    let blank = |s: &str| {
+        if key {
+            format!("<td class=\"answer\"><mark>{}</mark></td>", html_escape(s))
+        } else {
+            "<td class=\"blank\"></td>".to_string()
+        }
+    };
+    let mut out = format!("<p>{}</p>\n", heading(key));
+    for (table, rows) in rows(tables, blanked, blank, cell) {
+        out.push_str("<table class=\"worksheet\" lang=\"grc\">\n");
+        out.push_str(&format!(
+            "  <caption>{}</caption>\n",
+            html_escape(&table.title)
+        ));
+        out.push_str("  <thead>\n    <tr>");
+        for head in &table.head {
+            out.push_str(&format!("<th>{}</th>", head));
+        }
+        out.push_str("</tr>\n  </thead>\n  <tbody>\n");
+        for (label, cells) in rows {
+            out.push_str(&format!("    <tr><th scope=\"row\">{}</th>", label));
+            out.push_str(&cells.concat());
+            out.push_str("</tr>\n");
+        }
+        out.push_str("  </tbody>\n</table>\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greek_writer::output::{verb_tables, Layout};
+    use greek_writer::{conj_reqs, Verb};
+
+    fn tables(layout: Layout) -> Vec<Table> {
+        let mut vb = Verb::new("pres:παυ").unwrap();
+        let reqs = vec!["pai".parse().unwrap(), "iai".parse().unwrap()];
+        conj_reqs(&mut vb, &reqs).unwrap();
+        verb_tables(&vb, &reqs, layout)
+    }
+
+    #[test]
+    fn picks_cells_by_person_number_or_name() {
+        assert_eq!(picks("3rd", "3sg"), Some(true));
+        assert_eq!(picks("2", "3sg"), Some(false));
+        assert_eq!(picks("plural", "1pl"), Some(true));
+        assert_eq!(picks("sg", "1pl"), Some(false));
+        assert_eq!(picks("2pl", "2pl"), Some(true));
+        assert_eq!(picks("dual", "1sg"), None);
+    }
+
+    #[test]
+    fn blanks_by_pattern_down_a_paradigm_and_across_a_table_by_person() {
+        let pattern = Blanks::Pattern(vec!["3"]);
+        let blanked = blank(&tables(Layout::Paradigm), &pattern, 0);
+        // Rows 2 and 5 of each paradigm, 3sg and 3pl.
+        let rows: BTreeSet<_> = blanked.iter().map(|&(t, r, _)| (t, r)).collect();
+        assert_eq!(rows, BTreeSet::from([(0, 2), (0, 5), (1, 2), (1, 5)]));
+        let by_person = tables(Layout::ByPerson);
+        let blanked = blank(&by_person, &pattern, 0);
+        assert!(!blanked.is_empty());
+        assert!(blanked
+            .iter()
+            .all(|&(t, r, c)| cell_of(&by_person[t], r, c).starts_with('3')));
+    }
+
+    #[test]
+    fn blanks_the_same_cells_for_the_same_seed() {
+        let tables = tables(Layout::Paradigm);
+        let half = Blanks::Percent(50);
+        let blanked = blank(&tables, &half, 7);
+        assert_eq!(blanked, blank(&tables, &half, 7));
+        let rows: BTreeSet<_> = blanked.iter().map(|&(t, r, _)| (t, r)).collect();
+        assert_eq!(rows.len(), 6);
+        assert!(blank(&tables, &Blanks::Percent(0), 7).is_empty());
+        assert_eq!(
+            blank(&tables, &Blanks::Percent(100), 7).len(),
+            units(&tables).into_iter().flatten().count()
+        );
+    }
+
+    #[test]
+    fn fills_in_and_marks_the_blanks_in_the_key() {
+        let tables = tables(Layout::Paradigm);
+        let blanked = blank(&tables, &Blanks::Pattern(vec!["1sg"]), 0);
+        let sheet = markdown(&tables, &blanked, false);
+        assert!(sheet.starts_with("Fill in the missing forms."));
+        assert!(!sheet.contains("παυω"));
+        assert!(sheet.contains("| 2sg | παυεις |"));
+        let key = markdown(&tables, &blanked, true);
+        assert!(key.starts_with("Answer key."));
+        assert!(key.contains("| 1sg | **παυω** |"));
+        assert!(key.contains("| 2sg | παυεις |"));
+        assert!(html(&tables, &blanked, false).contains("<td class=\"blank\"></td>"));
+        assert!(
+            html(&tables, &blanked, true).contains("<td class=\"answer\"><mark>ἐπαυον</mark></td>")
+        );
+        assert!(latex(&tables, &blanked, true).contains("\\textbf{ἐπαυον}"));
+    }
+}
@@ -0,0 +1,204 @@
+use greek_writer::output::{self, Layout, Table};
+use greek_writer::{Tva, Verb};
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::collections::BTreeSet;
+
+// An .xlsx workbook of paradigms for --format xlsx, laid out as on screen
+// with a blank row between tables. Each verb gets its own sheet, or with
+// --sheets system each tense system does, holding that system's tables of
+// every verb.
+
+const SYSTEMS: [&str; 6] = ["pres", "fut", "aor", "perf", "perfmid", "aorpass"];
+
+// Excel forbids []:*?/\ in sheet names and allows at most 31 characters;
+// names must also differ, ignoring case.
+fn sheet_name(name: &str, taken: &mut BTreeSet<String>) -> String {
+    let clean: String = name
+        .chars()
+        .filter(|c| !"[]:*?/\\".contains(*c))
+        .take(31)
+        .collect();
+    let base = if clean.is_empty() {
+        "Sheet".to_string()
+    } else {
+        clean
+    };
+    let mut sheet = base.clone();
+    let mut n = 1;
+    while taken.contains(&sheet.to_lowercase()) {
+        n += 1;
+        let suffix = format!(" ({})", n);
+        let stem: String = base.chars().take(31 - suffix.len()).collect();
+        sheet = format!("{}{}", stem, suffix);
+    }
+    taken.insert(sheet.to_lowercase());
+    sheet
+}
+
+fn write_tables(sheet: &mut Worksheet, tables: &[Table], bold: &Format) -> Result<(), XlsxError> {
+    let mut row = 0;
+    for table in tables {
+        sheet.write_string_with_format(row, 0, &table.title, bold)?;
+        row += 1;
+        for (col, head) in table.head.iter().enumerate() {
+            sheet.write_string_with_format(row, col as u16, head, bold)?;
+        }
+        row += 1;
+        for (label, cells) in &table.rows {
+            sheet.write_string_with_format(row, 0, label, bold)?;
+            for (col, cell) in cells.iter().enumerate() {
+                sheet.write_string(row, col as u16 + 1, cell)?;
+            }
+            row += 1;
+        }
+        row += 1;
+    }
+    sheet.autofit();
+    Ok(())
+}
+
+pub fn write(
+    done: &[(Verb, Vec<Tva>)],
+    layout: Layout,
+    by_system: bool,
+    path: &str,
+) -> Result<(), XlsxError> {
+    let mut sheets: Vec<(String, Vec<Table>)> = Vec::new();
+    if by_system {
+        for system in SYSTEMS.iter() {
+            let tables: Vec<Table> = done
+                .iter()
+                .flat_map(|(vb, reqs)| {
+                    let reqs: Vec<Tva> = reqs
+                        .iter()
+                        .copied()
+                        .filter(|req| req.system() == *system)
+                        .collect();
+                    output::verb_tables(vb, &reqs, layout)
+                })
+                .collect();
+            if !tables.is_empty() {
+                sheets.push((system.to_string(), tables));
+            }
+        }
+    } else {
+        for (vb, reqs) in done {
+            let tables = output::verb_tables(vb, reqs, layout);
+            if let Some(first) = reqs.first() {
+                if !tables.is_empty() {
+                    sheets.push((output::name(vb, *first), tables));
+                }
+            }
+        }
+    }
+
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let mut taken = BTreeSet::new();
+    for (name, tables) in &sheets {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(sheet_name(name, &mut taken))?;
+        write_tables(sheet, tables, &bold)?;
+    }
+    // A workbook needs at least one sheet.
+    if sheets.is_empty() {
+        workbook.add_worksheet();
+    }
+    workbook.save(path)
+}
+
+// Kahoot's quiz template: its column headings on the 8th row, under room
+// for notes, and from the 9th a question per row, numbered in column A,
+// with four answers, the time limit and the number of the right answer.
+const KAHOOT_HEAD: [&str; 7] = [
+    "Question - max 120 characters",
+    "Answer 1 - max 75 characters",
+    "Answer 2 - max 75 characters",
+    "Answer 3 - max 75 characters",
+    "Answer 4 - max 75 characters",
+    "Time limit (sec) – 5, 10, 20, 30, 60, 90, 120, or 240 secs",
+    "Correct answer(s) - choose at least one",
+];
+
+// A workbook for Kahoot's spreadsheet import, for --format kahoot: each
+// cell a question asking for its form, the other answers the verb's other
+// forms.
+pub fn write_kahoot(
+    done: &[(Verb, Vec<Tva>)],
+    time_limit: u32,
+    path: &str,
+) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let sheet = workbook.add_worksheet();
+    sheet.write_string_with_format(0, 1, "Quiz template", &bold)?;
+    sheet.write_string(1, 1, "Generated by gkverb.")?;
+    for (col, head) in KAHOOT_HEAD.iter().enumerate() {
+        sheet.write_string_with_format(7, col as u16 + 1, *head, &bold)?;
+    }
+    for (i, choice) in output::choices(done, 4).iter().enumerate() {
+        let row = i as u32 + 8;
+        sheet.write_number(row, 0, (i + 1) as f64)?;
+        sheet.write_string(row, 1, &choice.question)?;
+        for (col, answer) in choice.answers.iter().enumerate() {
+            sheet.write_string(row, col as u16 + 2, answer)?;
+        }
+        sheet.write_number(row, 6, time_limit)?;
+        sheet.write_number(row, 7, (choice.correct + 1) as f64)?;
+    }
+    sheet.autofit();
+    workbook.save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greek_writer::conj_reqs;
+    use std::env;
+    use std::fs;
+
+    fn done() -> Vec<(Verb, Vec<Tva>)> {
+        let mut vb = Verb::builder()
+            .stem("pres:παυ")
+            .stem("aor:ἐπαυσ")
+            .build()
+            .unwrap();
+        let reqs = vec!["pai".parse().unwrap(), "aai".parse().unwrap()];
+        conj_reqs(&mut vb, &reqs).unwrap();
+        vec![(vb, reqs)]
+    }
+
+    #[test]
+    fn names_sheets_as_excel_allows() {
+        let mut taken = BTreeSet::new();
+        assert_eq!(sheet_name("παύω", &mut taken), "παύω");
+        assert_eq!(sheet_name("ΠΑΎΩ", &mut taken), "ΠΑΎΩ (2)");
+        assert_eq!(sheet_name("pres:παυ/[1]", &mut taken), "presπαυ1");
+        assert_eq!(sheet_name("*?", &mut taken), "Sheet");
+        let long = "α".repeat(40);
+        assert_eq!(sheet_name(&long, &mut taken).chars().count(), 31);
+        let again = sheet_name(&long, &mut taken);
+        assert_eq!(again.chars().count(), 31);
+        assert!(again.ends_with(" (2)"));
+    }
+
+    #[test]
+    fn saves_a_workbook_by_verb_or_by_system() {
+        for by_system in [false, true] {
+            let path =
+                env::temp_dir().join(format!("gkverb-{}-{}.xlsx", std::process::id(), by_system));
+            write(&done(), Layout::Paradigm, by_system, path.to_str().unwrap()).unwrap();
+            // An .xlsx file is a zip archive.
+            assert!(fs::read(&path).unwrap().starts_with(b"PK"));
+            fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn saves_a_kahoot_quiz() {
+        let path = env::temp_dir().join(format!("gkverb-{}-kahoot.xlsx", std::process::id()));
+        write_kahoot(&done(), 20, path.to_str().unwrap()).unwrap();
+        assert!(fs::read(&path).unwrap().starts_with(b"PK"));
+        fs::remove_file(&path).unwrap();
+    }
+}
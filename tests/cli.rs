@@ -0,0 +1,483 @@
+// The gkverb command run as a user would, format by format.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+// A scratch directory of the test's own, which is also where a config file
+// would be looked for, so that the user's does not apply.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gkverb-cli-{}-{}", std::process::id(), name));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run_in(dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greek-writer"))
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+// The command's stdout, once it has succeeded.
+fn stdout(name: &str, args: &[&str]) -> String {
+    let out = run_in(&scratch(name), args);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8(out.stdout).unwrap()
+}
+
+// The command's stderr, once it has failed.
+fn stderr(name: &str, args: &[&str]) -> String {
+    let out = run_in(&scratch(name), args);
+    assert!(!out.status.success());
+    String::from_utf8(out.stderr).unwrap()
+}
+
+const PAI: [&str; 4] = ["--stem", "pres:λυ", "--tva", "pai"];
+
+fn with<'a>(args: &[&'a str]) -> Vec<&'a str> {
+    let mut all = PAI.to_vec();
+    all.extend_from_slice(args);
+    all
+}
+
+#[test]
+fn prints_a_table() {
+    let out = stdout("table", &PAI);
+    assert!(out.starts_with("λυ: present active indicative (pai)\n"));
+    assert!(out.contains("3pl  λυουσι\n"));
+}
+
+#[test]
+fn writes_csv_to_stdout() {
+    let out = stdout("csv", &with(&["--outfile", "-"]));
+    assert_eq!(
+        out,
+        "1sg,2sg,3sg,1pl,2pl,3pl\nλυω,λυεις,λυει,λυομεν,λυετε,λυουσι\n"
+    );
+    let out = stdout(
+        "csv-long",
+        &with(&["--outfile", "-", "--csv-format", "long"]),
+    );
+    assert!(out.starts_with("lemma,stem,tense,voice,mood,person,number,form\n"));
+    assert!(out.contains(",λυ,present,active,indicative,3,plural,λυουσι\n"));
+}
+
+#[test]
+fn writes_csv_to_stdout_without_an_outfile() {
+    let dir = scratch("csv-default");
+    let out = run_in(&dir, &with(&["-c"]));
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "1sg,2sg,3sg,1pl,2pl,3pl\nλυω,λυεις,λυει,λυομεν,λυετε,λυουσι\n"
+    );
+    fs::write(dir.join("verbs.csv"), "stem\npres:λυ\n").unwrap();
+    let out = run_in(&dir, &["--infile", "verbs.csv", "--tva", "pai"]);
+    assert!(String::from_utf8(out.stdout)
+        .unwrap()
+        .contains("λυω,λυεις,λυει,λυομεν,λυετε,λυουσι\n"));
+    assert!(!dir.join("test-output.csv").exists());
+}
+
+#[test]
+fn writes_a_csv_file_only_when_asked_to_overwrite() {
+    let dir = scratch("csv-file");
+    let args = with(&["--outfile", "pai.csv", "--quiet"]);
+    let _ = fs::remove_file(dir.join("pai.csv"));
+    assert!(run_in(&dir, &args).status.success());
+    assert!(fs::read_to_string(dir.join("pai.csv"))
+        .unwrap()
+        .contains("λυουσι"));
+    let again = run_in(&dir, &args);
+    assert!(!again.status.success());
+    assert!(String::from_utf8_lossy(&again.stderr).contains("already exists"));
+}
+
+#[test]
+fn appends_to_a_csv_file_under_its_header() {
+    let dir = scratch("append");
+    let _ = fs::remove_file(dir.join("forms.csv"));
+    let args = [
+        "--tva",
+        "pai",
+        "--outfile",
+        "forms.csv",
+        "--quiet",
+        "--append",
+    ];
+    for stem in ["pres:λυ", "pres:παυ"] {
+        let out = run_in(&dir, &[&["--stem", stem][..], &args].concat());
+        assert!(out.status.success());
+    }
+    assert_eq!(
+        fs::read_to_string(dir.join("forms.csv")).unwrap(),
+        "1sg,2sg,3sg,1pl,2pl,3pl\n\
+         λυω,λυεις,λυει,λυομεν,λυετε,λυουσι\n\
+         παυω,παυεις,παυει,παυομεν,παυετε,παυουσι\n"
+    );
+    assert!(stderr("append-force", &with(&["--append", "--force"])).contains("cannot be used"));
+}
+
+#[test]
+fn writes_each_text_format() {
+    let plain = stdout("plain", &with(&["--format", "plain"]));
+    assert!(plain.contains("λυω, λυεις, λυει, λυομεν, λυετε, λυουσι"));
+    let tsv = stdout("tsv", &with(&["--format", "tsv"]));
+    assert!(tsv.contains("λυω\tλυεις\tλυει"));
+    let markdown = stdout("markdown", &with(&["--format", "markdown"]));
+    assert!(markdown.contains("| 3pl | λυουσι |"));
+    let html = stdout("html", &with(&["--format", "html"]));
+    assert!(html.contains("<td>λυουσι</td>"));
+    let latex = stdout("latex", &with(&["--format", "latex"]));
+    assert!(latex.contains("3pl & λυουσι \\\\"));
+    let tei = stdout("tei", &with(&["--format", "tei"]));
+    assert!(tei.contains("<orth>λυουσι</orth>"));
+    let gift = stdout("gift", &with(&["--format", "gift"]));
+    assert!(gift.contains("{=λυουσι}"));
+    let conllu = stdout("conllu", &with(&["--format", "conllu", "--nu", "both"]));
+    assert!(conllu.contains("# text = λυουσιν\n"));
+    assert!(!conllu.contains("(ν)"));
+}
+
+#[test]
+fn writes_json_and_json_lines() {
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout("json", &with(&["--format", "json"]))).unwrap();
+    assert_eq!(json[0]["tva"], "pai");
+    assert_eq!(json[0]["forms"][5]["form"], "λυουσι");
+    let jsonl = stdout("jsonl", &with(&["--format", "jsonl"]));
+    assert_eq!(jsonl.lines().count(), 6);
+    let first: serde_json::Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+    assert_eq!(first["form"], "λυω");
+}
+
+#[test]
+fn puts_nu_variants_on_rows() {
+    let out = stdout("nu-rows", &with(&["--nu", "rows"]));
+    assert!(out.contains("3pl  λυουσι\n3pl  λυουσιν\n"));
+    assert!(!out.contains(" / "));
+}
+
+#[test]
+fn lays_tables_out_by_person_or_by_tense() {
+    let args = ["--stem", "pres:λυ", "--tva", "pai,iai", "--layout"];
+    let by_person = stdout("layout-person", &[&args[..], &["by-person"]].concat());
+    assert!(by_person.starts_with("λυ\n     pai     iai\n1sg  λυω     ἐλυον\n"));
+    assert!(by_person.ends_with("3pl  λυουσι  ἐλυον\n"));
+    let by_tense = stdout("layout-tense", &[&args[..], &["by-tense"]].concat());
+    assert!(by_tense.contains("\npai  λυω    λυεις  λυει  λυομεν   λυετε   λυουσι\n"));
+    assert!(by_tense.ends_with("\niai  ἐλυον  ἐλυες  ἐλυε  ἐλυομεν  ἐλυετε  ἐλυον\n"));
+    assert!(stderr("layout-bad", &[&args[..], &["sideways"]].concat()).contains("by-person"));
+}
+
+#[test]
+fn puts_a_tenses_voices_side_by_side() {
+    let out = stdout(
+        "layout-voice",
+        &[
+            "--stem",
+            "pres:λυ",
+            "--tva",
+            "pai,ppi",
+            "--layout",
+            "by-voice",
+        ],
+    );
+    assert!(out.starts_with("λυ: present indicative\n     active  middle/passive\n"));
+    assert!(out.contains("\n2sg  λυεις   λυῃ\n"));
+}
+
+#[test]
+fn labels_each_row_of_a_csv_by_person() {
+    let out = stdout(
+        "by-person",
+        &[
+            "--lemma",
+            "λύω",
+            "--tva",
+            "pai,iai",
+            "--layout",
+            "by-person",
+            "--nu",
+            "rows",
+            "-c",
+        ],
+    );
+    assert!(out.starts_with("verb,person,pai,iai\nλύω,1sg,λυω,ἐλυον\n"));
+    assert!(out.ends_with("λύω,3pl,λυουσι,ἐλυον\nλύω,3pl,λυουσιν,ἐλυον\n"));
+    assert!(!out.contains(" / "));
+    let err = stderr(
+        "by-person-mixed",
+        &[
+            "--stem",
+            "pres:λυ",
+            "--stem",
+            "aor:ἐλυσ",
+            "--all",
+            "--layout",
+            "by-person",
+            "-c",
+        ],
+    );
+    assert!(err.contains("by person the columns are the first verb's tvas"));
+}
+
+#[test]
+fn writes_a_worksheets_key_beside_it() {
+    let dir = scratch("worksheet");
+    let args = [
+        "worksheet",
+        "--stem",
+        "pres:λυ",
+        "--tva",
+        "pai",
+        "--format",
+        "html",
+        "--pattern",
+        "1sg",
+        "--outfile",
+        "hw.html",
+    ];
+    let out = run_in(&dir, &args);
+    assert!(out.status.success());
+    let sheet = fs::read_to_string(dir.join("hw.html")).unwrap();
+    assert!(!sheet.contains("λυω"));
+    let key = fs::read_to_string(dir.join("hw-key.html")).unwrap();
+    assert!(key.contains("<mark>λυω</mark>"));
+}
+
+#[test]
+fn scans_a_text_for_verb_forms() {
+    let dir = scratch("scan");
+    fs::write(
+        dir.join("text.txt"),
+        "ὁ δὲ ἔλυσε τοὺς ἵππους.\nκαὶ ἐπαύσαμεν.\n",
+    )
+    .unwrap();
+    let out = run_in(&dir, &["scan", "text.txt"]);
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "1: ἔλυσε: 3rd sg. aorist active indicative of λύω\n\
+         2: ἐπαύσαμεν: 1st pl. aorist active indicative of παύω\n"
+    );
+    assert!(String::from_utf8(out.stderr)
+        .unwrap()
+        .contains("2 of 7 words"));
+}
+
+#[test]
+fn shows_only_the_persons_asked_for() {
+    let out = stdout("persons", &with(&["--persons", "1sg,3pl"]));
+    assert_eq!(
+        out,
+        "λυ: present active indicative (pai)\n     form\n1sg  λυω\n3pl  λυουσι\n"
+    );
+    let json = stdout(
+        "persons-json",
+        &with(&["--persons", "2sg", "--format", "json"]),
+    );
+    assert_eq!(json.matches("\"form\":").count(), 1);
+    assert!(json.contains("\"form\": \"λυεις\""));
+    assert!(stderr("persons-bad", &with(&["--persons", "4sg"])).contains("possible values"));
+}
+
+#[test]
+fn hyphenates_augment_stem_and_ending() {
+    let out = stdout(
+        "segmented",
+        &[
+            "--verb",
+            "pres:λυ,aor:ἐλυσ",
+            "--tva",
+            "iai,aai",
+            "--segmented",
+        ],
+    );
+    assert!(out.contains("\n1sg  ἐ-λυ-ον\n"));
+    assert!(out.contains("\n2pl  ἐ-λυσ-ατε\n"));
+    let csv = stdout("segmented-csv", &with(&["--segmented", "-c"]));
+    assert!(csv.ends_with("\nλυ-ω,λυ-εις,λυ-ει,λυ-ομεν,λυ-ετε,λυ-ουσι\n"));
+    let json = stdout(
+        "segmented-json",
+        &with(&["--segmented", "--format", "json"]),
+    );
+    assert!(json.contains("\"form\": \"λυ-ομεν\""));
+}
+
+#[test]
+fn strips_the_accents_but_not_the_breathings() {
+    let args = ["--lemma", "ἀκούω", "--tva", "aai", "--strip-accents"];
+    let out = stdout("strip-accents", &args);
+    assert!(out.starts_with("ἀκουω: aorist active indicative (aai)\n"));
+    assert!(out.contains("\n1sg  ἠκουσα\n"));
+    let json = stdout(
+        "strip-accents-json",
+        &[&args[..], &["--format", "json"]].concat(),
+    );
+    assert!(json.contains("\"lemma\": \"ἀκουω\""));
+}
+
+#[test]
+fn conjugates_a_lemma_from_the_lexicon() {
+    let out = stdout("lemma", &["--lemma", "τιμῶ", "--tva", "pai"]);
+    assert!(out.starts_with("τιμάω: present active indicative (pai)\n"));
+    let err = stderr("ambiguous", &["--lemma", "ξυζῶ", "--tva", "pai"]);
+    assert!(err.contains("give it uncontracted as ξυζάω, ξυζέω or ξυζόω"));
+}
+
+#[test]
+fn conjugates_a_list_of_lemmas() {
+    let dir = scratch("lemmas");
+    fs::write(dir.join("verbs.txt"), "# week 3\nλύω\n\nχορεύω\nπαύω\n").unwrap();
+    let out = run_in(&dir, &["--lemmas", "verbs.txt", "--tva", "pai", "-c"]);
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "1sg,2sg,3sg,1pl,2pl,3pl\n\
+         λυω,λυεις,λυει,λυομεν,λυετε,λυουσι\n\
+         παυω,παυεις,παυει,παυομεν,παυετε,παυουσι\n"
+    );
+    assert_eq!(
+        String::from_utf8(out.stderr).unwrap(),
+        "verbs.txt: 1 of 3 lemmas not in the lexicon, skipped: χορεύω\n"
+    );
+}
+
+#[test]
+fn reads_a_user_lexicon() {
+    let dir = scratch("lexicon");
+    fs::write(
+        dir.join("verbs.toml"),
+        "[[verb]]\nlemma = \"λύω\"\ngloss = \"set free\"\n\
+         [verb.overrides]\n\"pai.3pl\" = \"λυουσιν\"\n",
+    )
+    .unwrap();
+    let out = run_in(
+        &dir,
+        &[
+            "--lexicon",
+            "verbs.toml",
+            "--lemma",
+            "λύω",
+            "--tva",
+            "pai",
+            "--format",
+            "jsonl",
+        ],
+    );
+    let out = String::from_utf8(out.stdout).unwrap();
+    assert!(out.contains("\"gloss\":\"set free\""));
+    assert!(out.contains("\"form\":\"λυουσιν\""));
+}
+
+#[test]
+fn takes_defaults_from_a_config_file() {
+    let dir = scratch("config");
+    fs::write(
+        dir.join("config.toml"),
+        "nu = \"show\"\nformat = \"plain\"\n",
+    )
+    .unwrap();
+    let out = run_in(&dir, &with(&["--config", "config.toml"]));
+    assert!(String::from_utf8(out.stdout).unwrap().contains("λυουσιν\n"));
+    // The command line wins over the file.
+    let out = run_in(&dir, &with(&["--config", "config.toml", "--nu", "omit"]));
+    assert!(String::from_utf8(out.stdout).unwrap().ends_with("λυουσι\n"));
+}
+
+#[test]
+fn rejects_a_bad_config_file() {
+    let dir = scratch("bad-config");
+    fs::write(dir.join("unknown.toml"), "nu_policy = \"rows\"\n").unwrap();
+    fs::write(dir.join("value.toml"), "layout = \"by-row\"\n").unwrap();
+    let out = run_in(&dir, &with(&["--config", "unknown.toml"]));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown setting nu_policy"));
+    let out = run_in(&dir, &with(&["--config", "value.toml"]));
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("layout must be one of by-person, by-tense, by-voice, not by-row"));
+}
+
+#[test]
+fn rejects_an_unknown_profile() {
+    let err = stderr("profile", &with(&["--profile", "wheelock"]));
+    assert!(err.contains("'wheelock' isn't a valid value"));
+    let jact = stdout("jact", &with(&["--profile", "jact"]));
+    assert_eq!(jact, stdout("athenaze", &with(&["--profile", "athenaze"])));
+}
+
+#[test]
+fn reports_a_bad_stem() {
+    let err = stderr("stem", &["--stem", "aor:ἐ", "--tva", "aai"]);
+    assert!(err.contains("aor:ἐ is only an augment"));
+}
+
+#[test]
+fn checks_an_infile_without_writing_anything() {
+    let dir = scratch("check");
+    fs::write(dir.join("good.csv"), "stem\npres:λυ\npres:παυ\n").unwrap();
+    fs::write(
+        dir.join("bad.csv"),
+        "stem,tva\npres:λυ,pai\nxyz:παυ,pai\npres:λυ,aai\n",
+    )
+    .unwrap();
+    let out = run_in(&dir, &["--infile", "good.csv", "--tva", "pai", "--check"]);
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "good.csv: 2 rows, no problems found\n"
+    );
+    let out = run_in(&dir, &["--infile", "bad.csv", "--check"]);
+    assert!(!out.status.success());
+    let err = String::from_utf8(out.stderr).unwrap();
+    assert!(err.contains("bad.csv: row 3: unknown tense system in xyz:παυ"));
+    assert!(err.contains("bad.csv: row 4: pres:λυ has no aor stem for aai"));
+    assert!(err.ends_with("2 problems found in 3 rows\n"));
+}
+
+#[test]
+fn fails_on_a_bad_infile_row() {
+    let dir = scratch("bad-row");
+    fs::write(dir.join("verbs.csv"), "stem\npres:λυ\naor:ἐ\npres:παυ\n").unwrap();
+    let out = run_in(&dir, &["--infile", "verbs.csv", "--tva", "pai"]);
+    assert!(!out.status.success());
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("verbs.csv: row 3: aor:ἐ is only an augment")
+    );
+    let out = run_in(&dir, &["--infile", "verbs.csv", "--tva", "pai", "--check"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("1 problem found in 3 rows"));
+}
+
+#[test]
+fn stops_quietly_when_its_reader_does() {
+    for args in [
+        &["--lemma", "λύω", "--all"][..],
+        &["--lemma", "λύω", "--all", "-c"],
+    ] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_greek-writer"))
+            .args(args)
+            .current_dir(scratch("pipe"))
+            .env("XDG_CONFIG_HOME", scratch("pipe"))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        drop(child.stdout.take());
+        let out = child.wait_with_output().unwrap();
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "");
+    }
+}
+
+#[test]
+fn passes_its_selftest() {
+    assert!(stdout("selftest", &["selftest"]).contains("all as expected"));
+}